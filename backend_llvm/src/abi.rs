@@ -0,0 +1,446 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    AddressSpace,
+    attributes::{Attribute, AttributeLoc},
+    targets::TargetData,
+    types::{AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType},
+    values::{BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue},
+};
+use lexer::types::Datatype;
+
+use crate::{CodeGen, CodeGenError};
+
+/// How C passes aggregates on the target. LLVM leaves this to the frontend, so it
+/// is done here the way clang does it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Convention {
+    /// x86-64 on Linux and macOS
+    SysV,
+    /// x86-64 on Windows
+    Win64,
+    Aarch64,
+    /// Aggregates are passed and returned in memory, like on 32-bit x86 and
+    /// WebAssembly
+    Memory,
+}
+
+/// The C calling convention of the target, created when an `extern` function
+/// first takes or returns an aggregate
+pub(crate) struct CAbi {
+    convention: Convention,
+    target_data: TargetData,
+}
+
+/// How a value crosses the boundary of an `extern` function
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PassMode<'ctx> {
+    /// As is, like numbers, pointers and vectors
+    Direct,
+    /// In registers, as a value of another type holding the same bytes
+    Cast(BasicTypeEnum<'ctx>),
+    /// As a pointer to a copy, which LLVM makes on the stack when `byval` is set and
+    /// the caller makes otherwise. Returned values are written to memory that the
+    /// caller passes as a hidden first argument.
+    Indirect { byval: bool },
+}
+
+/// The signature of an `extern` function in the C ABI
+pub(crate) struct CSignature<'ctx> {
+    pub fn_type: FunctionType<'ctx>,
+    params: Vec<(BasicTypeEnum<'ctx>, PassMode<'ctx>)>,
+    ret: Option<(BasicTypeEnum<'ctx>, PassMode<'ctx>)>,
+}
+
+impl<'ctx> CSignature<'ctx> {
+    /// The types of the arguments before they are lowered
+    pub(crate) fn param_types(&self) -> Vec<BasicTypeEnum<'ctx>> {
+        self.params.iter().map(|(ty, _)| *ty).collect()
+    }
+
+    /// Whether the return value is written to a pointer passed as the first argument
+    pub(crate) fn has_sret(&self) -> bool {
+        matches!(self.ret, Some((_, PassMode::Indirect { .. })))
+    }
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    fn c_abi(&self) -> Result<&CAbi, CodeGenError> {
+        if let Some(abi) = self.c_abi.get() {
+            return Ok(abi);
+        }
+        let machine = self.target_machine()?;
+        let triple = machine.get_triple();
+        let triple = triple.as_str().to_string_lossy();
+        let convention = if triple.starts_with("x86_64") && triple.contains("windows") {
+            Convention::Win64
+        } else if triple.starts_with("x86_64") {
+            Convention::SysV
+        } else if triple.starts_with("aarch64") || triple.starts_with("arm64") {
+            Convention::Aarch64
+        } else {
+            Convention::Memory
+        };
+        Ok(self.c_abi.get_or_init(|| CAbi {
+            convention,
+            target_data: machine.get_target_data(),
+        }))
+    }
+
+    /// Lowers the signature of an `extern` function to the C ABI of the target
+    pub(crate) fn c_signature(
+        &self,
+        params: &[Datatype],
+        return_type: Option<&Datatype>,
+    ) -> Result<CSignature<'ctx>, CodeGenError> {
+        let ret = match return_type {
+            Some(dt) => {
+                let ty = self.parser_to_llvm_dt(dt)?;
+                Some((ty, self.c_pass_mode(ty, true)?))
+            }
+            None => None,
+        };
+        let params = params
+            .iter()
+            .map(|dt| {
+                let ty = self.parser_to_llvm_dt(dt)?;
+                Ok((ty, self.c_pass_mode(ty, false)?))
+            })
+            .collect::<Result<Vec<_>, CodeGenError>>()?;
+
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let mut lowered: Vec<BasicMetadataTypeEnum> = vec![];
+        if let Some((_, PassMode::Indirect { .. })) = ret {
+            lowered.push(ptr_type.into());
+        }
+        for (ty, mode) in params.iter() {
+            lowered.push(match mode {
+                PassMode::Direct => (*ty).into(),
+                PassMode::Cast(cast) => (*cast).into(),
+                PassMode::Indirect { .. } => ptr_type.into(),
+            });
+        }
+        let fn_type = match ret {
+            Some((ty, PassMode::Direct)) => ty.fn_type(&lowered, false),
+            Some((_, PassMode::Cast(cast))) => cast.fn_type(&lowered, false),
+            Some((_, PassMode::Indirect { .. })) | None => {
+                self.context.void_type().fn_type(&lowered, false)
+            }
+        };
+        Ok(CSignature {
+            fn_type,
+            params,
+            ret,
+        })
+    }
+
+    /// The `sret`, `byval` and `align` attributes of the pointers to aggregates, which
+    /// are set both on the declaration and on each call
+    pub(crate) fn c_abi_attributes(
+        &self,
+        signature: &CSignature<'ctx>,
+    ) -> Result<Vec<(AttributeLoc, Attribute)>, CodeGenError> {
+        let mut attributes = vec![];
+        let mut pointer = |index: u32, kind: &str, ty: BasicTypeEnum<'ctx>| {
+            let abi = self.c_abi()?;
+            let kind = Attribute::get_named_enum_kind_id(kind);
+            let align = Attribute::get_named_enum_kind_id("align");
+            let loc = AttributeLoc::Param(index);
+            attributes.push((
+                loc,
+                self.context
+                    .create_type_attribute(kind, ty.as_any_type_enum()),
+            ));
+            attributes.push((
+                loc,
+                self.context
+                    .create_enum_attribute(align, abi.target_data.get_abi_alignment(&ty) as u64),
+            ));
+            Ok::<_, CodeGenError>(())
+        };
+
+        let hidden = signature.has_sret() as u32;
+        if let Some((ty, PassMode::Indirect { .. })) = signature.ret {
+            pointer(0, "sret", ty)?;
+        }
+        for (index, (ty, mode)) in signature.params.iter().enumerate() {
+            if let PassMode::Indirect { byval: true } = mode {
+                pointer(index as u32 + hidden, "byval", *ty)?;
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Calls an `extern` function, passing the arguments and returning the value the
+    /// way C does
+    pub(crate) fn build_c_call(
+        &self,
+        func: FunctionValue<'ctx>,
+        signature: &CSignature<'ctx>,
+        args: &[BasicValueEnum<'ctx>],
+    ) -> Result<Option<BasicValueEnum<'ctx>>, CodeGenError> {
+        let mut lowered: Vec<BasicMetadataValueEnum> = vec![];
+        let ret_ptr = match signature.ret {
+            Some((ty, PassMode::Indirect { .. })) => {
+                let ptr = self
+                    .builder
+                    .build_alloca(ty, "")
+                    .map_err(CodeGenError::from_llvm_err)?;
+                lowered.push(ptr.into());
+                Some(ptr)
+            }
+            _ => None,
+        };
+        for (arg, (ty, mode)) in args.iter().zip(&signature.params) {
+            lowered.push(match mode {
+                PassMode::Direct => (*arg).into(),
+                PassMode::Cast(cast) => self.reinterpret(*arg, *cast)?.into(),
+                PassMode::Indirect { .. } => {
+                    let ptr = self
+                        .builder
+                        .build_alloca(*ty, "")
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    self.builder
+                        .build_store(ptr, *arg)
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    ptr.into()
+                }
+            });
+        }
+
+        let call = self
+            .builder
+            .build_call(func, &lowered, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        for (loc, attribute) in self.c_abi_attributes(signature)? {
+            call.add_attribute(loc, attribute);
+        }
+        let value = call.try_as_basic_value().left();
+        match (signature.ret, value, ret_ptr) {
+            (Some((ty, PassMode::Cast(_))), Some(value), _) => {
+                self.reinterpret(value, ty).map(Some)
+            }
+            (Some((ty, PassMode::Indirect { .. })), _, Some(ptr)) => self
+                .builder
+                .build_load(ty, ptr, "")
+                .map(Some)
+                .map_err(CodeGenError::from_llvm_err),
+            _ => Ok(value),
+        }
+    }
+
+    /// How a value of the type is passed to or returned from C
+    fn c_pass_mode(
+        &self,
+        ty: BasicTypeEnum<'ctx>,
+        is_return: bool,
+    ) -> Result<PassMode<'ctx>, CodeGenError> {
+        match ty {
+            BasicTypeEnum::StructType(_) => {}
+            BasicTypeEnum::ArrayType(_) if is_return => {
+                return Err(CodeGenError::new(
+                    ErrorCode::Unsupported,
+                    "Extern functions cannot return arrays, as C functions can't",
+                ));
+            }
+            // C takes arrays as a pointer to their first element
+            BasicTypeEnum::ArrayType(_) => return Ok(PassMode::Indirect { byval: false }),
+            _ => return Ok(PassMode::Direct),
+        }
+
+        let abi = self.c_abi()?;
+        let size = abi.target_data.get_abi_size(&ty);
+        let mut scalars = vec![];
+        abi.scalars(ty, 0, &mut scalars);
+        let (cast, byval) = match abi.convention {
+            Convention::SysV => (self.sysv_cast(&scalars, size), true),
+            Convention::Win64 => (
+                matches!(size, 1 | 2 | 4 | 8)
+                    .then(|| self.context.custom_width_int_type(size as u32 * 8).into()),
+                false,
+            ),
+            Convention::Aarch64 => (self.aarch64_cast(&scalars, size), false),
+            Convention::Memory => (None, true),
+        };
+        Ok(match cast {
+            Some(cast) => PassMode::Cast(cast),
+            None => PassMode::Indirect {
+                byval: byval && !is_return,
+            },
+        })
+    }
+
+    /// Aggregates of up to 16 bytes are passed in one or two registers. Each eightbyte
+    /// goes in a float register if it only holds floats, and in an integer one
+    /// otherwise.
+    fn sysv_cast(
+        &self,
+        scalars: &[(u64, BasicTypeEnum<'ctx>)],
+        size: u64,
+    ) -> Option<BasicTypeEnum<'ctx>> {
+        if size == 0 || size > 16 {
+            return None;
+        }
+        let eightbytes = (0..size.div_ceil(8))
+            .map(|eightbyte| {
+                let fields = scalars
+                    .iter()
+                    .filter(|(offset, _)| offset / 8 == eightbyte)
+                    .map(|(_, ty)| *ty)
+                    .collect::<Vec<_>>();
+                match fields.as_slice() {
+                    [BasicTypeEnum::FloatType(ft)] => (*ft).into(),
+                    [BasicTypeEnum::FloatType(a), BasicTypeEnum::FloatType(b)] if a == b => {
+                        a.vec_type(2).into()
+                    }
+                    _ => {
+                        let bytes = (size - eightbyte * 8).min(8);
+                        self.context.custom_width_int_type(bytes as u32 * 8).into()
+                    }
+                }
+            })
+            .collect::<Vec<BasicTypeEnum>>();
+        match eightbytes.as_slice() {
+            [ty] => Some(*ty),
+            _ => Some(self.context.struct_type(&eightbytes, false).into()),
+        }
+    }
+
+    /// Aggregates of up to four floats of the same type are passed in float
+    /// registers, and other aggregates of up to 16 bytes in one or two integer
+    /// registers
+    fn aarch64_cast(
+        &self,
+        scalars: &[(u64, BasicTypeEnum<'ctx>)],
+        size: u64,
+    ) -> Option<BasicTypeEnum<'ctx>> {
+        if let Some((_, first)) = scalars.first()
+            && first.is_float_type()
+            && scalars.len() <= 4
+            && scalars.iter().all(|(_, ty)| ty == first)
+        {
+            return Some(first.array_type(scalars.len() as u32).into());
+        }
+        let i64_type = self.context.i64_type();
+        match size {
+            1..=8 => Some(i64_type.into()),
+            9..=16 => Some(i64_type.array_type(2).into()),
+            _ => None,
+        }
+    }
+
+    /// Reinterprets the bytes of a value as another type, through the stack
+    fn reinterpret(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        to: BasicTypeEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let abi = self.c_abi()?;
+        let from = value.get_type();
+        // Registers can hold more bytes than the value, so the slot fits the larger type
+        let slot = if abi.target_data.get_abi_size(&from) >= abi.target_data.get_abi_size(&to) {
+            from
+        } else {
+            to
+        };
+        let ptr = self
+            .builder
+            .build_alloca(slot, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        let align = abi
+            .target_data
+            .get_abi_alignment(&from)
+            .max(abi.target_data.get_abi_alignment(&to));
+        if let Some(alloca) = ptr.as_instruction_value() {
+            _ = alloca.set_alignment(align);
+        }
+        self.builder
+            .build_store(ptr, value)
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_load(to, ptr, "")
+            .map_err(CodeGenError::from_llvm_err)
+    }
+}
+
+impl CAbi {
+    /// Flattens an aggregate into its numbers and pointers, with their offsets in bytes
+    fn scalars<'ctx>(
+        &self,
+        ty: BasicTypeEnum<'ctx>,
+        offset: u64,
+        scalars: &mut Vec<(u64, BasicTypeEnum<'ctx>)>,
+    ) {
+        match ty {
+            BasicTypeEnum::StructType(st) => {
+                for (index, field) in st.get_field_types().into_iter().enumerate() {
+                    let field_offset = self
+                        .target_data
+                        .offset_of_element(&st, index as u32)
+                        .unwrap_or_default();
+                    self.scalars(field, offset + field_offset, scalars);
+                }
+            }
+            BasicTypeEnum::ArrayType(at) => {
+                let element = at.get_element_type();
+                let stride = self.target_data.get_abi_size(&element);
+                for index in 0..at.len() as u64 {
+                    self.scalars(element, offset + index * stride, scalars);
+                }
+            }
+            BasicTypeEnum::VectorType(vt) => {
+                let element = vt.get_element_type();
+                let stride = self.target_data.get_abi_size(&element);
+                for index in 0..vt.get_size() as u64 {
+                    self.scalars(element, offset + index * stride, scalars);
+                }
+            }
+            ty => scalars.push((offset, ty)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+
+    use super::*;
+    use crate::TargetOptions;
+
+    fn extern_ir(triple: &str) -> String {
+        let data = "struct Pair { a i32, b f32 }
+
+struct Vec3 { x f32, y f32, z f32 }
+
+struct Big { a i64, b i64, c i64 }
+
+extern func pair(p Pair) Pair
+
+extern func vec3(v Vec3) Vec3
+
+extern func big(b Big) Big";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_target(TargetOptions {
+            triple: Some(triple.to_string()),
+            ..Default::default()
+        });
+        codegen.codegen().unwrap();
+        codegen.ir_as_string()
+    }
+
+    #[test]
+    fn test_sysv_extern_signatures() {
+        let ir = extern_ir("x86_64-unknown-linux-gnu");
+        assert!(ir.contains("declare i64 @pair(i64)"));
+        assert!(ir.contains("declare { <2 x float>, float } @vec3({ <2 x float>, float })"));
+        assert!(ir.contains("declare void @big(ptr sret(%Big) align 8, ptr byval(%Big) align 8)"));
+    }
+
+    #[test]
+    fn test_aarch64_extern_signatures() {
+        let ir = extern_ir("aarch64-unknown-linux-gnu");
+        assert!(ir.contains("declare i64 @pair(i64)"));
+        assert!(ir.contains("declare [3 x float] @vec3([3 x float])"));
+        assert!(ir.contains("declare void @big(ptr sret(%Big) align 8, ptr)"));
+    }
+}
@@ -1,7 +1,8 @@
-use inkwell::{basic_block::BasicBlock, values::FunctionValue};
-use parser::nodes::{ASTNodes, Block};
+use diagnostics::ErrorCode;
+use inkwell::{basic_block::BasicBlock, types::BasicTypeEnum, values::FunctionValue};
+use parser::nodes::{ASTNodes, Block, Param};
 
-use crate::{CodeGen, CodeGenError};
+use crate::{CodeGen, CodeGenError, stmt::resolved_id};
 
 impl<'ctx> CodeGen<'ctx> {
     pub(crate) fn codegen_block(
@@ -14,55 +15,153 @@ impl<'ctx> CodeGen<'ctx> {
         self.builder.position_at_end(basic_block);
 
         for node in &block.body {
-            match node {
-                ASTNodes::LetStmt(let_stmt) => {
-                    self.impl_let_stmt(built_func, let_stmt)?;
-                }
-                ASTNodes::Conditional(cond) => {
-                    self.impl_if_stmt(built_func, cond, next_block)?;
-                }
-                ASTNodes::Loop(loop_stmt) => {
-                    self.impl_loop_stmt(built_func, loop_stmt)?;
-                }
-                ASTNodes::ForLoop(loop_stmt) => {
-                    self.impl_for_loop_stmt(built_func, loop_stmt)?;
-                }
-                ASTNodes::Return(ret) => {
-                    self.impl_function_return(built_func, ret)?;
-                }
-                ASTNodes::AssignStmt(stmt) => {
-                    self.impl_assign_stmt(built_func, stmt)?;
-                }
-                ASTNodes::FunctionCall(call) => {
-                    self.impl_function_call(built_func, call)?;
-                }
-                ASTNodes::ImportCall(call) => {
-                    self.impl_import_call(built_func, call)?;
-                }
-                ASTNodes::Break => {
-                    self.codegen_break_stmt(built_func, next_block)?;
-                }
-                _ => todo!(),
-            };
+            // Code after a `return`, `break` or panic never runs, and can't follow the
+            // terminator of the block
+            let current = self.builder.get_insert_block();
+            if current.and_then(|block| block.get_terminator()).is_some() {
+                break;
+            }
+            if let Some(line) = node.line() {
+                self.stmt_line.set(line);
+            }
+            self.debug_location(node.line(), built_func);
+            self.codegen_stmt(node, built_func, next_block)
+                .map_err(|err| err.at_node(node))?;
         }
         Ok(basic_block)
     }
 
+    fn codegen_stmt(
+        &self,
+        node: &ASTNodes,
+        built_func: FunctionValue<'ctx>,
+        next_block: Option<BasicBlock<'ctx>>,
+    ) -> Result<(), CodeGenError> {
+        match node {
+            ASTNodes::LetStmt(let_stmt) => {
+                self.impl_let_stmt(built_func, let_stmt)?;
+            }
+            ASTNodes::Conditional(cond) => {
+                self.impl_if_stmt(built_func, cond, next_block)?;
+            }
+            ASTNodes::Loop(loop_stmt) => {
+                self.impl_loop_stmt(built_func, loop_stmt)?;
+            }
+            ASTNodes::ForLoop(_) => {
+                return Err(CodeGenError::new(
+                    ErrorCode::Internal,
+                    "`loop range` should have been lowered by the desugaring pass",
+                ));
+            }
+            ASTNodes::Return(ret) => {
+                self.impl_function_return(built_func, ret)?;
+            }
+            ASTNodes::AssignStmt(stmt) => {
+                self.impl_assign_stmt(built_func, stmt)?;
+            }
+            ASTNodes::FunctionCall(call) => {
+                let ret_val = self.impl_function_call(built_func, call)?;
+                self.check_result_used(ret_val, &call.name)?;
+            }
+            ASTNodes::ImportCall(call) => {
+                let ret_val = self.impl_import_call(built_func, call)?;
+                self.check_result_used(ret_val, &call.path.join("::"))?;
+            }
+            ASTNodes::Try(r#try) => {
+                self.impl_try(built_func, r#try)?;
+            }
+            ASTNodes::Break => {
+                self.codegen_break_stmt(built_func, next_block)?;
+            }
+            node => {
+                return Err(CodeGenError::unsupported(&format!(
+                    "Expected a statement, found {}",
+                    node.kind()
+                )));
+            }
+        };
+        Ok(())
+    }
+
     pub(crate) fn codegen_function_block(
         &self,
         block: &Block,
         built_func: FunctionValue<'ctx>,
+        params: &[Param],
+        line: usize,
     ) -> Result<(), CodeGenError> {
         let basic_block = self.context.append_basic_block(built_func, "entry");
+        self.builder.position_at_end(basic_block);
+        self.impl_params(built_func, params, line)?;
         self.codegen_block(block, built_func, basic_block, None)?;
         self.var_ptrs.clear();
 
-        if basic_block.get_terminator().is_none() {
+        // The body may have ended in a different block than it started in
+        let last_block = self
+            .builder
+            .get_insert_block()
+            .ok_or(CodeGenError::internal(
+                "The builder isn't positioned in a block",
+            ))?;
+        if last_block.get_terminator().is_none() {
             if built_func.get_type().get_return_type().is_none() {
-                self.builder.build_return(None).unwrap();
+                self.builder
+                    .build_return(None)
+                    .map_err(CodeGenError::from_llvm_err)?;
+            } else if last_block != basic_block && last_block.get_first_use().is_none() {
+                // Nothing branches to the block, like the end of an `if` whose
+                // branches all return
+                self.builder
+                    .build_unreachable()
+                    .map_err(CodeGenError::from_llvm_err)?;
             } else {
-                return Err(CodeGenError::new("Missing return statement"));
+                return Err(CodeGenError::new(
+                    ErrorCode::MissingReturn,
+                    "Missing return statement",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies mutable parameters to the stack, so that they can be assigned to, and
+    /// aggregates, so that their fields and elements can be accessed. With debug info
+    /// all of them are copied, so that the debugger can find them.
+    fn impl_params(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        params: &[Param],
+        line: usize,
+    ) -> Result<(), CodeGenError> {
+        for (index, (param, value)) in params.iter().zip(built_func.get_param_iter()).enumerate() {
+            let aggregate = matches!(
+                value.get_type(),
+                BasicTypeEnum::ArrayType(_)
+                    | BasicTypeEnum::StructType(_)
+                    | BasicTypeEnum::VectorType(_)
+            );
+            if !param.mutable && !aggregate && !self.debug_info {
+                continue;
+            }
+            let ptr = self
+                .builder
+                .build_alloca(value.get_type(), &param.name)
+                .map_err(CodeGenError::from_llvm_err)?;
+            self.builder
+                .build_store(ptr, value)
+                .map_err(CodeGenError::from_llvm_err)?;
+            let arg_no = Some(index as u32 + 1);
+            self.debug_variable(&param.name, &param.datatype, ptr, line, arg_no, built_func)?;
+            if !param.mutable && !aggregate {
+                continue;
             }
+            self.var_ptrs.insert(
+                resolved_id(param.id, &param.name)?,
+                ptr,
+                value.get_type(),
+                param.datatype.is_signed(),
+                param.mutable,
+            );
         }
         Ok(())
     }
@@ -1,3 +1,4 @@
+use diagnostics::ErrorCode;
 use inkwell::{basic_block::BasicBlock, values::FunctionValue};
 use parser::nodes::{self, Conditional};
 
@@ -7,41 +8,76 @@ impl<'ctx> CodeGen<'ctx> {
     pub(crate) fn impl_if_stmt(
         &self,
         built_func: FunctionValue<'ctx>,
-        mut stmt: &nodes::Conditional,
+        stmt: &nodes::Conditional,
         next_block: Option<BasicBlock<'ctx>>,
     ) -> Result<(), CodeGenError> {
-        while let Conditional::If {
+        let Conditional::If {
             condition,
             body,
             else_body,
+            ..
         } = stmt
-        {
-            let then_block = self.context.append_basic_block(built_func, "then");
-            let then_cond =
-                self.impl_expr(&condition, built_func, self.context.bool_type().into())?;
-            let else_block = self.context.append_basic_block(built_func, "else");
+        else {
+            return Err(CodeGenError::new(
+                ErrorCode::Internal,
+                "Expected an `if` before an `else`",
+            ));
+        };
+        let then_block = self.context.append_basic_block(built_func, "then");
+        let then_cond = self.impl_expr(condition, built_func, self.context.bool_type().into())?;
+        let else_block = self.context.append_basic_block(built_func, "else");
+
+        self.builder
+            .build_conditional_branch(then_cond.into_int_value(), then_block, else_block)
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.codegen_block(body, built_func, then_block, next_block)?;
+        // The blocks that branches end in without returning or breaking, which
+        // continue after the whole `if`
+        let mut fallthrough = Vec::from_iter(self.unterminated_block());
+
+        match else_body.as_deref() {
+            Some(Conditional::Else { body }) => {
+                self.codegen_block(body, built_func, else_block, next_block)?;
+                fallthrough.extend(self.unterminated_block());
+                if !fallthrough.is_empty() {
+                    let cont = self.context.append_basic_block(built_func, "if_cont");
+                    self.branch_to(&fallthrough, cont)?;
+                }
+            }
+            Some(Conditional::If { .. }) => {
+                return Err(CodeGenError::new(
+                    ErrorCode::Internal,
+                    "`else if` should have been lowered by the desugaring pass",
+                ));
+            }
+            // Without an else, the else block is where the `if` continues
+            None => self.branch_to(&fallthrough, else_block)?,
+        }
+        Ok(())
+    }
+
+    /// The block being built, if it doesn't end in a terminator yet. Bodies may
+    /// end in a different block than they started in.
+    fn unterminated_block(&self) -> Option<BasicBlock<'ctx>> {
+        self.builder
+            .get_insert_block()
+            .filter(|block| block.get_terminator().is_none())
+    }
 
+    /// Ends every block in `blocks` with a branch to `dest`, and continues in `dest`
+    fn branch_to(
+        &self,
+        blocks: &[BasicBlock<'ctx>],
+        dest: BasicBlock<'ctx>,
+    ) -> Result<(), CodeGenError> {
+        for block in blocks {
+            self.builder.position_at_end(*block);
             self.builder
-                .build_conditional_branch(then_cond.into_int_value(), then_block, else_block)
+                .build_unconditional_branch(dest)
                 .map_err(CodeGenError::from_llvm_err)?;
-
-            self.codegen_block(body, built_func, then_block, next_block)?;
-
-            if let Some(else_body) = else_body {
-                if let Conditional::Else { body } = &**else_body {
-                    self.codegen_block(body, built_func, else_block, next_block)?;
-                    break;
-                }
-                stmt = else_body;
-                self.builder.position_at_end(else_block);
-            } else {
-                if then_block.get_terminator().is_none() {
-                    self.builder.build_unconditional_branch(else_block).unwrap();
-                }
-                self.builder.position_at_end(else_block);
-                break;
-            }
         }
+        self.builder.position_at_end(dest);
         Ok(())
     }
 }
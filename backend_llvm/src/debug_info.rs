@@ -0,0 +1,432 @@
+use std::{
+    path::{Path, absolute},
+    slice,
+};
+
+use inkwell::{
+    AddressSpace,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFlags, DIFlagsConstants, DIType, DWARFEmissionKind,
+        DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::FlagBehavior,
+    targets::TargetData,
+    types::StructType,
+    values::{FunctionValue, PointerValue},
+};
+use lexer::types::Datatype;
+use parser::nodes;
+
+use crate::{CodeGen, CodeGenError, OptLevel};
+
+// Encodings of DWARF base types
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+const DW_ATE_UNSIGNED_CHAR: u32 = 0x08;
+
+/// Builds the DWARF metadata of the module. The sizes and offsets of types are
+/// taken from the layout of the target.
+pub(crate) struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    target_data: TargetData,
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Creates the compile unit of the source file, before any function is built
+    pub(crate) fn init_debug_info(&self) -> Result<(), CodeGenError> {
+        let machine = self.target_machine()?;
+        let source = self.module.get_source_file_name().to_string_lossy();
+        let path = absolute(Path::new(source.as_ref()))
+            .map_err(|err| CodeGenError::internal(&err.to_string()))?;
+        let file_name = path.file_name().map_or(source.to_string(), |name| {
+            name.to_string_lossy().to_string()
+        });
+        let directory = path
+            .parent()
+            .map_or(String::new(), |dir| dir.to_string_lossy().to_string());
+
+        let i32_type = self.context.i32_type();
+        self.module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(3, false),
+        );
+        self.module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(4, false),
+        );
+
+        let (builder, compile_unit) = self.module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &file_name,
+            &directory,
+            "compette",
+            self.opt_level != OptLevel::O0,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        let debug = DebugInfo {
+            builder,
+            compile_unit,
+            target_data: machine.get_target_data(),
+        };
+        if self.debug.set(debug).is_err() {
+            return Err(CodeGenError::internal("Debug info was already created"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finalize_debug_info(&self) {
+        if let Some(debug) = self.debug.get() {
+            debug.builder.finalize();
+        }
+    }
+
+    /// Attaches a subprogram to the function, which the locations of its statements
+    /// and its variables belong to
+    pub(crate) fn debug_function(
+        &self,
+        func: &nodes::Function,
+        built_func: FunctionValue<'ctx>,
+    ) -> Result<(), CodeGenError> {
+        let Some(debug) = self.debug.get() else {
+            return Ok(());
+        };
+        let return_type = match &func.return_type {
+            Some(Datatype::NONE) | None => None,
+            Some(dt) => Some(self.debug_type(dt)?),
+        };
+        let params = func
+            .args
+            .iter()
+            .map(|param| self.debug_type(&param.datatype))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let file = debug.compile_unit.get_file();
+        let subroutine_type =
+            debug
+                .builder
+                .create_subroutine_type(file, return_type, &params, DIFlags::ZERO);
+        let line = func.line as u32 + 1;
+        let subprogram = debug.builder.create_function(
+            debug.compile_unit.as_debug_info_scope(),
+            &func.name,
+            None,
+            file,
+            line,
+            subroutine_type,
+            false,
+            true,
+            line,
+            DIFlags::ZERO,
+            self.opt_level != OptLevel::O0,
+        );
+        built_func.set_subprogram(subprogram);
+        // Replaces the location left over from the previous function
+        self.debug_location(Some(func.line), built_func);
+        Ok(())
+    }
+
+    /// Makes the instructions built next belong to a line of the function
+    pub(crate) fn debug_location(&self, line: Option<usize>, built_func: FunctionValue<'ctx>) {
+        let (Some(debug), Some(line), Some(subprogram)) =
+            (self.debug.get(), line, built_func.get_subprogram())
+        else {
+            return;
+        };
+        let location = debug.builder.create_debug_location(
+            self.context,
+            line as u32 + 1,
+            0,
+            subprogram.as_debug_info_scope(),
+            None,
+        );
+        self.builder.set_current_debug_location(location);
+    }
+
+    /// Describes a variable stored at `ptr`, or a parameter when `arg_no` is given
+    pub(crate) fn debug_variable(
+        &self,
+        name: &str,
+        dt: &Datatype,
+        ptr: PointerValue<'ctx>,
+        line: usize,
+        arg_no: Option<u32>,
+        built_func: FunctionValue<'ctx>,
+    ) -> Result<(), CodeGenError> {
+        let (Some(debug), Some(subprogram)) = (self.debug.get(), built_func.get_subprogram())
+        else {
+            return Ok(());
+        };
+        let scope = subprogram.as_debug_info_scope();
+        let file = debug.compile_unit.get_file();
+        let ty = self.debug_type(dt)?;
+        let line = line as u32 + 1;
+        let var = match arg_no {
+            Some(arg_no) => debug.builder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line,
+                ty,
+                true,
+                DIFlags::ZERO,
+            ),
+            None => debug.builder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line,
+                ty,
+                true,
+                DIFlags::ZERO,
+                0,
+            ),
+        };
+        let location = debug
+            .builder
+            .create_debug_location(self.context, line, 0, scope, None);
+        let block = self
+            .builder
+            .get_insert_block()
+            .ok_or(CodeGenError::internal(
+                "The builder isn't positioned in a block",
+            ))?;
+        debug
+            .builder
+            .insert_declare_at_end(ptr, Some(var), None, location, block);
+        Ok(())
+    }
+
+    fn debug_type(&self, dt: &Datatype) -> Result<DIType<'ctx>, CodeGenError> {
+        let debug = self
+            .debug
+            .get()
+            .ok_or(CodeGenError::internal("Debug info was not created"))?;
+        let llvm_type = self.parser_to_llvm_dt(dt)?;
+        let size = debug.target_data.get_abi_size(&llvm_type) * 8;
+        let align = debug.target_data.get_abi_alignment(&llvm_type) * 8;
+        let basic_type = |name: &str, size: u64, encoding: u32| {
+            debug
+                .builder
+                .create_basic_type(name, size, encoding, DIFlags::ZERO)
+                .map(|ty| ty.as_type())
+                .map_err(CodeGenError::internal)
+        };
+
+        match dt {
+            Datatype::U8 | Datatype::U16 | Datatype::U32 | Datatype::U64 => {
+                basic_type(&dt.to_string(), size, DW_ATE_UNSIGNED)
+            }
+            Datatype::I8 | Datatype::I16 | Datatype::I32 | Datatype::I64 => {
+                basic_type(&dt.to_string(), size, DW_ATE_SIGNED)
+            }
+            Datatype::F32 | Datatype::F64 => basic_type(&dt.to_string(), size, DW_ATE_FLOAT),
+            Datatype::BOOL => basic_type("bool", size, DW_ATE_BOOLEAN),
+            Datatype::STRING(_) | Datatype::CSTRING(_) => {
+                let char_type = basic_type("char", 8, DW_ATE_UNSIGNED_CHAR)?;
+                let ptr_type = self.context.ptr_type(AddressSpace::default());
+                let ptr = debug
+                    .builder
+                    .create_pointer_type(
+                        "*char",
+                        char_type,
+                        debug.target_data.get_abi_size(&ptr_type) * 8,
+                        debug.target_data.get_abi_alignment(&ptr_type) * 8,
+                        AddressSpace::default(),
+                    )
+                    .as_type();
+                if let Datatype::CSTRING(_) = dt {
+                    return Ok(ptr);
+                }
+                let len = self.debug_type(&Datatype::U64)?;
+                self.debug_struct(
+                    "string",
+                    self.string_type(),
+                    &[("len".to_string(), len), ("ptr".to_string(), ptr)],
+                )
+            }
+            Datatype::NARRAY(inner, len) | Datatype::SIMD(inner, len) => Ok(debug
+                .builder
+                .create_array_type(
+                    self.debug_type(inner)?,
+                    size,
+                    align,
+                    slice::from_ref(&(0..*len as i64)),
+                )
+                .as_type()),
+            Datatype::SLICE(inner) => {
+                let ptr_type = self.context.ptr_type(AddressSpace::default());
+                let data = debug
+                    .builder
+                    .create_pointer_type(
+                        &format!("*{}", inner),
+                        self.debug_type(inner)?,
+                        debug.target_data.get_abi_size(&ptr_type) * 8,
+                        debug.target_data.get_abi_alignment(&ptr_type) * 8,
+                        AddressSpace::default(),
+                    )
+                    .as_type();
+                let len = self.debug_type(&Datatype::U64)?;
+                self.debug_struct(
+                    &dt.to_string(),
+                    llvm_type.into_struct_type(),
+                    &[("len".to_string(), len), ("data".to_string(), data)],
+                )
+            }
+            Datatype::RESULT(ok, err) => self.debug_struct(
+                &dt.to_string(),
+                self.result_type(ok, err)?,
+                &[
+                    ("is_ok".to_string(), self.debug_type(&Datatype::BOOL)?),
+                    ("ok".to_string(), self.debug_type(ok)?),
+                    ("err".to_string(), self.debug_type(err)?),
+                ],
+            ),
+            Datatype::CUSTOM(name) => {
+                let names = self.struct_defs.get_field_names(name).unwrap_or_default();
+                let fields = names
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let field_dt = self.struct_defs.get_field_datatype(name, index).ok_or(
+                            CodeGenError::internal(&format!(
+                                "Missing field `{}` of `{}`",
+                                field, name
+                            )),
+                        )?;
+                        Ok((field, self.debug_type(&field_dt)?))
+                    })
+                    .collect::<Result<Vec<_>, CodeGenError>>()?;
+                self.debug_struct(name, llvm_type.into_struct_type(), &fields)
+            }
+            Datatype::NONE => Err(CodeGenError::internal("Values can't have the type `none`")),
+        }
+    }
+
+    fn debug_struct(
+        &self,
+        name: &str,
+        struct_type: StructType<'ctx>,
+        fields: &[(String, DIType<'ctx>)],
+    ) -> Result<DIType<'ctx>, CodeGenError> {
+        let debug = self
+            .debug
+            .get()
+            .ok_or(CodeGenError::internal("Debug info was not created"))?;
+        let scope = debug.compile_unit.as_debug_info_scope();
+        let file = debug.compile_unit.get_file();
+        let members = fields
+            .iter()
+            .enumerate()
+            .map(|(index, (field, ty))| {
+                let offset = debug
+                    .target_data
+                    .offset_of_element(&struct_type, index as u32)
+                    .ok_or(CodeGenError::internal(&format!(
+                        "No field {} in the layout of `{}`",
+                        index, name
+                    )))?;
+                Ok(debug
+                    .builder
+                    .create_member_type(
+                        scope,
+                        field,
+                        file,
+                        0,
+                        ty.get_size_in_bits(),
+                        ty.get_align_in_bits(),
+                        offset * 8,
+                        DIFlags::ZERO,
+                        *ty,
+                    )
+                    .as_type())
+            })
+            .collect::<Result<Vec<_>, CodeGenError>>()?;
+        Ok(debug
+            .builder
+            .create_struct_type(
+                scope,
+                name,
+                file,
+                0,
+                debug.target_data.get_abi_size(&struct_type) * 8,
+                debug.target_data.get_abi_alignment(&struct_type) * 8,
+                DIFlags::ZERO,
+                None,
+                &members,
+                0,
+                None,
+                name,
+            )
+            .as_type())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+    use passes::r#impl::PassManager;
+
+    use crate::CodeGen;
+
+    #[test]
+    fn test_debug_info() {
+        let data = "struct Point { x i32, y f64 }
+
+func length(p Point, scale u32!) u32 {
+    scale = scale + 1
+    return scale
+}
+
+func main() u32 {
+    let Point p = { x 1, y 2.5 }
+    let u8[] bytes = [1, 2, 3]
+    let string name = \"point\"
+    return length(p, 2)
+}";
+        let context = Context::create();
+        let mut tokens = Parser::new(Lexer::new(data).tokenize()).parse().unwrap();
+        PassManager::new(&mut tokens).invoke().unwrap();
+        let codegen = CodeGen::new(&context, tokens, false)
+            .with_source_file_name("main.slpe")
+            .with_debug_info(true);
+        codegen.codegen().unwrap();
+        // Verifies the module, debug info included
+        codegen.optimize().unwrap();
+
+        let ir = codegen.ir_as_string();
+        for expected in [
+            r#"!DIFile(filename: "main.slpe""#,
+            r#"!DISubprogram(name: "length", scope: "#,
+            r#"!DISubprogram(name: "main", scope: "#,
+            r#"!DILocalVariable(name: "p", arg: 1"#,
+            r#"!DILocalVariable(name: "scale", arg: 2"#,
+            r#"!DILocalVariable(name: "p", scope: "#,
+            r#"!DILocalVariable(name: "bytes", scope: "#,
+            r#"!DILocalVariable(name: "name", scope: "#,
+            r#"!DICompositeType(tag: DW_TAG_structure_type, name: "Point""#,
+            r#"!DIDerivedType(tag: DW_TAG_member, name: "y""#,
+            r#"!DICompositeType(tag: DW_TAG_array_type"#,
+            r#"!DIBasicType(name: "i32", size: 32, encoding: DW_ATE_signed)"#,
+            "!DILocation(line: 12,",
+        ] {
+            assert!(ir.contains(expected), "missing {}", expected);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use diagnostics::ErrorCode;
+use inkwell::targets::{
+    CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+};
+
+use crate::{CodeGen, CodeGenError};
+
+/// The machine to generate code for, set with `CodeGen::with_target`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TargetOptions {
+    /// A triple such as `aarch64-unknown-linux-gnu`, the host when `None`
+    pub triple: Option<String>,
+    /// Defaults to the host CPU when compiling for the host, and to a generic one
+    /// otherwise
+    pub cpu: Option<String>,
+    /// Features to enable or disable, such as `+neon,-fp-armv8`
+    pub features: Option<String>,
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Writes the module as LLVM IR text
+    pub fn write_llvm_ir(&self, path: &Path) -> Result<(), CodeGenError> {
+        self.target_machine()?;
+        self.module
+            .print_to_file(path)
+            .map_err(|err| write_failed(path, &err.to_string()))
+    }
+
+    /// Writes the module as LLVM bitcode
+    pub fn write_llvm_bc(&self, path: &Path) -> Result<(), CodeGenError> {
+        self.target_machine()?;
+        if !self.module.write_bitcode_to_path(path) {
+            return Err(write_failed(path, "LLVM could not write the bitcode"));
+        }
+        Ok(())
+    }
+
+    /// Writes the assembly of the module for the target
+    pub fn write_asm(&self, path: &Path) -> Result<(), CodeGenError> {
+        self.write_native(path, FileType::Assembly)
+    }
+
+    /// Writes an object file of the module for the target, which still has to be
+    /// linked with the stdlib
+    pub fn write_object(&self, path: &Path) -> Result<(), CodeGenError> {
+        self.write_native(path, FileType::Object)
+    }
+
+    fn write_native(&self, path: &Path, file_type: FileType) -> Result<(), CodeGenError> {
+        let machine = self.target_machine()?;
+        machine
+            .write_to_file(&self.module, file_type, path)
+            .map_err(|err| write_failed(path, &err.to_string()))
+    }
+
+    /// Creates the machine code generator for the target, and sets the triple and data
+    /// layout of the module to match it
+    pub(crate) fn target_machine(&self) -> Result<TargetMachine, CodeGenError> {
+        let (triple, cpu, features) = match &self.target.triple {
+            None => {
+                Target::initialize_native(&InitializationConfig::default())
+                    .map_err(|err| CodeGenError::internal(&err))?;
+                (
+                    TargetMachine::get_default_triple(),
+                    TargetMachine::get_host_cpu_name().to_string(),
+                    TargetMachine::get_host_cpu_features().to_string(),
+                )
+            }
+            Some(triple) => {
+                Target::initialize_all(&InitializationConfig::default());
+                let triple = TargetMachine::normalize_triple(&TargetTriple::create(triple));
+                (triple, "generic".to_string(), String::new())
+            }
+        };
+        let cpu = self.target.cpu.as_ref().unwrap_or(&cpu);
+        let features = self.target.features.as_ref().unwrap_or(&features);
+
+        let target = Target::from_triple(&triple).map_err(|err| {
+            CodeGenError::new(
+                ErrorCode::UnknownTarget,
+                &format!("Unknown target `{}`: {}", triple, err),
+            )
+        })?;
+        let machine = target
+            .create_target_machine(
+                &triple,
+                cpu,
+                features,
+                self.opt_level.into(),
+                RelocMode::PIC,
+                CodeModel::Default,
+            )
+            .ok_or(CodeGenError::new(
+                ErrorCode::UnknownTarget,
+                &format!("Cannot generate code for `{}` with CPU `{}`", triple, cpu),
+            ))?;
+        self.module.set_triple(&triple);
+        self.module
+            .set_data_layout(&machine.get_target_data().get_data_layout());
+        Ok(machine)
+    }
+}
+
+fn write_failed(path: &Path, msg: &str) -> CodeGenError {
+    CodeGenError::new(
+        ErrorCode::WriteFailed,
+        &format!("Cannot write `{}`: {}", path.display(), msg),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs};
+
+    use inkwell::context::Context;
+
+    use super::*;
+
+    #[test]
+    fn test_write_outputs() {
+        let data = "func main(a u32) u32 {
+    return a
+}";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false);
+        codegen.codegen().unwrap();
+
+        let dir = temp_dir().join("compette_test_write_outputs");
+        fs::create_dir_all(&dir).unwrap();
+        codegen.write_llvm_ir(&dir.join("main.ll")).unwrap();
+        codegen.write_llvm_bc(&dir.join("main.bc")).unwrap();
+        codegen.write_asm(&dir.join("main.s")).unwrap();
+        codegen.write_object(&dir.join("main.o")).unwrap();
+
+        let ir = fs::read_to_string(dir.join("main.ll")).unwrap();
+        assert!(ir.contains("define i32 @main(i32 %a)"));
+        assert!(
+            fs::read_to_string(dir.join("main.s"))
+                .unwrap()
+                .contains("main")
+        );
+        // Bitcode files start with the magic bytes `BC`
+        assert!(fs::read(dir.join("main.bc")).unwrap().starts_with(b"BC"));
+        assert!(!fs::read(dir.join("main.o")).unwrap().is_empty());
+
+        let err = codegen
+            .write_llvm_ir(&dir.join("missing/main.ll"))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::WriteFailed);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cross_compile() {
+        let data = "func main(a u32) u32 {
+    return a
+}";
+        let dir = temp_dir().join("compette_test_cross_compile");
+        fs::create_dir_all(&dir).unwrap();
+        let compile = |triple: &str| {
+            let context = Context::create();
+            let tokens = crate::check_program(data).unwrap();
+            let codegen = CodeGen::new(&context, tokens, false).with_target(TargetOptions {
+                triple: Some(triple.to_string()),
+                ..Default::default()
+            });
+            codegen.codegen().unwrap();
+            let object = dir.join(triple).with_extension("o");
+            codegen.write_object(&object)?;
+            Ok::<_, CodeGenError>((codegen.ir_as_string(), fs::read(object).unwrap()))
+        };
+
+        let (ir, object) = compile("aarch64-unknown-linux-gnu").unwrap();
+        assert!(ir.contains(r#"target triple = "aarch64-unknown-linux-gnu""#));
+        assert!(ir.contains("target datalayout"));
+        // An ELF file, for the machine 183 (AArch64)
+        assert!(object.starts_with(b"\x7fELF"));
+        assert_eq!(u16::from_le_bytes([object[18], object[19]]), 183);
+
+        let (ir, object) = compile("wasm32-unknown-unknown").unwrap();
+        assert!(ir.contains(r#"target triple = "wasm32-unknown-unknown""#));
+        assert!(object.starts_with(b"\0asm"));
+
+        let err = compile("nonexistent-unknown-none").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::UnknownTarget);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use diagnostics::ErrorCode;
+use inkwell::{AddressSpace, module::Linkage, values::FunctionValue};
+use lexer::types::Datatype;
+use parser::nodes::Function;
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+/// The name of `main` in the module when it takes the arguments of the program, as the
+/// C `main` that calls it is generated
+const MAIN_WITH_ARGS: &str = "__compette_main";
+
+/// Whether the function is `main(args string[..])`
+pub(crate) fn takes_program_args(func: &Function) -> bool {
+    let [param] = func.args.as_slice() else {
+        return false;
+    };
+    func.name == "main"
+        && matches!(&param.datatype, Datatype::SLICE(dt) if matches!(**dt, Datatype::STRING(_)))
+}
+
+/// The name of the function in the module
+pub(crate) fn symbol_name(func: &Function) -> &str {
+    if takes_program_args(func) {
+        MAIN_WITH_ARGS
+    } else {
+        &func.name
+    }
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Generates the C `main`, which wraps `argc` and `argv` in strings and calls `main`
+    /// with them. What `main` returns becomes the exit code, 0 if it returns nothing.
+    pub(crate) fn build_entry(
+        &self,
+        main: &Function,
+        built_main: FunctionValue<'ctx>,
+    ) -> Result<FunctionValue<'ctx>, CodeGenError> {
+        built_main.set_linkage(Linkage::Internal);
+        let i32_type = self.context.i32_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = i32_type.fn_type(&[i32_type.into(), ptr_type.into()], false);
+        let entry = self.module.add_function("main", fn_type, None);
+        let (Some(argc), Some(argv)) = (entry.get_nth_param(0), entry.get_nth_param(1)) else {
+            return Err(CodeGenError::internal("`main` is missing its parameters"));
+        };
+        argc.set_name("argc");
+        argv.set_name("argv");
+
+        let block = self.context.append_basic_block(entry, "entry");
+        self.builder.position_at_end(block);
+        // The entry has no debug info, so it can't have the location of the last statement
+        self.builder.unset_current_debug_location();
+
+        let strings = self
+            .call_builtin("__builtin__args", &[argc.into(), argv.into()])?
+            .ok_or(CodeGenError::internal(
+                "Builtin __builtin__args returns nothing",
+            ))?
+            .into_pointer_value();
+        let len = self
+            .builder
+            .build_int_z_extend(argc.into_int_value(), self.context.i64_type(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        let slice_ty = self.slice_type(&Datatype::STRING(0))?;
+        let args = self.build_slice(slice_ty, len, strings)?;
+        let result = self
+            .builder
+            .build_call(built_main, &[args.into()], "")
+            .map_err(CodeGenError::from_llvm_err)?
+            .try_as_basic_value()
+            .left();
+
+        let code = match (result, &main.return_type) {
+            (None, _) => i32_type.const_zero(),
+            (Some(value), Some(dt)) if dt.is_integer() => self
+                .impl_cast_expr(
+                    TypedValue::new(value, dt.is_signed()),
+                    i32_type.into(),
+                    true,
+                )?
+                .value
+                .into_int_value(),
+            (Some(_), _) => {
+                return Err(CodeGenError::new(
+                    ErrorCode::Unsupported,
+                    "`main` can only return an integer or nothing",
+                ));
+            }
+        };
+        self.builder
+            .build_return(Some(&code))
+            .map_err(CodeGenError::from_llvm_err)?;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_main_with_args() {
+        let data = "func main(args string[..]) u8 { return args.len() -> u8 }";
+        let ir = crate::get_codegen_for_string(data).unwrap();
+
+        assert!(ir.contains(r#"define internal i8 @__compette_main(%"slice<string>" %args)"#));
+        assert!(ir.contains("define i32 @main(i32 %argc, ptr %argv)"));
+        assert!(ir.contains("call ptr @__builtin__args(i32 %argc, ptr %argv)"));
+        assert!(ir.contains("zext i8"));
+    }
+
+    #[test]
+    fn test_main_without_args() {
+        let data = "func main() u32 { return 0 }";
+        let ir = crate::get_codegen_for_string(data).unwrap();
+
+        assert!(ir.contains("define i32 @main()"));
+        assert!(!ir.contains("__compette_main"));
+    }
+}
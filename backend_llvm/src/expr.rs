@@ -1,12 +1,12 @@
+use diagnostics::ErrorCode;
 use inkwell::{
-    AddressSpace,
-    types::{BasicType, BasicTypeEnum},
-    values::{BasicValue, BasicValueEnum, FunctionValue},
+    types::BasicTypeEnum,
+    values::{BasicValue, BasicValueEnum, FunctionValue, InstructionOpcode},
 };
-use lexer::types::{Operator, Types};
+use lexer::types::{Datatype, Operator, Types};
 use parser::nodes::{ASTNodes, Expression, Literal, Variable};
 
-use crate::{CodeGen, CodeGenError};
+use crate::{CodeGen, CodeGenError, values::TypedValue};
 
 impl<'ctx> CodeGen<'ctx> {
     pub(crate) fn impl_expr(
@@ -15,118 +15,137 @@ impl<'ctx> CodeGen<'ctx> {
         built_func: FunctionValue<'ctx>,
         dt: BasicTypeEnum<'ctx>,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        self.impl_typed_expr(node, built_func, dt).map(|v| v.value)
+    }
+
+    pub(crate) fn impl_typed_expr(
+        &self,
+        node: &Expression,
+        built_func: FunctionValue<'ctx>,
+        dt: BasicTypeEnum<'ctx>,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
         match node {
             Expression::Simple {
                 left,
                 right,
                 operator,
             } => {
-                let left_val = self.impl_simple_expr_arm(left, built_func, dt)?;
+                let left_val = match right.as_deref() {
+                    Some(ASTNodes::Token(Types::DATATYPE(Datatype::SIMD(..)))) => {
+                        self.impl_vector_cast_operand(left, built_func, dt)?
+                    }
+                    _ => self.impl_simple_expr_arm(left, built_func, dt)?,
+                };
 
                 if let Some(right_val) = right {
                     if let ASTNodes::Token(Types::DATATYPE(dt)) = &**right_val {
-                        operator.as_ref().unwrap().ne(&Operator::CAST).then_some(
-                            CodeGenError::new("Invalid expression; expected a cast operation"),
-                        );
-                        let dt = self.parser_to_llvm_dt(dt);
-                        return self.impl_cast_expr(left_val, dt);
+                        if operator != &Some(Operator::CAST) {
+                            return Err(CodeGenError::internal(
+                                "Invalid expression; expected a cast operation",
+                            ));
+                        }
+                        let signed = dt.is_signed();
+                        let dt = self.parser_to_llvm_dt(dt)?;
+                        return self.impl_cast_expr(left_val, dt, signed);
                     }
 
                     let right_val = self.impl_simple_expr_arm(right_val, built_func, dt)?;
                     let (left_val, right_val) = self.impl_cast_simple_expr(left_val, right_val)?;
-                    return self.impl_binary_operation(
-                        left_val,
-                        right_val,
-                        operator.as_ref().unwrap(),
-                    );
+                    let operator = operator.as_ref().ok_or(CodeGenError::internal(
+                        "Invalid expression; expected an operator",
+                    ))?;
+                    return self.impl_binary_operation(left_val, right_val, operator);
                 }
                 return Ok(left_val);
             }
             Expression::Array(arr) if dt.is_array_type() => {
-                let dt = dt.into_array_type();
-                let inner_dt = dt.get_element_type();
+                let inner_dt = dt.into_array_type().get_element_type();
                 let mut array_val = vec![];
                 for value in arr {
                     array_val.push(self.impl_expr(value, built_func, inner_dt)?);
                 }
-                return Ok(self.dt_to_array(&inner_dt, array_val).into());
+                return Ok(TypedValue::unsigned(self.build_aggregate(dt, array_val)?));
             }
             Expression::Array(arr) if dt.is_vector_type() => {
                 let dt = dt.into_vector_type();
                 let inner_dt = dt.get_element_type();
                 let vec_size = dt.get_size() as usize;
                 if vec_size != arr.len() || vec_size % 2 != 0 {
-                    return Err(CodeGenError::new("Invalid vector size"));
+                    return Err(CodeGenError::new(
+                        ErrorCode::MismatchedTypes,
+                        "Invalid vector size",
+                    ));
                 }
                 let mut array_val = vec![];
                 for value in arr {
                     array_val.push(self.impl_expr(value, built_func, inner_dt)?);
                 }
-                return Ok(self.dt_to_vector(&inner_dt, array_val).into());
+                return Ok(TypedValue::unsigned(
+                    self.dt_to_vector(&inner_dt, array_val)?.into(),
+                ));
             }
             Expression::Struct(fields) if dt.is_struct_type() => {
-                let mut struct_vals = vec![None; fields.len()];
                 let dt = dt.into_struct_type();
-                let name = dt.get_name().unwrap().to_str().unwrap();
+                let name = Self::struct_name(dt)?;
+                let field_names =
+                    self.struct_defs
+                        .get_field_names(&name)
+                        .ok_or(CodeGenError::new(
+                            ErrorCode::UnknownType,
+                            &format!("Unknown type `{}`", name),
+                        ))?;
+                let mut struct_vals = vec![None; field_names.len()];
 
                 for (field, val) in fields {
-                    let field = self.struct_defs.get_field_index(name, field).unwrap();
-                    struct_vals[field] = Some(
-                        self.impl_expr(
-                            val,
-                            built_func,
-                            dt.get_field_type_at_index(field as u32).unwrap(),
-                        )
-                        .unwrap(),
-                    );
+                    let index =
+                        self.struct_defs
+                            .get_field_index(&name, field)
+                            .ok_or(CodeGenError::new(
+                                ErrorCode::UnknownField,
+                                &format!("No field `{}` on struct `{}`", field, name),
+                            ))?;
+                    let field_dt = Self::field_type(dt, index as u32)?;
+                    struct_vals[index] = Some(self.impl_expr(val, built_func, field_dt)?);
                 }
                 let struct_vals = struct_vals
                     .into_iter()
-                    .map(|v| v.unwrap())
-                    .collect::<Vec<_>>();
-                Ok(dt.const_named_struct(&struct_vals).into())
+                    .zip(&field_names)
+                    .map(|(v, field)| {
+                        v.ok_or(CodeGenError::new(
+                            ErrorCode::MissingField,
+                            &format!("Missing field `{}` of struct `{}`", field, name),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(TypedValue::unsigned(
+                    self.build_aggregate(dt.into(), struct_vals)?,
+                ))
             }
             Expression::String(str) if dt.is_pointer_type() => {
                 let string = self.context.const_string(str.as_bytes(), true);
-                let string_ptr = self.builder.build_alloca(string.get_type(), "").unwrap();
-                self.builder.build_store(string_ptr, string).unwrap();
-
-                Ok(string_ptr.into())
-            }
-            Expression::String(str) => {
-                let string = self.context.const_string(str.as_bytes(), false);
-                let string_ptr = self.builder.build_alloca(string.get_type(), "").unwrap();
-                self.builder.build_store(string_ptr, string).unwrap();
-
-                let struct_ty = self.context.struct_type(
-                    &[
-                        self.context.i64_type().into(),
-                        self.context.ptr_type(AddressSpace::default()).into(),
-                    ],
-                    false,
-                );
-                let struct_ptr = self.builder.build_alloca(struct_ty, "").unwrap();
-                let str_struct = struct_ty.get_undef();
-
-                let str_struct = self
-                    .builder
-                    .build_insert_value(
-                        str_struct,
-                        self.context.i64_type().const_int(str.len() as u64, false),
-                        0,
-                        "",
-                    )
-                    .unwrap();
-                let str_struct = self
+                let string_ptr = self
                     .builder
-                    .build_insert_value(str_struct, string_ptr, 1, "")
-                    .unwrap();
-
-                self.builder.build_store(struct_ptr, str_struct).unwrap();
+                    .build_alloca(string.get_type(), "")
+                    .map_err(CodeGenError::from_llvm_err)?;
+                self.builder
+                    .build_store(string_ptr, string)
+                    .map_err(CodeGenError::from_llvm_err)?;
 
-                Ok(struct_ptr.into())
+                Ok(TypedValue::unsigned(string_ptr.into()))
             }
-            _ => todo!(),
+            Expression::String(str) => self.impl_string_literal(str).map(TypedValue::unsigned),
+            Expression::None => Err(CodeGenError::new(
+                ErrorCode::NoValue,
+                "Expected an expression",
+            )),
+            expr => Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                &format!(
+                    "Mismatched types; found {} where `{}` was expected",
+                    expr.kind(),
+                    dt
+                ),
+            )),
         }
     }
 
@@ -135,14 +154,18 @@ impl<'ctx> CodeGen<'ctx> {
         arm: &ASTNodes,
         built_func: FunctionValue<'ctx>,
         dt: BasicTypeEnum<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
         match arm {
-            ASTNodes::Literal(lit) => self.impl_literal(lit, dt),
+            ASTNodes::Literal(lit) => self.impl_literal(lit, dt).map(TypedValue::unsigned),
             ASTNodes::Variable(var) => self.impl_variable(var, built_func),
-            ASTNodes::Expression(expr) => self.impl_expr(expr, built_func, dt),
+            ASTNodes::Expression(expr) => self.impl_typed_expr(expr, built_func, dt),
+            ASTNodes::FunctionCall(call) if call.name == "ok" || call.name == "err" => self
+                .impl_result_ctor(built_func, call, dt)
+                .map(TypedValue::unsigned),
             ASTNodes::FunctionCall(call) => {
+                let signed = self.func_defs.is_return_signed(&call.name);
                 self.impl_function_call(built_func, call).and_then(|v| {
-                    v.ok_or(CodeGenError::new(
+                    v.map(|v| TypedValue::new(v, signed)).ok_or(CodeGenError::new(ErrorCode::NoValue,
                         "Function does not have an associated return type; it cannot be used as an expression",
                     ))
                 })
@@ -150,37 +173,82 @@ impl<'ctx> CodeGen<'ctx> {
             ASTNodes::ArrayIndex(ind) => self.impl_array_index_val(built_func, ind),
             ASTNodes::Attr(attr) => self.impl_attr_access_val(built_func, attr),
             ASTNodes::Method(method) => self.impl_method_call(built_func, method),
-            _ => todo!("Simple expr arm {:?}", arm),
+            // Integers returned by the stdlib are signed
+            ASTNodes::ImportCall(call) => {
+                self.impl_import_call(built_func, call).and_then(|v| {
+                    v.map(|v| TypedValue::new(v, true)).ok_or(CodeGenError::new(ErrorCode::NoValue,
+                        "Function does not have an associated return type; it cannot be used as an expression",
+                    ))
+                })
+            }
+            ASTNodes::Try(r#try) => self.impl_try(built_func, r#try),
+            arm => Err(CodeGenError::unsupported(&format!(
+                "Expected a value, found {}",
+                arm.kind()
+            ))),
+        }
+    }
+
+    /// Arrays are cast to vectors by loading their memory as a vector, so the
+    /// address of an array variable is used instead of its value
+    fn impl_vector_cast_operand(
+        &self,
+        arm: &ASTNodes,
+        built_func: FunctionValue<'ctx>,
+        dt: BasicTypeEnum<'ctx>,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        if let ASTNodes::Variable(_) = arm
+            && let Ok(var) = self.resolve_var(built_func, arm)
+            && var.type_.is_array_type()
+        {
+            return Ok(TypedValue::new(var.ptr.into(), var.signed));
         }
+        self.impl_simple_expr_arm(arm, built_func, dt)
     }
 
     fn impl_binary_operation(
         &self,
-        left_val: BasicValueEnum<'ctx>,
-        right_val: BasicValueEnum<'ctx>,
+        left: TypedValue<'ctx>,
+        right: TypedValue<'ctx>,
         operator: &Operator,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
-        match operator {
-            Operator::PLUS => self.add_binary_operation(&left_val, &right_val),
-            Operator::MINUS => self.sub_binary_operation(&left_val, &right_val),
-            Operator::MULTIPLY => self.mul_binary_operation(&left_val, &right_val),
-            Operator::DIVIDE => self.div_binary_operation(&left_val, &right_val),
-            Operator::MODULO => self.mod_binary_operation(&left_val, &right_val),
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        let (left_val, right_val) = (left.value, right.value);
+        let signed = left.signed || right.signed;
+        if Self::is_string_type(left_val.get_type()) {
+            return self
+                .impl_string_operation(left_val, right_val, operator)
+                .map(TypedValue::unsigned);
+        }
+        let line = self.stmt_line.get();
+        let value = match operator {
+            Operator::PLUS => self.add_binary_operation(&left_val, &right_val, signed, line),
+            Operator::MINUS => self.sub_binary_operation(&left_val, &right_val, signed, line),
+            Operator::MULTIPLY => self.mul_binary_operation(&left_val, &right_val, signed, line),
+            Operator::DIVIDE => self.div_binary_operation(&left_val, &right_val, signed, line),
+            Operator::MODULO => self.mod_binary_operation(&left_val, &right_val, signed, line),
             Operator::EQUAL
             | Operator::NOT_EQUAL
             | Operator::GREATER
             | Operator::GREATER_EQUAL
             | Operator::LESSER
-            | Operator::LESSER_EQUAL => self
-                .comp_binary_operation(operator, &left_val, &right_val)
-                .map(|v| v.into()),
+            | Operator::LESSER_EQUAL => {
+                return self
+                    .comp_binary_operation(operator, &left_val, &right_val, signed)
+                    .map(|v| TypedValue::unsigned(v.into()));
+            }
             Operator::BITWISE_AND => self.and_binary_operation(&left_val, &right_val),
             Operator::BITWISE_OR => self.or_binary_operation(&left_val, &right_val),
             Operator::BITWISE_XOR => self.xor_binary_operation(&left_val, &right_val),
             Operator::LSHIFT => self.shl_binary_operation(&left_val, &right_val),
-            Operator::RSHIFT => self.shr_binary_operation(&left_val, &right_val),
-            _ => todo!("Binary operator {:?} not implemented", operator),
-        }
+            Operator::RSHIFT => self.shr_binary_operation(&left_val, &right_val, signed),
+            _ => {
+                return Err(CodeGenError::unsupported(&format!(
+                    "`{:?}` can't be used as a binary operator",
+                    operator
+                )));
+            }
+        }?;
+        Ok(TypedValue::new(value, signed))
     }
 
     fn impl_literal(
@@ -188,25 +256,50 @@ impl<'ctx> CodeGen<'ctx> {
         lit: &Literal,
         dt: BasicTypeEnum<'ctx>,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let int_value = || {
+            lit.value.parse::<u64>().map_err(|_| {
+                CodeGenError::new(
+                    ErrorCode::ConstantOverflow,
+                    &format!("Integer literal `{}` does not fit in 64 bits", lit.value),
+                )
+            })
+        };
         match lit.r#type {
             Types::BOOL => Ok(self
                 .context
                 .bool_type()
-                .const_int(lit.value.parse::<u64>().unwrap(), false)
+                .const_int(int_value()?, false)
                 .into()),
+            Types::DATATYPE(Datatype::STRING(_)) => self.impl_string_literal(&lit.value),
             Types::NUMBER => {
                 if lit.value.contains('.') {
-                    let f64_value = lit.value.parse::<f64>().unwrap();
+                    let f64_value = lit.value.parse::<f64>().map_err(|_| {
+                        CodeGenError::internal(&format!("Invalid float literal `{}`", lit.value))
+                    })?;
+                    if !dt.is_float_type() {
+                        return Err(CodeGenError::new(
+                            ErrorCode::MismatchedTypes,
+                            &format!(
+                                "Mismatched types; found a float where `{}` was expected",
+                                dt
+                            ),
+                        ));
+                    }
                     return Ok(dt.into_float_type().const_float(f64_value).into());
                 } else if dt.is_int_type() && dt.into_int_type().get_bit_width() != 1 {
-                    let i64_value = lit.value.parse::<u64>().unwrap();
-                    return Ok(dt.into_int_type().const_int(i64_value, false).into());
+                    return Ok(dt.into_int_type().const_int(int_value()?, false).into());
                 } else {
-                    let i64_value = lit.value.parse::<u64>().unwrap();
-                    return Ok(self.context.i64_type().const_int(i64_value, false).into());
+                    return Ok(self
+                        .context
+                        .i64_type()
+                        .const_int(int_value()?, false)
+                        .into());
                 }
             }
-            _ => todo!(),
+            _ => Err(CodeGenError::unsupported(&format!(
+                "`{:?}` literals can't be used as values",
+                lit.r#type
+            ))),
         }
     }
 
@@ -214,136 +307,172 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         var: &Variable,
         built_func: FunctionValue<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
-        if let Some(var_data) = self.var_ptrs.get(&var.name) {
-            if matches!(
-                var_data.type_,
-                BasicTypeEnum::ArrayType(_)
-                    | BasicTypeEnum::StructType(_)
-                    | BasicTypeEnum::PointerType(_)
-            ) {
-                return Ok(var_data.ptr.into());
-            }
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        // Immutable parameters of scalar types aren't stored as variables, so they are
+        // looked up by name
+        if let Some(var_data) = var.id.and_then(|id| self.var_ptrs.get(id)) {
+            // Arrays and structs are loaded like other values, so that they are copied
             self.builder
                 .build_load(var_data.type_, var_data.ptr, &var.name)
+                .map(|v| TypedValue::new(v, var_data.signed))
                 .map_err(CodeGenError::from_llvm_err)
         } else {
+            let func_name = built_func.get_name().to_string_lossy();
             built_func
                 .get_param_iter()
-                .find(|param| param.get_name().to_str().unwrap() == var.name)
-                .ok_or(CodeGenError::new(&format!(
-                    "Variable {} not found",
-                    var.name
-                )))
+                .enumerate()
+                .find(|(_, param)| param.get_name().to_string_lossy() == var.name)
+                .map(|(index, param)| {
+                    TypedValue::new(param, self.func_defs.is_arg_signed(&func_name, index))
+                })
+                .ok_or(CodeGenError::new(
+                    ErrorCode::UnknownVariable,
+                    &format!("Variable {} not found", var.name),
+                ))
         }
     }
 
+    /// Widens the narrower operand to the type of the other one
     fn impl_cast_simple_expr(
         &self,
-        left_expr: BasicValueEnum<'ctx>,
-        right_expr: BasicValueEnum<'ctx>,
-    ) -> Result<(BasicValueEnum<'ctx>, BasicValueEnum<'ctx>), CodeGenError> {
-        let left_type = left_expr.get_type();
-        let right_type = right_expr.get_type();
-
-        let cast_fn = |op, value, r#type| {
-            self.builder
-                .build_cast(op, value, r#type, "")
-                .map_err(CodeGenError::from_llvm_err)
-        };
+        left_expr: TypedValue<'ctx>,
+        right_expr: TypedValue<'ctx>,
+    ) -> Result<(TypedValue<'ctx>, TypedValue<'ctx>), CodeGenError> {
+        let left_type = left_expr.value.get_type();
+        let right_type = right_expr.value.get_type();
 
         match (left_type, right_type) {
             _ if left_type == right_type => Ok((left_expr, right_expr)),
             (BasicTypeEnum::IntType(a), BasicTypeEnum::IntType(b))
                 if a.get_bit_width() < b.get_bit_width() =>
             {
-                cast_fn(
-                    inkwell::values::InstructionOpcode::ZExt,
-                    left_expr,
-                    right_type,
-                )
-                .map(|v| (v, right_expr))
+                self.impl_cast_expr(left_expr, right_type, left_expr.signed)
+                    .map(|v| (v, right_expr))
             }
             (BasicTypeEnum::IntType(a), BasicTypeEnum::IntType(b))
                 if a.get_bit_width() > b.get_bit_width() =>
             {
-                cast_fn(
-                    inkwell::values::InstructionOpcode::ZExt,
-                    right_expr,
-                    left_type,
-                )
-                .map(|v| (left_expr, v))
+                self.impl_cast_expr(right_expr, left_type, right_expr.signed)
+                    .map(|v| (left_expr, v))
             }
             (BasicTypeEnum::FloatType(a), BasicTypeEnum::FloatType(b))
                 if self.get_float_size(a) < self.get_float_size(b) =>
             {
-                cast_fn(
-                    inkwell::values::InstructionOpcode::FPExt,
-                    left_expr,
-                    right_type,
-                )
-                .map(|v| (v, right_expr))
+                self.impl_cast_expr(left_expr, right_type, true)
+                    .map(|v| (v, right_expr))
             }
             (BasicTypeEnum::FloatType(a), BasicTypeEnum::FloatType(b))
                 if self.get_float_size(a) > self.get_float_size(b) =>
             {
-                cast_fn(
-                    inkwell::values::InstructionOpcode::FPTrunc,
-                    right_expr,
-                    left_type,
-                )
-                .map(|v| (left_expr, v))
+                self.impl_cast_expr(right_expr, left_type, true)
+                    .map(|v| (left_expr, v))
             }
-            _ => todo!("impl_cast_simple_expr {:?} to {:?}", left_type, right_type),
+            _ => Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                &format!(
+                    "Cannot use `{}` and `{}` in the same operation",
+                    left_type, right_type
+                ),
+            )),
         }
     }
 
-    fn impl_cast_expr(
+    /// Casts a value to `cast_to`. Integers are extended and converted to floats
+    /// according to the signedness of the value, and floats are converted to
+    /// integers according to `signed`, the signedness of the target type.
+    pub(crate) fn impl_cast_expr(
         &self,
-        left_expr: BasicValueEnum<'ctx>,
+        left_expr: TypedValue<'ctx>,
         cast_to: BasicTypeEnum<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
-        let left_type = left_expr.get_type();
+        signed: bool,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        let left_type = left_expr.value.get_type();
 
         let cast_fn = |op| {
             self.builder
-                .build_cast(op, left_expr, cast_to, "")
+                .build_cast(op, left_expr.value, cast_to, "")
+                .map(|v| TypedValue::new(v, signed))
                 .map_err(CodeGenError::from_llvm_err)
         };
 
         match (left_type, cast_to) {
-            _ if left_type == cast_to => Ok(left_expr),
+            _ if left_type == cast_to => Ok(TypedValue::new(left_expr.value, signed)),
+            (BasicTypeEnum::IntType(_), BasicTypeEnum::FloatType(_)) if left_expr.signed => {
+                cast_fn(InstructionOpcode::SIToFP)
+            }
             (BasicTypeEnum::IntType(_), BasicTypeEnum::FloatType(_)) => {
-                cast_fn(inkwell::values::InstructionOpcode::SIToFP)
+                cast_fn(InstructionOpcode::UIToFP)
+            }
+            (BasicTypeEnum::FloatType(_), BasicTypeEnum::IntType(_)) if signed => {
+                cast_fn(InstructionOpcode::FPToSI)
             }
             (BasicTypeEnum::FloatType(_), BasicTypeEnum::IntType(_)) => {
-                cast_fn(inkwell::values::InstructionOpcode::FPToSI)
+                cast_fn(InstructionOpcode::FPToUI)
+            }
+            (BasicTypeEnum::IntType(a), BasicTypeEnum::IntType(b))
+                if a.get_bit_width() < b.get_bit_width() && left_expr.signed =>
+            {
+                cast_fn(InstructionOpcode::SExt)
             }
             (BasicTypeEnum::IntType(a), BasicTypeEnum::IntType(b))
                 if a.get_bit_width() < b.get_bit_width() =>
             {
-                cast_fn(inkwell::values::InstructionOpcode::ZExt)
+                cast_fn(InstructionOpcode::ZExt)
             }
             (BasicTypeEnum::IntType(a), BasicTypeEnum::IntType(b))
                 if a.get_bit_width() > b.get_bit_width() =>
             {
-                cast_fn(inkwell::values::InstructionOpcode::Trunc)
+                cast_fn(InstructionOpcode::Trunc)
+            }
+            (BasicTypeEnum::IntType(_), BasicTypeEnum::IntType(_)) => {
+                Ok(TypedValue::new(left_expr.value, signed))
+            }
+            (BasicTypeEnum::FloatType(a), BasicTypeEnum::FloatType(b))
+                if self.get_float_size(a) < self.get_float_size(b) =>
+            {
+                cast_fn(InstructionOpcode::FPExt)
+            }
+            (BasicTypeEnum::FloatType(a), BasicTypeEnum::FloatType(b))
+                if self.get_float_size(a) > self.get_float_size(b) =>
+            {
+                cast_fn(InstructionOpcode::FPTrunc)
+            }
+            (BasicTypeEnum::StructType(_), BasicTypeEnum::PointerType(_))
+                if Self::is_string_type(left_type) =>
+            {
+                self.string_to_cstring(left_expr.value)
+                    .map(TypedValue::unsigned)
+            }
+            (BasicTypeEnum::PointerType(_), BasicTypeEnum::StructType(_))
+                if Self::is_string_type(cast_to) =>
+            {
+                self.cstring_to_string(left_expr.value)
+                    .map(TypedValue::unsigned)
             }
             (BasicTypeEnum::PointerType(_), BasicTypeEnum::VectorType(v)) => {
                 let vector = self
                     .builder
-                    .build_load(v, left_expr.into_pointer_value(), "")
+                    .build_load(v, left_expr.value.into_pointer_value(), "")
                     .map_err(CodeGenError::from_llvm_err)?;
-                _ = vector.as_instruction_value().unwrap().set_alignment(
-                    match v.get_element_type() {
-                        BasicTypeEnum::IntType(it) => it.get_bit_width() / 8 as u32,
-                        BasicTypeEnum::FloatType(ft) => self.get_float_size(ft) as u32,
-                        _ => unreachable!(),
-                    },
-                );
-                Ok(vector)
+                let alignment = match v.get_element_type() {
+                    BasicTypeEnum::IntType(it) => it.get_bit_width() / 8 as u32,
+                    BasicTypeEnum::FloatType(ft) => self.get_float_size(ft) as u32,
+                    _ => {
+                        return Err(CodeGenError::new(
+                            ErrorCode::MismatchedTypes,
+                            "Vectors can only hold integers and floats",
+                        ));
+                    }
+                };
+                if let Some(load) = vector.as_instruction_value() {
+                    _ = load.set_alignment(alignment);
+                }
+                Ok(TypedValue::new(vector, signed))
             }
-            _ => todo!("{} to {}", left_type, cast_to),
+            _ => Err(CodeGenError::new(
+                ErrorCode::InvalidCast,
+                &format!("Cannot cast `{}` to `{}`", left_type, cast_to),
+            )),
         }
     }
 }
@@ -461,7 +590,7 @@ entry:
   %c = alloca i32, align 4
   store i32 %1, ptr %c, align 4
   %c3 = load i32, ptr %c, align 4
-  %2 = zext i32 %c3 to i64
+  %2 = sext i32 %c3 to i64
   %b4 = load i64, ptr %b, align 4
   %3 = add i64 %2, %b4
   %d = alloca i64, align 8
@@ -504,7 +633,7 @@ entry:
   %0 = add i32 %a1, %b2
   %c3 = load i32, ptr %c, align 4
   %1 = add i32 %0, %c3
-  %2 = zext i32 %1 to i64
+  %2 = sext i32 %1 to i64
   %d = alloca i64, align 8
   store i64 %2, ptr %d, align 4
   %d4 = load i64, ptr %d, align 4
@@ -515,6 +644,37 @@ entry:
         )
     }
 
+    #[test]
+    fn test_unsigned_operations() {
+        let data = "func main(a u32, b i8) bool {
+    let u32 c = a / 2
+    let i32 d = b -> i32
+    return c > 10
+}";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+define i1 @main(i32 %a, i8 %b) {
+entry:
+  %0 = udiv i32 %a, 2
+  %c = alloca i32, align 4
+  store i32 %0, ptr %c, align 4
+  %1 = sext i8 %b to i32
+  %d = alloca i32, align 4
+  store i32 %1, ptr %d, align 4
+  %c1 = load i32, ptr %c, align 4
+  %2 = zext i32 %c1 to i64
+  %3 = icmp ugt i64 %2, 10
+  ret i1 %3
+}
+"#
+        )
+    }
+
     #[test]
     fn test_bitwise_operations() {
         let data = "func main() i32 {
@@ -539,7 +699,7 @@ entry:
   %b2 = load i32, ptr %b, align 4
   %b3 = load i32, ptr %b, align 4
   %0 = shl i32 %b3, 4
-  %1 = lshr i32 %0, 2
+  %1 = ashr i32 %0, 2
   %2 = xor i32 %b2, %1
   %3 = or i32 %a1, %2
   ret i32 %3
@@ -1,13 +1,34 @@
 use std::{cell::RefCell, collections::HashMap};
 
+use diagnostics::ErrorCode;
 use inkwell::{
     AddressSpace, context,
-    types::{BasicTypeEnum, FunctionType},
+    types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType},
     values::BasicValueEnum,
 };
+use lexer::types::Datatype;
 use parser::nodes::ImportDef;
+use passes::stdlib::std_function;
 
-use crate::CodeGenError;
+use crate::{CodeGenError, strings::get_string_type};
+
+/// The address of a function of the standard library, for the JIT
+fn std_function_ptr(name: &str) -> Option<usize> {
+    let ptr = match name {
+        "__std__io__print" => stdlib::io::__std__io__print as usize,
+        "__std__io__println" => stdlib::io::__std__io__println as usize,
+        "__std__io__printint" => stdlib::io::__std__io__printint as usize,
+        "__std__io__printflt" => stdlib::io::__std__io__printflt as usize,
+        "__std__fs__write" => stdlib::fs::__std__fs__write as usize,
+        "__std__fs__size" => stdlib::fs::__std__fs__size as usize,
+        "__std__str__parse_int" => stdlib::str::__std__str__parse_int as usize,
+        "__std__str__parse_float" => stdlib::str::__std__str__parse_float as usize,
+        "__std__str__from_int" => stdlib::str::__std__str__from_int as usize,
+        "__std__str__from_float" => stdlib::str::__std__str__from_float as usize,
+        _ => return None,
+    };
+    Some(ptr)
+}
 
 #[derive(Debug)]
 pub struct Resolver<'ctx> {
@@ -18,6 +39,9 @@ pub struct Resolver<'ctx> {
 pub struct StdLibFunc<'ctx> {
     pub ptr: usize,
     pub func: FunctionType<'ctx>,
+    /// The ok type of a fallible function. These take a pointer to write the
+    /// value to as their last param, and return a non-zero error code on failure.
+    pub result: Option<Datatype>,
 }
 
 impl<'ctx> Resolver<'ctx> {
@@ -28,42 +52,207 @@ impl<'ctx> Resolver<'ctx> {
         }
     }
 
-    fn get_stdlib_function(&self, name: &str) -> Option<StdLibFunc<'ctx>> {
+    /// A function of the standard library, with the signature the type checker uses,
+    /// or a compiler builtin
+    pub(crate) fn get_stdlib_function(&self, name: &str) -> Option<StdLibFunc<'ctx>> {
         let func = match name {
-            "__std__io__print" => StdLibFunc {
-                ptr: stdlib::io::__std__io__print as usize,
-                func: self.context.void_type().fn_type(
+            "__builtin__str_concat" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_concat as usize,
+                func: get_string_type(self.context).fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__str_cmp" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_cmp as usize,
+                func: self.context.i32_type().fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__str_slice" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_slice as usize,
+                func: get_string_type(self.context).fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.i64_type().into(),
+                        self.context.i64_type().into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__str_find" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_find as usize,
+                func: self.context.i64_type().fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__str_starts_with" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_starts_with as usize,
+                func: self.context.bool_type().fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__str_to_cstr" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__str_to_cstr as usize,
+                func: self.context.ptr_type(AddressSpace::default()).fn_type(
+                    &[self.context.ptr_type(AddressSpace::default()).into()],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__cstr_to_str" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__cstr_to_str as usize,
+                func: get_string_type(self.context).fn_type(
                     &[self.context.ptr_type(AddressSpace::default()).into()],
                     false,
                 ),
+                result: None,
             },
-            "__std__io__println" => StdLibFunc {
-                ptr: stdlib::io::__std__io__println as usize,
+            "__builtin__args" => StdLibFunc {
+                ptr: stdlib::builtin::strings::__builtin__args as usize,
+                func: self.context.ptr_type(AddressSpace::default()).fn_type(
+                    &[
+                        self.context.i32_type().into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__panic" => StdLibFunc {
+                ptr: stdlib::builtin::panic::__builtin__panic as usize,
+                func: self.context.void_type().fn_type(
+                    &[
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__panic_bounds" => StdLibFunc {
+                ptr: stdlib::builtin::panic::__builtin__panic_bounds as usize,
+                func: self.context.void_type().fn_type(
+                    &[
+                        self.context.i64_type().into(),
+                        self.context.i64_type().into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__fmt_str" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_str as usize,
                 func: self.context.void_type().fn_type(
                     &[self.context.ptr_type(AddressSpace::default()).into()],
                     false,
                 ),
+                result: None,
+            },
+            "__builtin__fmt_int" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_int as usize,
+                func: self
+                    .context
+                    .void_type()
+                    .fn_type(&[self.context.i64_type().into()], false),
+                result: None,
             },
-            // Temporary funtion until format print is implemented
-            "__std__io__printint" => StdLibFunc {
-                ptr: stdlib::io::__std__io__printint as usize,
+            "__builtin__fmt_uint" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_uint as usize,
                 func: self
                     .context
                     .void_type()
                     .fn_type(&[self.context.i64_type().into()], false),
+                result: None,
+            },
+            "__builtin__fmt_float" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_float as usize,
+                func: self.context.void_type().fn_type(
+                    &[
+                        self.context.f64_type().into(),
+                        self.context.i32_type().into(),
+                    ],
+                    false,
+                ),
+                result: None,
+            },
+            "__builtin__fmt_bool" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_bool as usize,
+                func: self
+                    .context
+                    .void_type()
+                    .fn_type(&[self.context.bool_type().into()], false),
+                result: None,
             },
-            "__std__io__printflt" => StdLibFunc {
-                ptr: stdlib::io::__std__io__printflt as usize,
+            "__builtin__fmt_end" => StdLibFunc {
+                ptr: stdlib::builtin::fmt::__builtin__fmt_end as usize,
                 func: self
                     .context
                     .void_type()
-                    .fn_type(&[self.context.f64_type().into()], false),
+                    .fn_type(&[self.context.bool_type().into()], false),
+                result: None,
             },
-            _ => return None,
+            _ => return self.get_std_function(name),
         };
         Some(func)
     }
 
+    /// Maps the signature of a function of the standard library, such as `__std__io__println`,
+    /// to that of its implementation. Strings are passed by pointer, and fallible functions
+    /// write their ok value through a pointer after the params and return an error code.
+    fn get_std_function(&self, name: &str) -> Option<StdLibFunc<'ctx>> {
+        let path = name
+            .strip_prefix("__")?
+            .split("__")
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let sig = std_function(&path)?;
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let mut params = sig
+            .params
+            .iter()
+            .map(|dt| match dt {
+                Datatype::I64 => self.context.i64_type().into(),
+                Datatype::F64 => self.context.f64_type().into(),
+                _ => ptr_type.into(),
+            })
+            .collect::<Vec<BasicMetadataTypeEnum>>();
+        let (func, result) = match sig.return_type {
+            Datatype::NONE => (self.context.void_type().fn_type(&params, false), None),
+            Datatype::RESULT(ok, _) => {
+                params.push(ptr_type.into());
+                (self.context.i32_type().fn_type(&params, false), Some(*ok))
+            }
+            _ => (get_string_type(self.context).fn_type(&params, false), None),
+        };
+        Some(StdLibFunc {
+            ptr: std_function_ptr(name)?,
+            func,
+            result,
+        })
+    }
+
     pub(crate) fn get_builtin_function(
         &self,
         callee_type: BasicTypeEnum<'ctx>,
@@ -76,15 +265,26 @@ impl<'ctx> Resolver<'ctx> {
                     .i64_type()
                     .const_int(arr.len() as u64, false)
                     .into()),
-                _ => todo!(),
+                _ => Err(CodeGenError::new(
+                    ErrorCode::UnknownMethod,
+                    &format!("No method `{}` on arrays", name),
+                )),
             },
 
-            _ => todo!(),
+            _ => Err(CodeGenError::unsupported(&format!(
+                "Methods can't be called on values of type `{}`",
+                callee_type
+            ))),
         };
     }
 
     pub(crate) fn resolve_import_def(&self, path: &ImportDef) -> Result<(), CodeGenError> {
-        let k = path.path.last().unwrap().to_string();
+        let Some(k) = path.path.last().cloned() else {
+            return Err(CodeGenError::new(
+                ErrorCode::UnknownModule,
+                "Empty import path",
+            ));
+        };
         let v = path
             .path
             .iter()
@@ -96,20 +296,27 @@ impl<'ctx> Resolver<'ctx> {
         Ok(())
     }
 
+    /// The symbol of a call through an imported module, such as `__std__io__println`
+    /// for `io::println`
+    pub(crate) fn get_extern_path(&self, path: &str, last: &str) -> String {
+        if let Some(ext) = self.resolved_paths.borrow().get(last) {
+            "__".to_string() + ext + "__" + path
+        } else {
+            "__".to_string() + path
+        }
+    }
+
     pub(crate) fn get_extern_function(
         &self,
         path: &str,
         last: &str,
     ) -> Option<(StdLibFunc<'ctx>, String)> {
-        let path = if let Some(ext) = self.resolved_paths.borrow().get(last) {
-            "__".to_string() + ext + "__" + path
-        } else {
-            "__".to_string() + path
-        };
+        let path = self.get_extern_path(path, last);
+        // Only the stdlib can be imported for now
         if path.starts_with("__std__") {
             self.get_stdlib_function(&path).map(|v| (v, path))
         } else {
-            todo!()
+            None
         }
     }
 }
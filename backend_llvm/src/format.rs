@@ -0,0 +1,302 @@
+use diagnostics::ErrorCode;
+use inkwell::{types::BasicTypeEnum, values::FunctionValue};
+use lexer::types::Types;
+use parser::{
+    format::{FormatPiece, parse_format_string},
+    nodes::{ASTNodes, Expression},
+};
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Compiles a print with a literal format string to calls to the stdlib fmt builtins.
+    /// The type checker has already checked the arguments against their placeholders.
+    pub(crate) fn impl_format_print(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        fmt: &str,
+        args: &[Expression],
+        newline: bool,
+    ) -> Result<(), CodeGenError> {
+        let pieces = parse_format_string(fmt).map_err(|err| CodeGenError::internal(&err))?;
+        let positional = pieces
+            .iter()
+            .filter(|p| matches!(p, FormatPiece::Arg { name: None, .. }))
+            .count();
+        if positional != args.len() {
+            return Err(CodeGenError::internal(&format!(
+                "Format string expects {} arguments, but {} were given",
+                positional,
+                args.len()
+            )));
+        }
+
+        let mut args = args.iter();
+        for piece in pieces {
+            match piece {
+                FormatPiece::Str(s) => self.impl_format_str(&s)?,
+                // The resolution pass turns named args into positional ones
+                FormatPiece::Arg {
+                    name: Some(name), ..
+                } => {
+                    return Err(CodeGenError::internal(&format!(
+                        "Unresolved format argument `{}`",
+                        name
+                    )));
+                }
+                FormatPiece::Arg {
+                    name: None,
+                    precision,
+                } => {
+                    let arg = args
+                        .next()
+                        .ok_or(CodeGenError::internal("Missing format argument"))?;
+                    let value = self.impl_format_expr(built_func, arg)?;
+                    self.impl_format_value(value, precision)?;
+                }
+            }
+        }
+
+        let newline = self.context.bool_type().const_int(newline as u64, false);
+        self.call_builtin("__builtin__fmt_end", &[newline.into()])?;
+        Ok(())
+    }
+
+    fn impl_format_str(&self, s: &str) -> Result<(), CodeGenError> {
+        let string = self.impl_string_literal(s)?;
+        let string_ptr = self.string_to_ptr(string)?;
+        self.call_builtin("__builtin__fmt_str", &[string_ptr.into()])?;
+        Ok(())
+    }
+
+    /// Evaluates a format argument. Variables are loaded by value, so that
+    /// structs and arrays keep their types.
+    fn impl_format_expr(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        expr: &Expression,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        if let Expression::Simple {
+            left, right: None, ..
+        } = expr
+        {
+            let is_local = match &**left {
+                ASTNodes::Variable(var) => var.id.and_then(|id| self.var_ptrs.get(id)).is_some(),
+                ASTNodes::Attr(_) | ASTNodes::ArrayIndex(_) => true,
+                _ => false,
+            };
+            if is_local {
+                let var = self.resolve_var(built_func, left)?;
+                return self
+                    .builder
+                    .build_load(var.type_, var.ptr, "")
+                    .map(|v| TypedValue::new(v, var.signed))
+                    .map_err(CodeGenError::from_llvm_err);
+            }
+        }
+
+        let dt = if let Expression::String(_) = expr {
+            self.string_type().into()
+        } else if Self::has_float_literal(expr) {
+            self.context.f64_type().into()
+        } else {
+            self.context.i64_type().into()
+        };
+        self.impl_typed_expr(expr, built_func, dt)
+    }
+
+    fn has_float_literal(expr: &Expression) -> bool {
+        let Expression::Simple { left, right, .. } = expr else {
+            return false;
+        };
+        [Some(left), right.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|node| match &**node {
+                ASTNodes::Literal(lit) => lit.r#type == Types::NUMBER && lit.value.contains('.'),
+                ASTNodes::Expression(expr) => Self::has_float_literal(expr),
+                _ => false,
+            })
+    }
+
+    fn impl_format_value(
+        &self,
+        typed: TypedValue<'ctx>,
+        precision: Option<u32>,
+    ) -> Result<(), CodeGenError> {
+        let value = typed.value;
+        let dt = value.get_type();
+        if precision.is_some() && !dt.is_float_type() {
+            return Err(CodeGenError::internal(&format!(
+                "Precision can only be used to format floats, found {}",
+                dt
+            )));
+        }
+
+        match dt {
+            BasicTypeEnum::IntType(it) if it.get_bit_width() == 1 => {
+                self.call_builtin("__builtin__fmt_bool", &[value.into()])?;
+            }
+            BasicTypeEnum::IntType(_) => {
+                let value =
+                    self.impl_cast_expr(typed, self.context.i64_type().into(), typed.signed)?;
+                let builtin = if typed.signed {
+                    "__builtin__fmt_int"
+                } else {
+                    "__builtin__fmt_uint"
+                };
+                self.call_builtin(builtin, &[value.value.into()])?;
+            }
+            BasicTypeEnum::FloatType(_) => {
+                let value = self
+                    .impl_cast_expr(typed, self.context.f64_type().into(), true)?
+                    .value;
+                let precision = precision.map_or(-1, |p| p as i64);
+                let precision = self.context.i32_type().const_int(precision as u64, true);
+                self.call_builtin("__builtin__fmt_float", &[value.into(), precision.into()])?;
+            }
+            BasicTypeEnum::PointerType(_) => {
+                // Aggregates are loaded by value, so a pointer is a cstring
+                let string = self.cstring_to_string(value)?;
+                self.impl_format_value(TypedValue::unsigned(string), None)?;
+            }
+            _ if Self::is_string_type(dt) => {
+                let string_ptr = self.string_to_ptr(value)?;
+                self.call_builtin("__builtin__fmt_str", &[string_ptr.into()])?;
+            }
+            _ if Self::is_result_type(dt) => {
+                return Err(CodeGenError::internal("Results cannot be formatted"));
+            }
+            BasicTypeEnum::StructType(st) => {
+                let name = Self::struct_name(st)?;
+                let fields = self
+                    .struct_defs
+                    .get_field_names(&name)
+                    .ok_or(CodeGenError::new(
+                        ErrorCode::UnknownType,
+                        &format!("Struct {} not found", name),
+                    ))?;
+
+                self.impl_format_str(&format!("{} {{ ", name))?;
+                for (index, field) in fields.iter().enumerate() {
+                    if index != 0 {
+                        self.impl_format_str(", ")?;
+                    }
+                    self.impl_format_str(&format!("{}: ", field))?;
+                    let field = self
+                        .builder
+                        .build_extract_value(value.into_struct_value(), index as u32, "")
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    let signed = self
+                        .struct_defs
+                        .get_field_datatype(&name, index)
+                        .is_some_and(|dt| dt.is_signed());
+                    self.impl_format_value(TypedValue::new(field, signed), None)?;
+                }
+                self.impl_format_str(" }")?;
+            }
+            BasicTypeEnum::ArrayType(at) => {
+                self.impl_format_str("[")?;
+                for index in 0..at.len() {
+                    if index != 0 {
+                        self.impl_format_str(", ")?;
+                    }
+                    let elem = self
+                        .builder
+                        .build_extract_value(value.into_array_value(), index, "")
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    self.impl_format_value(TypedValue::new(elem, typed.signed), None)?;
+                }
+                self.impl_format_str("]")?;
+            }
+            BasicTypeEnum::VectorType(vt) => {
+                self.impl_format_str("<")?;
+                for index in 0..vt.get_size() {
+                    if index != 0 {
+                        self.impl_format_str(", ")?;
+                    }
+                    let elem = self
+                        .builder
+                        .build_extract_element(
+                            value.into_vector_value(),
+                            self.context.i32_type().const_int(index as u64, false),
+                            "",
+                        )
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    self.impl_format_value(TypedValue::new(elem, typed.signed), None)?;
+                }
+                self.impl_format_str(">")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_format_print() {
+        let data = r#"
+func main() {
+    let i32 x = 5
+    std::io::println("x = {x}, {:.1}", 2.5)
+}"#;
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [5 x i8] c"x = \00", align 1
+@1 = private unnamed_addr constant [3 x i8] c", \00", align 1
+
+define void @main() {
+entry:
+  %x = alloca i32, align 4
+  store i32 5, ptr %x, align 4
+  %0 = alloca %string, align 8
+  store %string { i64 4, ptr @0 }, ptr %0, align 8
+  call void @__builtin__fmt_str(ptr %0)
+  %1 = load i32, ptr %x, align 4
+  %2 = sext i32 %1 to i64
+  call void @__builtin__fmt_int(i64 %2)
+  %3 = alloca %string, align 8
+  store %string { i64 2, ptr @1 }, ptr %3, align 8
+  call void @__builtin__fmt_str(ptr %3)
+  call void @__builtin__fmt_float(double 2.500000e+00, i32 1)
+  call void @__builtin__fmt_end(i1 true)
+  ret void
+}
+
+declare void @__builtin__fmt_str(ptr)
+
+declare void @__builtin__fmt_int(i64)
+
+declare void @__builtin__fmt_float(double, i32)
+
+declare void @__builtin__fmt_end(i1)
+"#
+        )
+    }
+
+    #[test]
+    fn test_format_print_arg_count() {
+        let data = r#"
+func main() {
+    std::io::println("{} {}", 1)
+}"#;
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+
+    #[test]
+    fn test_format_print_precision_on_int() {
+        let data = r#"
+func main() {
+    std::io::println("{:.2}", 1)
+}"#;
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+}
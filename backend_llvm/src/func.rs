@@ -1,15 +1,78 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use diagnostics::ErrorCode;
 use inkwell::{
     module::Linkage,
     types::{BasicMetadataTypeEnum, BasicType},
-    values::{BasicValueEnum, FunctionValue, InstructionValue},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, InstructionValue},
 };
 use lexer::types::Datatype;
-use parser::nodes::{self, ASTNodes, Return};
+use parser::nodes::{self, ASTNodes, Expression, Param, Return};
+
+use crate::{CodeGen, CodeGenError, entry::symbol_name, ext_defs::StdLibFunc, values::TypedValue};
+
+#[derive(Debug, Default)]
+pub struct FuncDefs {
+    items: RefCell<HashMap<String, FuncData>>,
+}
+
+#[derive(Debug)]
+pub struct FuncData {
+    args: Vec<Datatype>,
+    return_type: Option<Datatype>,
+    /// Defined outside of the program, and called with the C ABI
+    external: bool,
+}
+
+impl FuncDefs {
+    pub(crate) fn add_func(
+        &self,
+        name: &str,
+        args: &[Param],
+        return_type: &Option<Datatype>,
+        external: bool,
+    ) {
+        let args = args.iter().map(|x| x.datatype.clone()).collect::<Vec<_>>();
+        self.items.borrow_mut().insert(
+            name.to_string(),
+            FuncData {
+                args,
+                return_type: return_type.clone(),
+                external,
+            },
+        );
+    }
+
+    /// The parameter and return types of an `extern` function
+    pub(crate) fn get_extern(&self, name: &str) -> Option<(Vec<Datatype>, Option<Datatype>)> {
+        let funcs = self.items.borrow();
+        funcs
+            .get(name)
+            .filter(|x| x.external)
+            .map(|x| (x.args.clone(), x.return_type.clone()))
+    }
+
+    pub(crate) fn is_arg_signed(&self, name: &str, index: usize) -> bool {
+        let funcs = self.items.borrow();
+        funcs
+            .get(name)
+            .and_then(|x| x.args.get(index))
+            .is_some_and(|dt| dt.is_signed())
+    }
 
-use crate::{CodeGen, CodeGenError};
+    pub(crate) fn is_return_signed(&self, name: &str) -> bool {
+        let funcs = self.items.borrow();
+        funcs
+            .get(name)
+            .and_then(|x| x.return_type.as_ref())
+            .is_some_and(|dt| dt.is_signed())
+    }
+}
 
 impl<'ctx> CodeGen<'ctx> {
-    pub(crate) fn impl_function_def(
+    /// Adds the signature of the function to the module, so that it can be called
+    /// before its body is built
+    pub(crate) fn declare_function(
         &self,
         func: &nodes::Function,
     ) -> Result<FunctionValue<'ctx>, CodeGenError> {
@@ -18,32 +81,50 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Function return type
         let func_type = if let Some(rt) = &func.return_type {
-            let llvm_rt = self.parser_to_llvm_dt(&rt);
+            let llvm_rt = self.parser_to_llvm_dt(&rt)?;
             llvm_rt.fn_type(&args, false)
         } else {
             self.context.void_type().fn_type(&args, false)
         };
 
         // Build the function definition
-        let built_func = self.module.add_function(&func.name, func_type, None);
+        let built_func = self.module.add_function(symbol_name(func), func_type, None);
+        self.func_defs
+            .add_func(&func.name, &func.args, &func.return_type, false);
 
         // Set function parameters names
         for (index, arg) in built_func.get_param_iter().enumerate() {
-            arg.set_name(&func.args[index].0);
+            arg.set_name(&func.args[index].name);
         }
+        Ok(built_func)
+    }
 
-        self.codegen_function_block(&func.body, built_func)?;
+    /// Builds the body of a function added by `declare_function`
+    pub(crate) fn impl_function_def(
+        &self,
+        func: &nodes::Function,
+    ) -> Result<FunctionValue<'ctx>, CodeGenError> {
+        let built_func =
+            self.module
+                .get_function(symbol_name(func))
+                .ok_or(CodeGenError::internal(&format!(
+                    "Function `{}` was not declared",
+                    func.name
+                )))?;
+        self.debug_function(func, built_func)?;
+
+        self.codegen_function_block(&func.body, built_func, &func.args, func.line)?;
 
         Ok(built_func)
     }
 
     fn impl_function_args(
         &self,
-        args: &Vec<(String, Datatype)>,
+        args: &Vec<Param>,
     ) -> Result<Vec<BasicMetadataTypeEnum<'ctx>>, CodeGenError> {
         let mut res_args = vec![];
-        for (_, dt) in args {
-            let llvm_dt = self.parser_to_llvm_dt(&dt);
+        for param in args {
+            let llvm_dt = self.parser_to_llvm_dt(&param.datatype)?;
             res_args.push(llvm_dt.into());
         }
         Ok(res_args)
@@ -54,16 +135,19 @@ impl<'ctx> CodeGen<'ctx> {
         built_func: FunctionValue<'ctx>,
         ret: &Return,
     ) -> Result<InstructionValue<'ctx>, CodeGenError> {
-        if let Some(expr) = &ret.value {
-            let ret_val = self.impl_expr(
-                expr,
-                built_func,
-                built_func.get_type().get_return_type().unwrap(),
-            )?;
-            Ok(self.builder.build_return(Some(&ret_val)).unwrap())
-        } else {
-            Ok(self.builder.build_return(None).unwrap())
-        }
+        let ret_val =
+            if let Some(expr) = &ret.value {
+                let ret_type = built_func.get_type().get_return_type().ok_or(CodeGenError::new(
+                ErrorCode::InvalidReturn,
+                "Function does not have an associated return type; it cannot return a value",
+            ))?;
+                Some(self.impl_expr(expr, built_func, ret_type)?)
+            } else {
+                None
+            };
+        self.builder
+            .build_return(ret_val.as_ref().map(|v| v as _))
+            .map_err(CodeGenError::from_llvm_err)
     }
 
     pub(crate) fn impl_function_call(
@@ -71,17 +155,43 @@ impl<'ctx> CodeGen<'ctx> {
         built_func: FunctionValue<'ctx>,
         func_call: &nodes::FunctionCall,
     ) -> Result<Option<BasicValueEnum<'ctx>>, CodeGenError> {
+        match func_call.name.as_str() {
+            "assert" => return self.impl_assert(built_func, func_call).map(|_| None),
+            "unreachable" => return self.impl_unreachable(built_func, func_call).map(|_| None),
+            _ => {}
+        }
         let func = self
             .module
             .get_function(&func_call.name)
-            .ok_or(CodeGenError::new("Function not found"))?;
+            .ok_or(CodeGenError::new(
+                ErrorCode::UnknownFunction,
+                "Function not found",
+            ))?;
+        // Extern functions take aggregates the way C does, which differs from their
+        // LLVM types
+        let signature = match self.func_defs.get_extern(&func_call.name) {
+            Some((params, return_type)) => Some(self.c_signature(&params, return_type.as_ref())?),
+            None => None,
+        };
+        let params = match &signature {
+            Some(signature) => signature.param_types(),
+            None => func.get_type().get_param_types(),
+        };
+        Self::check_arg_count(&func_call.name, params.len(), func_call.args.len())?;
         let mut args = vec![];
-        let params = func.get_type().get_param_types();
-        for (i, arg) in func_call.args.iter().enumerate() {
-            let param = params.get(i).ok_or(CodeGenError::new("Invalid arg"))?;
-            let arg = self.impl_expr(arg, built_func, *param)?;
-            args.push(arg.into());
+        for (arg, param) in func_call.args.iter().zip(&params) {
+            let arg = match self.slice_element_type(*param) {
+                Some(element) if signature.is_none() => {
+                    self.impl_slice_arg(arg, built_func, param.into_struct_type(), element)?
+                }
+                _ => self.impl_expr(arg, built_func, *param)?,
+            };
+            args.push(arg);
         }
+        if let Some(signature) = signature {
+            return self.build_c_call(func, &signature, &args);
+        }
+        let args = args.into_iter().map(|arg| arg.into()).collect::<Vec<_>>();
         let ret_val = self
             .builder
             .build_call(func, &args, "")
@@ -96,74 +206,219 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<Option<BasicValueEnum<'ctx>>, CodeGenError> {
         let path = &call.path.join("__");
 
-        let func_attrs = self
-            .import_resolver
-            .get_extern_function(&path, call.path.first().unwrap());
-        if func_attrs.is_none() {
-            return Err(CodeGenError::new("Import could not be resolved"));
+        let module = call.path.first().map_or("", String::as_str);
+        // `std::panic` is the panic builtin, called with the location of the call
+        if let ASTNodes::FunctionCall(func_call) = &*call.ident
+            && self.import_resolver.get_extern_path(path, module) == "__std__panic"
+        {
+            self.impl_std_panic(built_func, func_call)?;
+            return Ok(None);
         }
-        let (func_attrs, path) = func_attrs.unwrap();
-
-        let func = if let Some(func) = self.module.get_function(&path) {
-            func
-        } else {
-            let func = self
-                .module
-                .add_function(&path, func_attrs.func, Some(Linkage::External));
-            self.execution_engine
-                .as_ref()
-                .map(|exec| exec.add_global_mapping(&func, func_attrs.ptr));
-            func
+        let Some((func_attrs, path)) = self.import_resolver.get_extern_function(path, module)
+        else {
+            return Err(CodeGenError::new(
+                ErrorCode::UnknownModule,
+                "Import could not be resolved",
+            ));
         };
+        if let ASTNodes::FunctionCall(func_call) = &*call.ident
+            && let Some(Expression::String(fmt)) = func_call.args.first()
+            && (path == "__std__io__print" || path == "__std__io__println")
+        {
+            let newline = path == "__std__io__println";
+            self.impl_format_print(built_func, fmt, &func_call.args[1..], newline)?;
+            return Ok(None);
+        }
+        let func = self.declare_stdlib_function(&path, &func_attrs)?;
 
         match &*call.ident {
             ASTNodes::FunctionCall(func_call) => {
                 let mut args = vec![];
                 let params = func.get_type().get_param_types();
-                for (i, arg) in func_call.args.iter().enumerate() {
-                    let param = params.get(i).ok_or(CodeGenError::new("Invalid arg"))?;
-                    let arg = self.impl_expr(arg, built_func, *param)?;
-                    args.push(arg.into());
+                // Fallible functions take a pointer to their ok value as the last argument
+                let expected = params.len() - func_attrs.result.is_some() as usize;
+                Self::check_arg_count(&call.path.join("::"), expected, func_call.args.len())?;
+                for (arg, param) in func_call.args.iter().zip(&params) {
+                    // The stdlib takes strings by pointer
+                    let arg = if let Expression::String(_) = arg {
+                        self.impl_typed_expr(arg, built_func, self.string_type().into())?
+                    } else {
+                        self.impl_typed_expr(arg, built_func, *param)?
+                    };
+                    let value = arg.value;
+                    if Self::is_string_type(value.get_type()) && param.is_pointer_type() {
+                        args.push(self.string_to_ptr(value)?.into());
+                    } else if (value.is_int_value() || value.is_float_value())
+                        && (param.is_int_type() || param.is_float_type())
+                    {
+                        // The stdlib takes signed numbers
+                        args.push(self.impl_cast_expr(arg, *param, true)?.value.into());
+                    } else {
+                        args.push(value.into());
+                    }
                 }
+
+                let out = if let Some(ok_dt) = &func_attrs.result {
+                    let out_ptr = self
+                        .builder
+                        .build_alloca(self.parser_to_llvm_dt(ok_dt)?, "")
+                        .map_err(CodeGenError::from_llvm_err)?;
+                    args.push(out_ptr.into());
+                    Some((ok_dt, out_ptr))
+                } else {
+                    None
+                };
+
                 let ret_val = self
                     .builder
                     .build_call(func, &args, "")
+                    .map_err(CodeGenError::from_llvm_err)?
+                    .try_as_basic_value()
+                    .left();
+
+                let Some((ok_dt, out_ptr)) = out else {
+                    return Ok(ret_val);
+                };
+                let value = self
+                    .builder
+                    .build_load(self.parser_to_llvm_dt(ok_dt)?, out_ptr, "")
                     .map_err(CodeGenError::from_llvm_err)?;
-                Ok(ret_val.try_as_basic_value().left())
+                let result_ty = self.result_type(ok_dt, &Datatype::I32)?;
+                let code = ret_val.ok_or(CodeGenError::internal(&format!(
+                    "`{}` should return an error code",
+                    path
+                )))?;
+                let result =
+                    self.build_result_from_code(result_ty, code.into_int_value(), value)?;
+                Ok(Some(result.into()))
             }
-            _ => todo!(),
+            node => Err(CodeGenError::unsupported(&format!(
+                "Expected a call to `{}`, found {}",
+                call.path.join("::"),
+                node.kind()
+            ))),
+        }
+    }
+
+    fn check_arg_count(name: &str, expected: usize, found: usize) -> Result<(), CodeGenError> {
+        if expected != found {
+            return Err(CodeGenError::new(
+                ErrorCode::WrongArgumentCount,
+                &format!(
+                    "Function `{}` takes {} arguments, but {} were given",
+                    name, expected, found
+                ),
+            ));
         }
+        Ok(())
+    }
+
+    /// Calls a compiler builtin from the stdlib, declaring it on first use
+    pub(crate) fn call_builtin(
+        &self,
+        name: &str,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> Result<Option<BasicValueEnum<'ctx>>, CodeGenError> {
+        let func_attrs =
+            self.import_resolver
+                .get_stdlib_function(name)
+                .ok_or(CodeGenError::new(
+                    ErrorCode::Internal,
+                    &format!("Builtin {} not found", name),
+                ))?;
+        let func = self.declare_stdlib_function(name, &func_attrs)?;
+        Ok(self
+            .builder
+            .build_call(func, args, "")
+            .map_err(CodeGenError::from_llvm_err)?
+            .try_as_basic_value()
+            .left())
+    }
+
+    pub(crate) fn declare_stdlib_function(
+        &self,
+        path: &str,
+        func_attrs: &StdLibFunc<'ctx>,
+    ) -> Result<FunctionValue<'ctx>, CodeGenError> {
+        if let Some(func) = self.module.get_function(path) {
+            return Ok(func);
+        }
+        let func = self
+            .module
+            .add_function(path, func_attrs.func, Some(Linkage::External));
+        if let Some(exec) = self.execution_engine()? {
+            exec.add_global_mapping(&func, func_attrs.ptr);
+        }
+        Ok(func)
     }
 
     pub(crate) fn impl_method_call(
         &self,
         built_func: FunctionValue<'ctx>,
         method: &nodes::Method,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
         let callee = self.resolve_var(built_func, &*method.parent)?;
+        if Self::is_result_type(callee.type_) {
+            let result = self
+                .builder
+                .build_load(callee.type_, callee.ptr, "")
+                .map_err(CodeGenError::from_llvm_err)?;
+            return self.impl_result_method(built_func, result.into_struct_value(), &method.func);
+        }
+        if self.slice_element_type(callee.type_).is_some() {
+            return self.impl_slice_method(callee, &method.func);
+        }
+        if Self::is_string_type(callee.type_) {
+            let string = self
+                .builder
+                .build_load(callee.type_, callee.ptr, "")
+                .map_err(CodeGenError::from_llvm_err)?;
+            return self.impl_string_method(built_func, string.into_struct_value(), &method.func);
+        }
+        if callee.type_.is_int_type() {
+            let int = self
+                .builder
+                .build_load(callee.type_, callee.ptr, "")
+                .map_err(CodeGenError::from_llvm_err)?;
+            return self.impl_int_method(
+                built_func,
+                TypedValue::new(int, callee.signed),
+                &method.func,
+            );
+        }
         self.import_resolver
             .get_builtin_function(callee.type_, &method.func.name)
+            .map(TypedValue::unsigned)
     }
 
+    /// Declares a function defined outside of the program, with its signature lowered
+    /// to the C ABI of the target
     pub(crate) fn impl_extern_call(
         &self,
         ext: &nodes::Extern,
     ) -> Result<FunctionValue<'ctx>, CodeGenError> {
-        let args = self.impl_function_args(&ext.args)?;
-
-        let func_type = if let Some(rt) = &ext.return_type {
-            let llvm_rt = self.parser_to_llvm_dt(&rt);
-            llvm_rt.fn_type(&args, false)
-        } else {
-            self.context.void_type().fn_type(&args, false)
-        };
-
-        let built_func = self
-            .module
-            .add_function(&ext.name, func_type, Some(Linkage::External));
-
-        for (index, arg) in built_func.get_param_iter().enumerate() {
-            arg.set_name(&ext.args[index].0);
+        let params = ext
+            .args
+            .iter()
+            .map(|param| param.datatype.clone())
+            .collect::<Vec<_>>();
+        let signature = self.c_signature(&params, ext.return_type.as_ref())?;
+
+        let built_func =
+            self.module
+                .add_function(&ext.name, signature.fn_type, Some(Linkage::External));
+        for (loc, attribute) in self.c_abi_attributes(&signature)? {
+            built_func.add_attribute(loc, attribute);
+        }
+        self.func_defs
+            .add_func(&ext.name, &ext.args, &ext.return_type, true);
+
+        // The pointer to the return value comes before the parameters
+        let params = built_func
+            .get_param_iter()
+            .skip(signature.has_sret() as usize);
+        for (param, arg) in params.zip(&ext.args) {
+            param.set_name(&arg.name);
         }
 
         Ok(built_func)
@@ -326,6 +581,40 @@ entry:
         )
     }
 
+    #[test]
+    fn test_codegen_call_before_definition() {
+        let data = "
+func main() u32 { return ping(1) }
+func ping(n u32) u32 { return pong(n) }
+func pong(n u32) u32 { return ping(n) }";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+define i32 @main() {
+entry:
+  %0 = call i32 @ping(i32 1)
+  ret i32 %0
+}
+
+define i32 @ping(i32 %n) {
+entry:
+  %0 = call i32 @pong(i32 %n)
+  ret i32 %0
+}
+
+define i32 @pong(i32 %n) {
+entry:
+  %0 = call i32 @ping(i32 %n)
+  ret i32 %0
+}
+"#
+        )
+    }
+
     #[test]
     fn test_imported_func_call() {
         let data = r#"
@@ -342,14 +631,18 @@ func main() u32 {
             r#"; ModuleID = 'main'
 source_filename = "main"
 
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [5 x i8] c"Test\00", align 1
+
 define i32 @main() {
 entry:
-  %0 = alloca [4 x i8], align 1
-  store [4 x i8] c"Test", ptr %0, align 1
-  %1 = alloca { i64, ptr }, align 8
-  %2 = insertvalue { i64, ptr } { i64 4, ptr undef }, ptr %0, 1
-  store { i64, ptr } %2, ptr %1, align 8
-  call void @__std__io__println(ptr %1)
+  %s = alloca %string, align 8
+  store %string { i64 4, ptr @0 }, ptr %s, align 8
+  %s1 = load %string, ptr %s, align 8
+  %0 = alloca %string, align 8
+  store %string %s1, ptr %0, align 8
+  call void @__std__io__println(ptr %0)
   ret i32 0
 }
 
@@ -1,79 +1,180 @@
-use std::{error::Error, fmt::Display};
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+};
 
+use abi::CAbi;
+use debug_info::DebugInfo;
+use diagnostics::{Diagnostic, ErrorCode, Span};
+use entry::takes_program_args;
 use ext_defs::Resolver;
+use func::FuncDefs;
 use inkwell::{
-    OptimizationLevel,
     builder::{Builder, BuilderError},
     context::Context,
     execution_engine::ExecutionEngine,
     module::Module,
+    types::BasicTypeEnum,
 };
 use parser::nodes::ASTNodes;
 use stmt::Variables;
 use structs::StructDefs;
 
+pub use emit::TargetOptions;
+pub use optimize::OptLevel;
+
+mod abi;
 mod block;
 mod cond;
+mod debug_info;
+mod emit;
+mod entry;
 mod expr;
 mod ext_defs;
+mod format;
 mod func;
 mod loops;
 mod ops;
+mod optimize;
+mod panic;
+mod results;
+mod slices;
 mod stmt;
+mod strings;
 mod structs;
 mod utils;
+mod values;
 
 pub struct CodeGen<'ctx> {
     pub context: &'ctx Context,
     pub builder: Builder<'ctx>,
     pub module: Module<'ctx>,
-    pub execution_engine: Option<ExecutionEngine<'ctx>>,
+    /// Created on first use, so that it is built with the optimization level
+    execution_engine: OnceCell<ExecutionEngine<'ctx>>,
+    with_jit: bool,
     pub tokens: Vec<ASTNodes>,
 
     pub struct_defs: StructDefs<'ctx>,
+    pub func_defs: FuncDefs,
     pub var_ptrs: Variables<'ctx>,
     pub import_resolver: Resolver<'ctx>,
+    /// The element types of the slice types, by the name of their struct
+    slice_elements: RefCell<HashMap<String, BasicTypeEnum<'ctx>>>,
+
+    /// Emits runtime checks that panic on arithmetic overflow, division by zero and
+    /// out of bounds array indexing
+    pub runtime_checks: bool,
+    /// The line of the statement being compiled, where runtime checks report panics
+    stmt_line: Cell<usize>,
+    pub opt_level: OptLevel,
+    pub target: TargetOptions,
+    /// Emits DWARF debug information, for debuggers like gdb and lldb
+    pub debug_info: bool,
+    debug: OnceCell<DebugInfo<'ctx>>,
+    c_abi: OnceCell<CAbi>,
 }
 
 impl<'ctx> CodeGen<'ctx> {
     pub fn new(context: &'ctx Context, tokens: Vec<ASTNodes>, with_jit: bool) -> Self {
         let builder = context.create_builder();
         let module = context.create_module("main");
-        let execution_engine = with_jit.then(|| {
-            module
-                .create_jit_execution_engine(OptimizationLevel::None)
-                .unwrap()
-        });
         Self {
             context,
             builder,
             module,
-            execution_engine,
+            execution_engine: OnceCell::new(),
+            with_jit,
             tokens,
 
             struct_defs: StructDefs::default(),
+            func_defs: FuncDefs::default(),
             var_ptrs: Variables::default(),
             import_resolver: Resolver::new(context),
+            slice_elements: RefCell::default(),
+
+            runtime_checks: false,
+            stmt_line: Cell::new(0),
+            opt_level: OptLevel::default(),
+            target: TargetOptions::default(),
+            debug_info: false,
+            debug: OnceCell::new(),
+            c_abi: OnceCell::new(),
         }
     }
 
+    pub fn with_runtime_checks(mut self, enabled: bool) -> Self {
+        self.runtime_checks = enabled;
+        self
+    }
+
+    /// Sets the optimization level of `optimize` and of the JIT
+    pub fn with_opt_level(mut self, level: OptLevel) -> Self {
+        self.opt_level = level;
+        self
+    }
+
+    /// Sets the machine `optimize` and the `write_` functions generate code for
+    pub fn with_target(mut self, target: TargetOptions) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Emits debug information, using the file name of `with_source_file_name`
+    pub fn with_debug_info(mut self, enabled: bool) -> Self {
+        self.debug_info = enabled;
+        self
+    }
+
+    /// Sets the file name used in the module and in the locations of runtime panics
+    pub fn with_source_file_name(self, file_name: &str) -> Self {
+        self.module.set_source_file_name(file_name);
+        self
+    }
+
+    /// Generates the module. Structs and functions are declared before any function
+    /// body is built, so they can be used before their definition.
     pub fn codegen(&self) -> Result<(), CodeGenError> {
-        for node in self.tokens.iter() {
-            match node {
-                ASTNodes::Function(func) => {
-                    self.impl_function_def(func)?;
-                }
-                ASTNodes::StructDef(st) => {
-                    self.def_struct(st)?;
-                }
-                ASTNodes::ImportDef(imp) => {
-                    self.import_resolver.resolve_import_def(imp)?;
-                }
-                ASTNodes::Extern(ext) => {
-                    self.impl_extern_call(ext)?;
+        if self.debug_info {
+            self.init_debug_info()?;
+        }
+        // Structs are created empty first, so their fields can refer to any of them
+        self.for_each_node(|node| match node {
+            ASTNodes::StructDef(st) => self.declare_struct(st).map(|_| ()),
+            _ => Ok(()),
+        })?;
+        self.for_each_node(|node| match node {
+            ASTNodes::Function(func) => self.declare_function(func).map(|_| ()),
+            ASTNodes::StructDef(st) => self.def_struct(st),
+            ASTNodes::ImportDef(imp) => self.import_resolver.resolve_import_def(imp),
+            ASTNodes::Extern(ext) => self.impl_extern_call(ext).map(|_| ()),
+            node => Err(CodeGenError::unsupported(&format!(
+                "Expected a function, struct, import or extern, found {}",
+                node.kind()
+            ))),
+        })?;
+        self.for_each_node(|node| match node {
+            ASTNodes::Function(func) => {
+                let built_func = self.impl_function_def(func)?;
+                // A C `main` is generated to pass the arguments to this one
+                if takes_program_args(func) {
+                    self.build_entry(func, built_func)?;
                 }
-                _ => unreachable!(),
-            };
+                Ok(())
+            }
+            _ => Ok(()),
+        })?;
+        self.finalize_debug_info();
+        Ok(())
+    }
+
+    fn for_each_node(
+        &self,
+        f: impl Fn(&ASTNodes) -> Result<(), CodeGenError>,
+    ) -> Result<(), CodeGenError> {
+        for node in self.tokens.iter() {
+            f(node).map_err(|err| err.at_node(node))?;
         }
         Ok(())
     }
@@ -82,17 +183,46 @@ impl<'ctx> CodeGen<'ctx> {
         self.module.print_to_string().to_string()
     }
 
-    pub fn run_with_jit(&self) -> Option<i32> {
-        let function = self.module.get_function("main").unwrap();
-        let exec_engine = self.execution_engine.as_ref().unwrap();
-        let result = unsafe { exec_engine.run_function_as_main(function, &[]) };
-        return Some(result);
+    /// Runs `main` with the arguments of the program, which start with its name like in
+    /// C. `None` when there is no `main` or the JIT wasn't enabled
+    pub fn run_with_jit(&self, args: &[String]) -> Result<Option<i32>, CodeGenError> {
+        let Some(function) = self.module.get_function("main") else {
+            return Ok(None);
+        };
+        let Some(exec_engine) = self.execution_engine()? else {
+            return Ok(None);
+        };
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let result = unsafe { exec_engine.run_function_as_main(function, &args) };
+        return Ok(Some(result));
+    }
+
+    /// The JIT, or `None` when it wasn't enabled
+    pub(crate) fn execution_engine(
+        &self,
+    ) -> Result<Option<&ExecutionEngine<'ctx>>, CodeGenError> {
+        if !self.with_jit {
+            return Ok(None);
+        }
+        if let Some(exec_engine) = self.execution_engine.get() {
+            return Ok(Some(exec_engine));
+        }
+        let exec_engine = self
+            .module
+            .create_jit_execution_engine(self.opt_level.into())
+            .map_err(|err| CodeGenError::internal(&format!("Cannot create the JIT: {}", err)))?;
+        Ok(Some(self.execution_engine.get_or_init(|| exec_engine)))
     }
 }
 
 #[derive(Debug)]
 pub struct CodeGenError {
+    code: ErrorCode,
     msg: String,
+    /// Where the statement or definition being compiled is, when known
+    span: Option<Span>,
+    /// What that statement or definition is, such as a `let` statement
+    node: Option<&'static str>,
 }
 impl Display for CodeGenError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -101,33 +231,98 @@ impl Display for CodeGenError {
 }
 impl Error for CodeGenError {}
 
+impl From<&CodeGenError> for Diagnostic {
+    fn from(err: &CodeGenError) -> Self {
+        let diag = Diagnostic::error(&err.msg).with_code(err.code);
+        match (err.span, err.node) {
+            (Some(span), Some(kind)) => diag.with_primary(span, &format!("in this {}", kind)),
+            (Some(span), None) => diag.with_primary(span, ""),
+            (None, _) => diag,
+        }
+    }
+}
+
 impl CodeGenError {
-    fn new(msg: &str) -> Self {
+    fn new(code: ErrorCode, msg: &str) -> Self {
         Self {
+            code,
             msg: msg.to_string(),
+            span: None,
+            node: None,
         }
     }
 
     fn from_llvm_err(err: BuilderError) -> Self {
-        Self {
-            msg: err.to_string(),
+        Self::new(ErrorCode::Internal, &err.to_string())
+    }
+
+    /// For constructs that pass the checks but have no code generation
+    fn unsupported(msg: &str) -> Self {
+        Self::new(ErrorCode::Unsupported, msg)
+    }
+
+    fn internal(msg: &str) -> Self {
+        Self::new(ErrorCode::Internal, msg)
+    }
+
+    /// Records the node being compiled. Errors keep the innermost node that has a
+    /// location, so outer nodes don't overwrite it.
+    fn at_node(mut self, node: &ASTNodes) -> Self {
+        if self.span.is_none()
+            && let Some(line) = node.line()
+        {
+            // Nodes only record their line, and statements take up a whole line
+            self.span = Some(Span::line(line));
+            self.node = Some(node.kind());
         }
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// The kind of the statement or definition the error happened in
+    pub fn node(&self) -> Option<&'static str> {
+        self.node
     }
 }
 
+/// Parses the program and runs the passes on it, as the backend expects them to have run
 #[cfg(test)]
-pub(crate) fn get_codegen_for_string(code: &str) -> Result<String, CodeGenError> {
+pub(crate) fn check_program(code: &str) -> Result<Vec<ASTNodes>, CodeGenError> {
     use lexer::lexer::Lexer;
     use parser::Parser;
+    use passes::r#impl::PassManager;
 
-    let context = Context::create();
     let lexer = Lexer::new(code).tokenize();
     let parser = Parser::new(lexer).parse();
     if let Err(err) = parser {
-        return Err(CodeGenError::new(&format!("Failed to parse: {}", err)));
+        return Err(CodeGenError::new(
+            err.code(),
+            &format!("Failed to parse: {}", err),
+        ));
     }
-    let parser = parser.unwrap();
-    let codegen = CodeGen::new(&context, parser, false);
+    let mut parser = parser.unwrap();
+    // Folding would replace the operations these tests check with their results
+    let mut pass_manager = PassManager::new(&mut parser).with_const_folding(false);
+    if let Err(errors) = pass_manager.invoke() {
+        return Err(CodeGenError::new(
+            errors[0].code().unwrap_or(ErrorCode::Internal),
+            &format!("Failed to check: {}", errors[0]),
+        ));
+    }
+    Ok(parser)
+}
+
+#[cfg(test)]
+pub(crate) fn get_codegen_for_string(code: &str) -> Result<String, CodeGenError> {
+    let context = Context::create();
+    let codegen = CodeGen::new(&context, check_program(code)?, false);
     codegen.codegen()?;
     Ok(codegen.ir_as_string())
 }
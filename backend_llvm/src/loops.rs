@@ -1,129 +1,10 @@
+use diagnostics::ErrorCode;
 use inkwell::{basic_block::BasicBlock, values::FunctionValue};
-use lexer::types::Operator;
-use parser::nodes::{self, ASTNodes, Expression};
+use parser::nodes;
 
 use crate::{CodeGen, CodeGenError};
 
 impl<'ctx> CodeGen<'ctx> {
-    pub(crate) fn impl_for_loop_stmt(
-        &self,
-        built_func: FunctionValue<'ctx>,
-        stmt: &nodes::ForLoop,
-    ) -> Result<(), CodeGenError> {
-        let for_init_block = self.context.append_basic_block(built_func, "for_init");
-        let for_body_block = self.context.append_basic_block(built_func, "for_body");
-        let for_cond_block = self.context.append_basic_block(built_func, "for_cond");
-        let cont = self.context.append_basic_block(built_func, "for_cont");
-
-        let index_ptr = self
-            .builder
-            .build_alloca(self.context.i64_type(), &stmt.increment.name)
-            .map_err(CodeGenError::from_llvm_err)?;
-        self.builder
-            .build_store(index_ptr, self.context.i64_type().const_zero())
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        self.var_ptrs.insert(
-            &stmt.increment.name,
-            index_ptr,
-            self.context.i64_type().into(),
-            false,
-        );
-
-        self.builder
-            .build_unconditional_branch(for_init_block)
-            .unwrap();
-        self.builder.position_at_end(for_init_block);
-
-        // For now, we only support using a variable as the iterator
-        let iter = if let Expression::Simple { left, .. } = &stmt.iterator {
-            if let ASTNodes::Variable(var) = left.as_ref() {
-                self.var_ptrs.get(&var.name).unwrap()
-            } else {
-                unimplemented!()
-            }
-        } else {
-            unimplemented!()
-        };
-
-        let iter_type = iter.type_.into_array_type();
-        let iter_inner_type = iter_type.get_element_type();
-
-        let index = self
-            .builder
-            .build_load(self.context.i64_type(), index_ptr, "")
-            .unwrap();
-
-        let is_avail = self.comp_binary_operation(
-            &Operator::LESSER,
-            &index,
-            &self
-                .context
-                .i64_type()
-                .const_int(iter_type.len() as u64, false)
-                .into(),
-        )?;
-
-        let value_instance = unsafe {
-            self.builder
-                .build_in_bounds_gep(
-                    iter_type,
-                    iter.ptr,
-                    &[self.context.i32_type().const_zero(), index.into_int_value()],
-                    "",
-                )
-                .map_err(CodeGenError::from_llvm_err)
-        }?;
-
-        self.var_ptrs.insert(
-            &stmt.value.name,
-            value_instance,
-            iter_inner_type.into(),
-            false,
-        );
-
-        self.builder
-            .build_conditional_branch(is_avail, for_body_block, cont)
-            .unwrap();
-
-        self.codegen_block(&stmt.body, built_func, for_body_block, Some(cont))?;
-
-        self.builder
-            .build_unconditional_branch(for_cond_block)
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        self.builder.position_at_end(for_cond_block);
-
-        let index = self
-            .builder
-            .build_load(self.context.i64_type(), index_ptr, "")
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        let step =
-            stmt.step
-                .as_ref()
-                .map_or(Ok(self.context.i64_type().const_int(1, false)), |s| {
-                    self.impl_expr(&s, built_func, self.context.i64_type().into())
-                        .map(|v| v.into_int_value())
-                })?;
-
-        let new_index = self
-            .builder
-            .build_int_add(index.into_int_value(), step, "")
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        self.builder
-            .build_store(index_ptr, new_index)
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        self.builder
-            .build_unconditional_branch(for_init_block)
-            .map_err(CodeGenError::from_llvm_err)?;
-
-        self.builder.position_at_end(cont);
-        Ok(())
-    }
-
     pub(crate) fn impl_loop_stmt(
         &self,
         built_func: FunctionValue<'ctx>,
@@ -131,17 +12,13 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<(), CodeGenError> {
         let loop_block = self.context.append_basic_block(built_func, "loop");
         let cont = self.context.append_basic_block(built_func, "loop_cont");
-        if stmt.condition.is_some() {
+        if let Some(condition) = &stmt.condition {
             let loop_init = self.context.prepend_basic_block(loop_block, "loop_init");
             self.builder
                 .build_unconditional_branch(loop_init)
                 .map_err(CodeGenError::from_llvm_err)?;
             self.builder.position_at_end(loop_init);
-            let expr = self.impl_expr(
-                stmt.condition.as_ref().unwrap(),
-                built_func,
-                self.context.bool_type().into(),
-            )?;
+            let expr = self.impl_expr(condition, built_func, self.context.bool_type().into())?;
 
             self.builder
                 .build_conditional_branch(expr.into_int_value(), loop_block, cont)
@@ -152,9 +29,13 @@ impl<'ctx> CodeGen<'ctx> {
                 .build_unconditional_branch(loop_init)
                 .map_err(CodeGenError::from_llvm_err)?;
         } else {
-            self.builder.build_unconditional_branch(loop_block).unwrap();
+            self.builder
+                .build_unconditional_branch(loop_block)
+                .map_err(CodeGenError::from_llvm_err)?;
             self.codegen_block(&stmt.body, built_func, loop_block, Some(cont))?;
-            self.builder.build_unconditional_branch(loop_block).unwrap();
+            self.builder
+                .build_unconditional_branch(loop_block)
+                .map_err(CodeGenError::from_llvm_err)?;
         }
 
         self.builder.position_at_end(cont);
@@ -166,8 +47,12 @@ impl<'ctx> CodeGen<'ctx> {
         _built_func: FunctionValue<'ctx>,
         next_block: Option<BasicBlock<'ctx>>,
     ) -> Result<(), CodeGenError> {
+        let next_block = next_block.ok_or(CodeGenError::new(
+            ErrorCode::InvalidStatement,
+            "`break` can only be used inside of a loop",
+        ))?;
         self.builder
-            .build_unconditional_branch(next_block.unwrap())
+            .build_unconditional_branch(next_block)
             .map_err(CodeGenError::from_llvm_err)?;
         Ok(())
     }
@@ -200,7 +85,7 @@ entry:
 loop_init:                                        ; preds = %loop, %entry
   %a1 = load i32, ptr %a, align 4
   %0 = zext i32 %a1 to i64
-  %1 = icmp slt i64 %0, 10
+  %1 = icmp ult i64 %0, 10
   br i1 %1, label %loop, label %loop_cont
 
 loop:                                             ; preds = %loop_init
@@ -242,31 +127,29 @@ entry:
   store i64 0, ptr %a, align 4
   %index = alloca i64, align 8
   store i64 0, ptr %index, align 4
-  br label %for_init
-
-for_init:                                         ; preds = %for_cond, %entry
-  %0 = load i64, ptr %index, align 4
-  %1 = icmp slt i64 %0, 10
-  %2 = getelementptr inbounds [10 x i64], ptr %array, i32 0, i64 %0
-  br i1 %1, label %for_body, label %for_cont
+  br label %loop_init
 
-for_body:                                         ; preds = %for_init
+loop_init:                                        ; preds = %loop, %entry
   %index1 = load i64, ptr %index, align 4
-  %val = load i64, ptr %2, align 4
-  %3 = mul i64 %index1, %val
-  store i64 %3, ptr %a, align 4
-  br label %for_cond
+  %0 = icmp ult i64 %index1, 10
+  br i1 %0, label %loop, label %loop_cont
 
-for_cond:                                         ; preds = %for_body
-  %4 = load i64, ptr %index, align 4
-  %5 = add i64 %4, 1
-  store i64 %5, ptr %index, align 4
-  br label %for_init
+loop:                                             ; preds = %loop_init
+  %index2 = load i64, ptr %index, align 4
+  %index3 = load i64, ptr %index, align 4
+  %1 = getelementptr inbounds [10 x i64], ptr %array, i32 0, i64 %index3
+  %2 = load i64, ptr %1, align 4
+  %3 = mul i64 %index2, %2
+  store i64 %3, ptr %a, align 4
+  %index4 = load i64, ptr %index, align 4
+  %4 = add i64 %index4, 1
+  store i64 %4, ptr %index, align 4
+  br label %loop_init
 
-for_cont:                                         ; preds = %for_init
-  %a2 = load i64, ptr %a, align 4
-  %6 = trunc i64 %a2 to i32
-  ret i32 %6
+loop_cont:                                        ; preds = %loop_init
+  %a5 = load i64, ptr %a, align 4
+  %5 = trunc i64 %a5 to i32
+  ret i32 %5
 }
 "#
         )
@@ -299,7 +182,7 @@ entry:
 
 loop_init:                                        ; preds = %else, %entry
   %a1 = load i64, ptr %a, align 4
-  %0 = icmp slt i64 %a1, 10
+  %0 = icmp ult i64 %a1, 10
   br i1 %0, label %loop, label %loop_cont
 
 loop:                                             ; preds = %loop_init
@@ -350,31 +233,29 @@ entry:
   store i64 0, ptr %a, align 4
   %index = alloca i64, align 8
   store i64 0, ptr %index, align 4
-  br label %for_init
-
-for_init:                                         ; preds = %for_cond, %entry
-  %0 = load i64, ptr %index, align 4
-  %1 = icmp slt i64 %0, 10
-  %2 = getelementptr inbounds [10 x i64], ptr %array, i32 0, i64 %0
-  br i1 %1, label %for_body, label %for_cont
+  br label %loop_init
 
-for_body:                                         ; preds = %for_init
+loop_init:                                        ; preds = %loop, %entry
   %index1 = load i64, ptr %index, align 4
-  %val = load i64, ptr %2, align 4
-  %3 = mul i64 %index1, %val
-  store i64 %3, ptr %a, align 4
-  br label %for_cond
+  %0 = icmp ult i64 %index1, 10
+  br i1 %0, label %loop, label %loop_cont
 
-for_cond:                                         ; preds = %for_body
-  %4 = load i64, ptr %index, align 4
-  %5 = add i64 %4, 2
-  store i64 %5, ptr %index, align 4
-  br label %for_init
+loop:                                             ; preds = %loop_init
+  %index2 = load i64, ptr %index, align 4
+  %index3 = load i64, ptr %index, align 4
+  %1 = getelementptr inbounds [10 x i64], ptr %array, i32 0, i64 %index3
+  %2 = load i64, ptr %1, align 4
+  %3 = mul i64 %index2, %2
+  store i64 %3, ptr %a, align 4
+  %index4 = load i64, ptr %index, align 4
+  %4 = add i64 %index4, 2
+  store i64 %4, ptr %index, align 4
+  br label %loop_init
 
-for_cont:                                         ; preds = %for_init
-  %a2 = load i64, ptr %a, align 4
-  %6 = trunc i64 %a2 to i32
-  ret i32 %6
+loop_cont:                                        ; preds = %loop_init
+  %a5 = load i64, ptr %a, align 4
+  %5 = trunc i64 %a5 to i32
+  ret i32 %5
 }
 "#
         )
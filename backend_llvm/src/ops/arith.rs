@@ -1,4 +1,6 @@
+use diagnostics::ErrorCode;
 use inkwell::values::BasicValueEnum;
+use lexer::types::Operator;
 
 use crate::{CodeGen, CodeGenError};
 
@@ -7,33 +9,40 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
+        line: usize,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
+            if self.runtime_checks {
+                return self
+                    .build_checked_int_op(&Operator::PLUS, left_int, right_int, signed, line)
+                    .map(|v| v.into());
+            }
+            self.builder
                 .build_int_add(left_int, right_int, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_add(left_float, right_float, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_vector_value() && right.is_vector_value() {
             let left_vec = left.into_vector_value();
             let right_vec = right.into_vector_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_int_add(left_vec, right_vec, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for addition"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for addition",
+            ))
         }
     }
 
@@ -41,33 +50,40 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
+        line: usize,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
+            if self.runtime_checks {
+                return self
+                    .build_checked_int_op(&Operator::MINUS, left_int, right_int, signed, line)
+                    .map(|v| v.into());
+            }
+            self.builder
                 .build_int_sub(left_int, right_int, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_sub(left_float, right_float, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_vector_value() && right.is_vector_value() {
             let left_vec = left.into_vector_value();
             let right_vec = right.into_vector_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_int_sub(left_vec, right_vec, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for subtraction"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for subtraction",
+            ))
         }
     }
 
@@ -75,33 +91,40 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
+        line: usize,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
+            if self.runtime_checks {
+                return self
+                    .build_checked_int_op(&Operator::MULTIPLY, left_int, right_int, signed, line)
+                    .map(|v| v.into());
+            }
+            self.builder
                 .build_int_mul(left_int, right_int, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_mul(left_float, right_float, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_vector_value() && right.is_vector_value() {
             let left_vec = left.into_vector_value();
             let right_vec = right.into_vector_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_int_mul(left_vec, right_vec, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for multiplication"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for multiplication",
+            ))
         }
     }
 
@@ -109,33 +132,50 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
+        line: usize,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
-                .build_int_signed_div(left_int, right_int, "")
-                .unwrap()
-                .into())
+            if self.runtime_checks {
+                self.build_zero_divisor_check(&Operator::DIVIDE, right_int, line)?;
+                if signed {
+                    self.build_signed_div_overflow_check(
+                        &Operator::DIVIDE,
+                        left_int,
+                        right_int,
+                        line,
+                    )?;
+                }
+            }
+            let div = if signed {
+                self.builder.build_int_signed_div(left_int, right_int, "")
+            } else {
+                self.builder.build_int_unsigned_div(left_int, right_int, "")
+            };
+            div.map(|v| v.into()).map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_div(left_float, right_float, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_vector_value() && right.is_vector_value() {
             let left_vec = left.into_vector_value();
             let right_vec = right.into_vector_value();
-            Ok(self
-                .builder
-                .build_int_signed_div(left_vec, right_vec, "")
-                .unwrap()
-                .into())
+            let div = if signed {
+                self.builder.build_int_signed_div(left_vec, right_vec, "")
+            } else {
+                self.builder.build_int_unsigned_div(left_vec, right_vec, "")
+            };
+            div.map(|v| v.into()).map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for division"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for division",
+            ))
         }
     }
 
@@ -143,33 +183,50 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
+        line: usize,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
-                .build_int_signed_rem(left_int, right_int, "")
-                .unwrap()
-                .into())
+            if self.runtime_checks {
+                self.build_zero_divisor_check(&Operator::MODULO, right_int, line)?;
+                if signed {
+                    self.build_signed_div_overflow_check(
+                        &Operator::MODULO,
+                        left_int,
+                        right_int,
+                        line,
+                    )?;
+                }
+            }
+            let rem = if signed {
+                self.builder.build_int_signed_rem(left_int, right_int, "")
+            } else {
+                self.builder.build_int_unsigned_rem(left_int, right_int, "")
+            };
+            rem.map(|v| v.into()).map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_rem(left_float, right_float, "")
-                .unwrap()
-                .into())
+                .map(|v| v.into())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_vector_value() && right.is_vector_value() {
             let left_vec = left.into_vector_value();
             let right_vec = right.into_vector_value();
-            Ok(self
-                .builder
-                .build_int_signed_rem(left_vec, right_vec, "")
-                .unwrap()
-                .into())
+            let rem = if signed {
+                self.builder.build_int_signed_rem(left_vec, right_vec, "")
+            } else {
+                self.builder.build_int_unsigned_rem(left_vec, right_vec, "")
+            };
+            rem.map(|v| v.into()).map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for modulo"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for modulo",
+            ))
         }
     }
 }
@@ -1,3 +1,4 @@
+use diagnostics::ErrorCode;
 use inkwell::values::BasicValueEnum;
 
 use crate::{CodeGen, CodeGenError};
@@ -10,6 +11,7 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if !(left.is_int_value() && right.is_int_value()) && !(left.is_vector_value() && right.is_vector_value()) {
             return Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
                 "Bitwise operations can only be performed on integers",
             ));
         }
@@ -26,6 +28,7 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if !(left.is_int_value() && right.is_int_value()) && !(left.is_vector_value() && right.is_vector_value()) {
             return Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
                 "Bitwise operations can only be performed on integers",
             ));
         }
@@ -42,6 +45,7 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if !(left.is_int_value() && right.is_int_value()) && !(left.is_vector_value() && right.is_vector_value()) {
             return Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
                 "Bitwise operations can only be performed on integers",
             ));
         }
@@ -58,6 +62,7 @@ impl<'ctx> CodeGen<'ctx> {
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if !(left.is_int_value() && right.is_int_value()) && !(left.is_vector_value() && right.is_vector_value()) {
             return Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
                 "Bitwise operations can only be performed on integers",
             ));
         }
@@ -72,14 +77,16 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
     ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
         if !(left.is_int_value() && right.is_int_value()) && !(left.is_vector_value() && right.is_vector_value()) {
             return Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
                 "Bitwise operations can only be performed on integers",
             ));
         }
         self.builder
-            .build_right_shift(left.into_int_value(), right.into_int_value(), false, "")
+            .build_right_shift(left.into_int_value(), right.into_int_value(), signed, "")
             .map_err(CodeGenError::from_llvm_err)
             .map(|op| op.into())
     }
@@ -0,0 +1,362 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    IntPredicate,
+    intrinsics::Intrinsic,
+    types::IntType,
+    values::{BasicValueEnum, FunctionValue, IntValue, StructValue},
+};
+use lexer::types::{Datatype, Operator};
+use parser::nodes;
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+impl<'ctx> CodeGen<'ctx> {
+    fn get_int_intrinsic(
+        &self,
+        name: &str,
+        int_type: IntType<'ctx>,
+    ) -> Result<FunctionValue<'ctx>, CodeGenError> {
+        Intrinsic::find(name)
+            .and_then(|intrinsic| intrinsic.get_declaration(&self.module, &[int_type.into()]))
+            .ok_or(CodeGenError::new(
+                ErrorCode::Internal,
+                &format!("Intrinsic {} not found", name),
+            ))
+    }
+
+    fn call_int_intrinsic(
+        &self,
+        name: &str,
+        left: IntValue<'ctx>,
+        right: IntValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let intrinsic = self.get_int_intrinsic(name, left.get_type())?;
+        self.builder
+            .build_call(intrinsic, &[left.into(), right.into()], "")
+            .map_err(CodeGenError::from_llvm_err)?
+            .try_as_basic_value()
+            .left()
+            .ok_or(CodeGenError::internal(&format!(
+                "`{}` returned no value",
+                name
+            )))
+    }
+
+    /// Calls `llvm.{s,u}{op}.with.overflow`, returning the wrapped value and whether it overflowed
+    fn build_overflow_op(
+        &self,
+        op: &str,
+        left: IntValue<'ctx>,
+        right: IntValue<'ctx>,
+        signed: bool,
+    ) -> Result<(IntValue<'ctx>, IntValue<'ctx>), CodeGenError> {
+        let name = format!(
+            "llvm.{}{}.with.overflow",
+            if signed { "s" } else { "u" },
+            op
+        );
+        let result = self
+            .call_int_intrinsic(&name, left, right)?
+            .into_struct_value();
+        let extract = |index| {
+            self.builder
+                .build_extract_value(result, index, "")
+                .map(|v| v.into_int_value())
+                .map_err(CodeGenError::from_llvm_err)
+        };
+        Ok((extract(0)?, extract(1)?))
+    }
+
+    /// Integer addition, subtraction or multiplication that panics on overflow
+    pub(crate) fn build_checked_int_op(
+        &self,
+        op: &Operator,
+        left: IntValue<'ctx>,
+        right: IntValue<'ctx>,
+        signed: bool,
+        line: usize,
+    ) -> Result<IntValue<'ctx>, CodeGenError> {
+        let (intrinsic, verb) = match op {
+            Operator::PLUS => ("add", "add"),
+            Operator::MINUS => ("sub", "subtract"),
+            Operator::MULTIPLY => ("mul", "multiply"),
+            _ => {
+                return Err(CodeGenError::internal(&format!(
+                    "`{:?}` has no overflow check",
+                    op
+                )));
+            }
+        };
+        let (value, overflow) = self.build_overflow_op(intrinsic, left, right, signed)?;
+        self.build_panic_if(overflow, || {
+            self.build_panic(&format!("attempt to {} with overflow", verb), line)
+        })?;
+        Ok(value)
+    }
+
+    /// Panics if the divisor of a division or remainder is zero
+    pub(crate) fn build_zero_divisor_check(
+        &self,
+        op: &Operator,
+        divisor: IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), CodeGenError> {
+        let is_zero = self
+            .builder
+            .build_int_compare(
+                IntPredicate::EQ,
+                divisor,
+                divisor.get_type().const_zero(),
+                "",
+            )
+            .map_err(CodeGenError::from_llvm_err)?;
+        let msg = match op {
+            Operator::DIVIDE => "attempt to divide by zero",
+            _ => "attempt to calculate the remainder with a divisor of zero",
+        };
+        self.build_panic_if(is_zero, || self.build_panic(msg, line))
+    }
+
+    /// Panics if a signed division or remainder overflows, which only happens when the
+    /// smallest value of the type is divided by -1
+    pub(crate) fn build_signed_div_overflow_check(
+        &self,
+        op: &Operator,
+        dividend: IntValue<'ctx>,
+        divisor: IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), CodeGenError> {
+        let int_type = dividend.get_type();
+        let min = int_type.const_int(1 << (int_type.get_bit_width() - 1), false);
+        let compare = |value, expected| {
+            self.builder
+                .build_int_compare(IntPredicate::EQ, value, expected, "")
+                .map_err(CodeGenError::from_llvm_err)
+        };
+        let overflow = self
+            .builder
+            .build_and(
+                compare(dividend, min)?,
+                compare(divisor, int_type.const_all_ones())?,
+                "",
+            )
+            .map_err(CodeGenError::from_llvm_err)?;
+        let msg = match op {
+            Operator::DIVIDE => "attempt to divide with overflow",
+            _ => "attempt to calculate the remainder with overflow",
+        };
+        self.build_panic_if(overflow, || self.build_panic(msg, line))
+    }
+
+    /// The `wrapping_*`, `saturating_*` and `checked_*` methods on integers, which
+    /// behave the same with and without runtime checks
+    pub(crate) fn impl_int_method(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        int: TypedValue<'ctx>,
+        method: &nodes::FunctionCall,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        let int_type = int.value.get_type().into_int_type();
+        let (kind, op) = method.name.split_once('_').unwrap_or(("", ""));
+        let [arg] = method.args.as_slice() else {
+            return Err(CodeGenError::new(
+                ErrorCode::UnknownMethod,
+                &format!(
+                    "No method `{}` taking {} arguments on integers",
+                    method.name,
+                    method.args.len()
+                ),
+            ));
+        };
+        let arg = self.impl_typed_expr(arg, built_func, int_type.into())?;
+        if !arg.value.is_int_value() {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                "Mismatched types; expected an integer",
+            ));
+        }
+        let arg = self.impl_cast_expr(arg, int_type.into(), int.signed)?;
+        let (left, right) = (int.value.into_int_value(), arg.value.into_int_value());
+
+        let value = match (kind, op) {
+            ("wrapping", "add") => self.builder.build_int_add(left, right, ""),
+            ("wrapping", "sub") => self.builder.build_int_sub(left, right, ""),
+            ("wrapping", "mul") => self.builder.build_int_mul(left, right, ""),
+            ("saturating", "add" | "sub") => {
+                let name = format!("llvm.{}{}.sat", if int.signed { "s" } else { "u" }, op);
+                return Ok(TypedValue::new(
+                    self.call_int_intrinsic(&name, left, right)?,
+                    int.signed,
+                ));
+            }
+            ("checked", "add" | "sub" | "mul") => {
+                let (value, overflow) = self.build_overflow_op(op, left, right, int.signed)?;
+                return self
+                    .build_checked_result(value, overflow, int.signed)
+                    .map(|v| TypedValue::unsigned(v.into()));
+            }
+            _ => {
+                return Err(CodeGenError::new(
+                    ErrorCode::UnknownMethod,
+                    &format!("No method `{}` on integers", method.name),
+                ));
+            }
+        }
+        .map_err(CodeGenError::from_llvm_err)?;
+        Ok(TypedValue::new(value.into(), int.signed))
+    }
+
+    /// Builds the `Result<T, i32>` of a checked operation, which is `err(1)` on overflow
+    fn build_checked_result(
+        &self,
+        value: IntValue<'ctx>,
+        overflow: IntValue<'ctx>,
+        signed: bool,
+    ) -> Result<StructValue<'ctx>, CodeGenError> {
+        let ok_dt = match (value.get_type().get_bit_width(), signed) {
+            (8, false) => Datatype::U8,
+            (16, false) => Datatype::U16,
+            (32, false) => Datatype::U32,
+            (64, false) => Datatype::U64,
+            (8, true) => Datatype::I8,
+            (16, true) => Datatype::I16,
+            (32, true) => Datatype::I32,
+            (64, true) => Datatype::I64,
+            _ => {
+                return Err(CodeGenError::new(
+                    ErrorCode::UnknownMethod,
+                    "Checked operations need a sized integer",
+                ));
+            }
+        };
+        let result_ty = self.result_type(&ok_dt, &Datatype::I32)?;
+        let code = self
+            .builder
+            .build_int_z_extend(overflow, self.context.i32_type(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.build_result_from_code(result_ty, code, value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+
+    use crate::CodeGen;
+
+    #[test]
+    fn test_wrapping_add_method() {
+        let data = "func main() u8 {
+    let u8 a = 200
+    return a.wrapping_add(100)
+}";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+define i8 @main() {
+entry:
+  %a = alloca i8, align 1
+  store i8 -56, ptr %a, align 1
+  %0 = load i8, ptr %a, align 1
+  %1 = add i8 %0, 100
+  ret i8 %1
+}
+"#
+        )
+    }
+
+    #[test]
+    fn test_unknown_int_method() {
+        let data = "func main() u8 {
+    let u8 a = 200
+    return a.rotating_add(100)
+}";
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+
+    #[test]
+    fn test_runtime_overflow_check() {
+        let data = "func main(a u8, b u8) u8 {
+    return a + b
+}";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_runtime_checks(true);
+        codegen.codegen().unwrap();
+
+        assert_eq!(
+            codegen.ir_as_string(),
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [29 x i8] c"attempt to add with overflow\00", align 1
+@1 = private unnamed_addr constant [7 x i8] c"main:2\00", align 1
+
+define i8 @main(i8 %a, i8 %b) {
+entry:
+  %0 = call { i8, i1 } @llvm.uadd.with.overflow.i8(i8 %a, i8 %b)
+  %1 = extractvalue { i8, i1 } %0, 0
+  %2 = extractvalue { i8, i1 } %0, 1
+  br i1 %2, label %panic, label %panic_cont
+
+panic:                                            ; preds = %entry
+  %3 = alloca %string, align 8
+  store %string { i64 28, ptr @0 }, ptr %3, align 8
+  %4 = alloca %string, align 8
+  store %string { i64 6, ptr @1 }, ptr %4, align 8
+  call void @__builtin__panic(ptr %3, ptr %4)
+  unreachable
+
+panic_cont:                                       ; preds = %entry
+  ret i8 %1
+}
+
+; Function Attrs: nocallback nofree nosync nounwind speculatable willreturn memory(none)
+declare { i8, i1 } @llvm.uadd.with.overflow.i8(i8, i8) #0
+
+; Function Attrs: noreturn
+declare void @__builtin__panic(ptr, ptr) #1
+
+attributes #0 = { nocallback nofree nosync nounwind speculatable willreturn memory(none) }
+attributes #1 = { noreturn }
+"#
+        )
+    }
+
+    #[test]
+    fn test_runtime_signed_div_check() {
+        let data = "func main(a i32, b i32) i32 {
+    return a / b
+}";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_runtime_checks(true);
+        codegen.codegen().unwrap();
+        let ir = codegen.ir_as_string();
+
+        assert!(ir.contains("icmp eq i32 %a, -2147483648"));
+        assert!(ir.contains("icmp eq i32 %b, -1"));
+        assert!(ir.contains(r#"c"attempt to divide with overflow\00""#));
+        assert!(ir.contains(r#"c"main:2\00""#));
+        assert!(ir.contains("sdiv i32 %a, %b"));
+    }
+
+    #[test]
+    fn test_unsigned_div_has_no_overflow_check() {
+        let data = "func main(a u32, b u32) u32 {
+    return a / b
+}";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_runtime_checks(true);
+        codegen.codegen().unwrap();
+
+        assert!(!codegen.ir_as_string().contains("with overflow"));
+    }
+}
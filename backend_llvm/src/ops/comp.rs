@@ -1,3 +1,4 @@
+use diagnostics::ErrorCode;
 use inkwell::values::{BasicValueEnum, IntValue};
 use lexer::types::Operator;
 
@@ -9,39 +10,61 @@ impl<'ctx> CodeGen<'ctx> {
         op: &Operator,
         left: &BasicValueEnum<'ctx>,
         right: &BasicValueEnum<'ctx>,
+        signed: bool,
     ) -> Result<IntValue<'ctx>, CodeGenError> {
-        let (ip, fp) = self.ops_to_llvm_predicate(op);
+        let (ip, fp) = self.ops_to_llvm_predicate(op, signed)?;
         if left.is_int_value() && right.is_int_value() {
             let left_int = left.into_int_value();
             let right_int = right.into_int_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_int_compare(ip, left_int, right_int, "")
-                .unwrap())
+                .map_err(CodeGenError::from_llvm_err)
         } else if left.is_float_value() && right.is_float_value() {
             let left_float = left.into_float_value();
             let right_float = right.into_float_value();
-            Ok(self
-                .builder
+            self.builder
                 .build_float_compare(fp, left_float, right_float, "")
-                .unwrap())
+                .map_err(CodeGenError::from_llvm_err)
         } else {
-            Err(CodeGenError::new("Invalid operands for comparison"))
+            Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                "Invalid operands for comparison",
+            ))
         }
     }
 
     pub(crate) fn ops_to_llvm_predicate(
         &self,
         op: &Operator,
-    ) -> (inkwell::IntPredicate, inkwell::FloatPredicate) {
-        match op {
+        signed: bool,
+    ) -> Result<(inkwell::IntPredicate, inkwell::FloatPredicate), CodeGenError> {
+        let pick = |s, u| if signed { s } else { u };
+        let predicates = match op {
             Operator::EQUAL => (inkwell::IntPredicate::EQ, inkwell::FloatPredicate::OEQ),
             Operator::NOT_EQUAL => (inkwell::IntPredicate::NE, inkwell::FloatPredicate::ONE),
-            Operator::GREATER => (inkwell::IntPredicate::SGT, inkwell::FloatPredicate::OGT),
-            Operator::LESSER => (inkwell::IntPredicate::SLT, inkwell::FloatPredicate::OLT),
-            Operator::GREATER_EQUAL => (inkwell::IntPredicate::SGE, inkwell::FloatPredicate::OGE),
-            Operator::LESSER_EQUAL => (inkwell::IntPredicate::SLE, inkwell::FloatPredicate::UEQ),
-            _ => todo!("Binary operator {:?} not implemented", op),
-        }
+            Operator::GREATER => (
+                pick(inkwell::IntPredicate::SGT, inkwell::IntPredicate::UGT),
+                inkwell::FloatPredicate::OGT,
+            ),
+            Operator::LESSER => (
+                pick(inkwell::IntPredicate::SLT, inkwell::IntPredicate::ULT),
+                inkwell::FloatPredicate::OLT,
+            ),
+            Operator::GREATER_EQUAL => (
+                pick(inkwell::IntPredicate::SGE, inkwell::IntPredicate::UGE),
+                inkwell::FloatPredicate::OGE,
+            ),
+            Operator::LESSER_EQUAL => (
+                pick(inkwell::IntPredicate::SLE, inkwell::IntPredicate::ULE),
+                inkwell::FloatPredicate::OLE,
+            ),
+            _ => {
+                return Err(CodeGenError::internal(&format!(
+                    "`{:?}` is not a comparison operator",
+                    op
+                )));
+            }
+        };
+        Ok(predicates)
     }
 }
@@ -1,3 +1,4 @@
 mod arith;
 mod bitwise;
+mod checked;
 mod comp;
@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+
+use inkwell::{OptimizationLevel, passes::PassBuilderOptions};
+
+use crate::{CodeGen, CodeGenError};
+
+/// How much the module is optimized, like the `-O` flags of clang
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptLevel {
+    #[default]
+    O0,
+    O1,
+    O2,
+    O3,
+    /// Optimize for size
+    Os,
+}
+
+impl OptLevel {
+    /// The new pass manager pipeline that runs for this level
+    fn pipeline(&self) -> &'static str {
+        match self {
+            OptLevel::O0 => "default<O0>",
+            OptLevel::O1 => "default<O1>",
+            OptLevel::O2 => "default<O2>",
+            OptLevel::O3 => "default<O3>",
+            OptLevel::Os => "default<Os>",
+        }
+    }
+}
+
+impl From<OptLevel> for OptimizationLevel {
+    fn from(level: OptLevel) -> Self {
+        match level {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+        }
+    }
+}
+
+impl Display for OptLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self {
+            OptLevel::O0 => "0",
+            OptLevel::O1 => "1",
+            OptLevel::O2 => "2",
+            OptLevel::O3 => "3",
+            OptLevel::Os => "s",
+        };
+        write!(f, "O{}", level)
+    }
+}
+
+/// Parses the part after `-O`, such as `2` or `s`
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(OptLevel::O0),
+            "1" => Ok(OptLevel::O1),
+            "2" => Ok(OptLevel::O2),
+            "3" => Ok(OptLevel::O3),
+            "s" => Ok(OptLevel::Os),
+            _ => Err(format!(
+                "Unknown optimization level `{}`, expected 0, 1, 2, 3 or s",
+                s
+            )),
+        }
+    }
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Verifies the generated module and runs the pass pipeline of the optimization
+    /// level on it. The optimized module is verified again.
+    pub fn optimize(&self) -> Result<(), CodeGenError> {
+        self.verify()?;
+        if self.opt_level == OptLevel::O0 {
+            return Ok(());
+        }
+
+        let machine = self.target_machine()?;
+        self.module
+            .run_passes(
+                self.opt_level.pipeline(),
+                &machine,
+                PassBuilderOptions::create(),
+            )
+            .map_err(|err| {
+                CodeGenError::internal(&format!("Failed to optimize the module: {}", err))
+            })?;
+        self.verify()
+    }
+
+    fn verify(&self) -> Result<(), CodeGenError> {
+        self.module
+            .verify()
+            .map_err(|err| CodeGenError::internal(&format!("Generated an invalid module: {}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+    use passes::r#impl::PassManager;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_opt_level() {
+        assert_eq!("2".parse::<OptLevel>(), Ok(OptLevel::O2));
+        assert_eq!("s".parse::<OptLevel>(), Ok(OptLevel::Os));
+        assert!("4".parse::<OptLevel>().is_err());
+        assert_eq!(OptLevel::O3.to_string(), "O3");
+    }
+
+    #[test]
+    fn test_optimize_removes_allocas() {
+        let data = "func fib(num i64) i64 {
+    if 3 > num {
+        return 1
+    }
+    let i64 prev = num - 1
+    return fib(prev) + fib(num - 2)
+}";
+        let context = Context::create();
+        let mut tokens = Parser::new(Lexer::new(data).tokenize()).parse().unwrap();
+        PassManager::new(&mut tokens).invoke().unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_opt_level(OptLevel::O2);
+        codegen.codegen().unwrap();
+        assert!(codegen.ir_as_string().contains("alloca"));
+
+        codegen.optimize().unwrap();
+        assert!(!codegen.ir_as_string().contains("alloca"));
+    }
+}
@@ -0,0 +1,306 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    IntPredicate,
+    attributes::{Attribute, AttributeLoc},
+    types::BasicType,
+    values::{BasicMetadataValueEnum, FunctionValue, IntValue},
+};
+use parser::nodes;
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+impl<'ctx> CodeGen<'ctx> {
+    /// Calls one of the stdlib panic routines, marking it `noreturn`, and ends the block
+    fn call_panic_builtin(
+        &self,
+        name: &str,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> Result<(), CodeGenError> {
+        let func_attrs =
+            self.import_resolver
+                .get_stdlib_function(name)
+                .ok_or(CodeGenError::new(
+                    ErrorCode::Internal,
+                    &format!("Builtin {} not found", name),
+                ))?;
+        let func = self.declare_stdlib_function(name, &func_attrs)?;
+        let noreturn = self
+            .context
+            .create_enum_attribute(Attribute::get_named_enum_kind_id("noreturn"), 0);
+        func.add_attribute(AttributeLoc::Function, noreturn);
+
+        self.builder
+            .build_call(func, args, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_unreachable()
+            .map_err(CodeGenError::from_llvm_err)?;
+        Ok(())
+    }
+
+    fn current_function(&self) -> Result<FunctionValue<'ctx>, CodeGenError> {
+        self.builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or(CodeGenError::internal(
+                "The builder isn't positioned in a function",
+            ))
+    }
+
+    fn source_file_name(&self) -> String {
+        self.module
+            .get_source_file_name()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// The `file:line` of a node. Lines are counted from zero by the lexer.
+    fn source_location(&self, line: usize) -> String {
+        format!("{}:{}", self.source_file_name(), line + 1)
+    }
+
+    /// Calls the stdlib panic routine with `msg` and the location of `line`
+    pub(crate) fn build_panic(&self, msg: &str, line: usize) -> Result<(), CodeGenError> {
+        let msg = self.impl_string_literal(msg)?;
+        let location = self.impl_string_literal(&self.source_location(line))?;
+        self.call_panic_builtin(
+            "__builtin__panic",
+            &[
+                self.string_to_ptr(msg)?.into(),
+                self.string_to_ptr(location)?.into(),
+            ],
+        )
+    }
+
+    /// Runs `build_panic` in a new block if `cond` is true, otherwise continues in another one
+    pub(crate) fn build_panic_if(
+        &self,
+        cond: IntValue<'ctx>,
+        build_panic: impl FnOnce() -> Result<(), CodeGenError>,
+    ) -> Result<(), CodeGenError> {
+        let built_func = self.current_function()?;
+        let panic_block = self.context.append_basic_block(built_func, "panic");
+        let cont_block = self.context.append_basic_block(built_func, "panic_cont");
+        self.builder
+            .build_conditional_branch(cond, panic_block, cont_block)
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.builder.position_at_end(panic_block);
+        build_panic()?;
+        self.builder.position_at_end(cont_block);
+        Ok(())
+    }
+
+    /// Panics if `index` is past the end of an array, vector or slice of `len` elements,
+    /// an `i64`. Negative signed indexes wrap around to huge unsigned ones, so they are
+    /// caught as well
+    pub(crate) fn build_bounds_check(
+        &self,
+        index: TypedValue<'ctx>,
+        len: IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), CodeGenError> {
+        let i64_type = self.context.i64_type();
+        let index = self
+            .impl_cast_expr(index, i64_type.as_basic_type_enum(), false)?
+            .value
+            .into_int_value();
+        let out_of_bounds = self
+            .builder
+            .build_int_compare(IntPredicate::UGE, index, len, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.build_panic_if(out_of_bounds, || {
+            let location = self.impl_string_literal(&self.source_location(line))?;
+            self.call_panic_builtin(
+                "__builtin__panic_bounds",
+                &[
+                    len.into(),
+                    index.into(),
+                    self.string_to_ptr(location)?.into(),
+                ],
+            )
+        })
+    }
+
+    /// Calls `name` with a message string evaluated at runtime and the location of `line`
+    fn build_panic_at(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        name: &str,
+        msg: &nodes::Expression,
+        line: usize,
+    ) -> Result<(), CodeGenError> {
+        let msg = self.impl_expr(msg, built_func, self.string_type().into())?;
+        if !Self::is_string_type(msg.get_type()) {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                "Mismatched types; expected a string",
+            ));
+        }
+        let location = self.impl_string_literal(&self.source_location(line))?;
+        self.call_panic_builtin(
+            name,
+            &[
+                self.string_to_ptr(msg)?.into(),
+                self.string_to_ptr(location)?.into(),
+            ],
+        )
+    }
+
+    /// `std::panic(msg)`
+    pub(crate) fn impl_std_panic(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        call: &nodes::FunctionCall,
+    ) -> Result<(), CodeGenError> {
+        let [msg] = call.args.as_slice() else {
+            return Err(CodeGenError::new(
+                ErrorCode::WrongArgumentCount,
+                "`std::panic` takes a message",
+            ));
+        };
+        self.build_panic_at(built_func, "__builtin__panic", msg, call.line)
+    }
+
+    /// `assert(cond)` or `assert(cond, msg)`, which panics if `cond` is false
+    pub(crate) fn impl_assert(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        call: &nodes::FunctionCall,
+    ) -> Result<(), CodeGenError> {
+        let default_msg = nodes::Expression::String("assertion failed".to_string());
+        let (cond, msg) = match call.args.as_slice() {
+            [cond] => (cond, &default_msg),
+            [cond, msg] => (cond, msg),
+            _ => {
+                return Err(CodeGenError::new(
+                    ErrorCode::WrongArgumentCount,
+                    "`assert` takes a condition and an optional message",
+                ));
+            }
+        };
+        let cond = self.impl_expr(cond, built_func, self.context.bool_type().into())?;
+        if !cond.is_int_value() || cond.into_int_value().get_type().get_bit_width() != 1 {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                "Mismatched types; expected a bool",
+            ));
+        }
+        let failed = self
+            .builder
+            .build_not(cond.into_int_value(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.build_panic_if(failed, || {
+            self.build_panic_at(built_func, "__builtin__panic", msg, call.line)
+        })
+    }
+
+    /// `unreachable()`, which always panics
+    pub(crate) fn impl_unreachable(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        call: &nodes::FunctionCall,
+    ) -> Result<(), CodeGenError> {
+        if !call.args.is_empty() {
+            return Err(CodeGenError::new(
+                ErrorCode::WrongArgumentCount,
+                "`unreachable` takes no arguments",
+            ));
+        }
+        self.build_panic_at(
+            built_func,
+            "__builtin__panic",
+            &nodes::Expression::String("entered unreachable code".to_string()),
+            call.line,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+
+    use crate::CodeGen;
+
+    #[test]
+    fn test_assert() {
+        let data = "func main(a u32) u32 {
+    assert(a < 10, \"too big\")
+    return a
+}";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [8 x i8] c"too big\00", align 1
+@1 = private unnamed_addr constant [7 x i8] c"main:2\00", align 1
+
+define i32 @main(i32 %a) {
+entry:
+  %0 = zext i32 %a to i64
+  %1 = icmp ult i64 %0, 10
+  %2 = xor i1 %1, true
+  br i1 %2, label %panic, label %panic_cont
+
+panic:                                            ; preds = %entry
+  %3 = alloca %string, align 8
+  store %string { i64 7, ptr @0 }, ptr %3, align 8
+  %4 = alloca %string, align 8
+  store %string { i64 6, ptr @1 }, ptr %4, align 8
+  call void @__builtin__panic(ptr %3, ptr %4)
+  unreachable
+
+panic_cont:                                       ; preds = %entry
+  ret i32 %a
+}
+
+; Function Attrs: noreturn
+declare void @__builtin__panic(ptr, ptr) #0
+
+attributes #0 = { noreturn }
+"#
+        )
+    }
+
+    #[test]
+    fn test_code_after_panic() {
+        let data = "func main(a u32) u32 {
+    if a > 1 {
+        std::panic(\"too big\")
+        return 1
+    }
+    unreachable()
+    return 0
+}";
+        let context = Context::create();
+        let codegen = CodeGen::new(&context, crate::check_program(data).unwrap(), false);
+        codegen.codegen().unwrap();
+        codegen.optimize().unwrap();
+        assert!(!codegen.ir_as_string().contains("ret i32"));
+    }
+
+    #[test]
+    fn test_std_panic_without_message() {
+        let data = "func main() u32 {
+    std::panic()
+    return 0
+}";
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+
+    #[test]
+    fn test_unreachable_with_args() {
+        let data = "func main() u32 {
+    unreachable(1)
+    return 0
+}";
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+}
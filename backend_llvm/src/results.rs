@@ -0,0 +1,356 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    IntPredicate,
+    types::{BasicTypeEnum, StructType},
+    values::{BasicValueEnum, FunctionValue, IntValue, StructValue},
+};
+use lexer::types::Datatype;
+use parser::nodes::{self, ASTNodes};
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+// A Result<T, E> is lowered to a named struct `{ i1 is_err, T ok, E err }`.
+const RESULT_IS_ERR: u32 = 0;
+const RESULT_OK: u32 = 1;
+const RESULT_ERR: u32 = 2;
+
+impl<'ctx> CodeGen<'ctx> {
+    pub(crate) fn result_type(
+        &self,
+        ok: &Datatype,
+        err: &Datatype,
+    ) -> Result<StructType<'ctx>, CodeGenError> {
+        let name = Datatype::RESULT(Box::new(ok.clone()), Box::new(err.clone())).to_string();
+        if let Some(result_ty) = self.context.get_struct_type(&name) {
+            return Ok(result_ty);
+        }
+
+        let result_ty = self.context.opaque_struct_type(&name);
+        result_ty.set_body(
+            &[
+                self.context.bool_type().into(),
+                self.parser_to_llvm_dt(ok)?,
+                self.parser_to_llvm_dt(err)?,
+            ],
+            false,
+        );
+        Ok(result_ty)
+    }
+
+    pub(crate) fn is_result_type(dt: BasicTypeEnum<'ctx>) -> bool {
+        matches!(dt, BasicTypeEnum::StructType(st) if st
+            .get_name()
+            .is_some_and(|name| name.to_bytes().starts_with(b"Result<")))
+    }
+
+    /// Whether the ok value of a Result is signed. Its type name is derived from
+    /// the source types, so a signed integer ok type starts with `i`.
+    fn is_result_ok_signed(result_ty: StructType<'ctx>) -> bool {
+        result_ty
+            .get_name()
+            .is_some_and(|name| name.to_bytes().starts_with(b"Result<i"))
+    }
+
+    pub(crate) fn build_result(
+        &self,
+        result_ty: StructType<'ctx>,
+        is_err: bool,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<StructValue<'ctx>, CodeGenError> {
+        let index = if is_err { RESULT_ERR } else { RESULT_OK };
+        if result_ty.get_field_type_at_index(index) != Some(value.get_type()) {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                &format!(
+                    "Mismatched types; expected {} value for {}",
+                    if is_err { "an error" } else { "an ok" },
+                    Self::struct_name(result_ty)?
+                ),
+            ));
+        }
+
+        let tag = self.context.bool_type().const_int(is_err as u64, false);
+        let result = self
+            .builder
+            .build_insert_value(result_ty.const_zero(), tag, RESULT_IS_ERR, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_insert_value(result, value, index, "")
+            .map(|v| v.into_struct_value())
+            .map_err(CodeGenError::from_llvm_err)
+    }
+
+    /// Builds a Result from the return code and out value of a fallible stdlib function
+    pub(crate) fn build_result_from_code(
+        &self,
+        result_ty: StructType<'ctx>,
+        code: IntValue<'ctx>,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<StructValue<'ctx>, CodeGenError> {
+        let is_err = self
+            .builder
+            .build_int_compare(IntPredicate::NE, code, code.get_type().const_zero(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        let mut result = self
+            .builder
+            .build_insert_value(result_ty.const_zero(), is_err, RESULT_IS_ERR, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        for (index, field) in [(RESULT_OK, value), (RESULT_ERR, code.into())] {
+            result = self
+                .builder
+                .build_insert_value(result, field, index, "")
+                .map_err(CodeGenError::from_llvm_err)?;
+        }
+        Ok(result.into_struct_value())
+    }
+
+    /// Handles the `ok(...)` and `err(...)` constructors
+    pub(crate) fn impl_result_ctor(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        call: &nodes::FunctionCall,
+        dt: BasicTypeEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        if !Self::is_result_type(dt) {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                &format!(
+                    "`{}` can only be used where a Result is expected",
+                    call.name
+                ),
+            ));
+        }
+        let [arg] = call.args.as_slice() else {
+            return Err(CodeGenError::new(
+                ErrorCode::WrongArgumentCount,
+                &format!("`{}` expects exactly one argument", call.name),
+            ));
+        };
+
+        let is_err = call.name == "err";
+        let result_ty = dt.into_struct_type();
+        let field_dt = Self::field_type(result_ty, if is_err { RESULT_ERR } else { RESULT_OK })?;
+        let value = self.impl_expr(arg, built_func, field_dt)?;
+        self.build_result(result_ty, is_err, value)
+            .map(|v| v.into())
+    }
+
+    /// Unwraps the ok value of a Result, returning early from the function with its error
+    pub(crate) fn impl_try(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        r#try: &nodes::Try,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        let value = match &*r#try.value {
+            ASTNodes::FunctionCall(call) => self.impl_function_call(built_func, call)?,
+            ASTNodes::ImportCall(call) => self.impl_import_call(built_func, call)?,
+            node => {
+                let var = self.resolve_var(built_func, node)?;
+                Some(
+                    self.builder
+                        .build_load(var.type_, var.ptr, "")
+                        .map_err(CodeGenError::from_llvm_err)?,
+                )
+            }
+        };
+        let result = match value {
+            Some(BasicValueEnum::StructValue(sv)) if Self::is_result_type(sv.get_type().into()) => {
+                sv
+            }
+            _ => {
+                return Err(CodeGenError::new(
+                    ErrorCode::InvalidTry,
+                    "`?` can only be applied to a Result",
+                ));
+            }
+        };
+
+        let ret_ty = built_func
+            .get_type()
+            .get_return_type()
+            .filter(|rt| Self::is_result_type(*rt))
+            .ok_or(CodeGenError::new(
+                ErrorCode::InvalidTry,
+                "`?` can only be used in a function that returns a Result",
+            ))?
+            .into_struct_type();
+        if ret_ty.get_field_type_at_index(RESULT_ERR)
+            != result.get_type().get_field_type_at_index(RESULT_ERR)
+        {
+            return Err(CodeGenError::new(
+                ErrorCode::InvalidTry,
+                "Mismatched error types; `?` cannot convert between error types",
+            ));
+        }
+
+        let is_err = self
+            .builder
+            .build_extract_value(result, RESULT_IS_ERR, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        let err_block = self.context.append_basic_block(built_func, "try_err");
+        let ok_block = self.context.append_basic_block(built_func, "try_ok");
+        self.builder
+            .build_conditional_branch(is_err.into_int_value(), err_block, ok_block)
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.builder.position_at_end(err_block);
+        let err = self
+            .builder
+            .build_extract_value(result, RESULT_ERR, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        let ret_val = self.build_result(ret_ty, true, err)?;
+        self.builder
+            .build_return(Some(&ret_val))
+            .map_err(CodeGenError::from_llvm_err)?;
+
+        self.builder.position_at_end(ok_block);
+        self.builder
+            .build_extract_value(result, RESULT_OK, "")
+            .map(|v| TypedValue::new(v, Self::is_result_ok_signed(result.get_type())))
+            .map_err(CodeGenError::from_llvm_err)
+    }
+
+    pub(crate) fn impl_result_method(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        result: StructValue<'ctx>,
+        method: &nodes::FunctionCall,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        let is_err = self
+            .builder
+            .build_extract_value(result, RESULT_IS_ERR, "")
+            .map_err(CodeGenError::from_llvm_err)?
+            .into_int_value();
+        match (method.name.as_str(), method.args.as_slice()) {
+            ("is_err", []) => Ok(TypedValue::unsigned(is_err.into())),
+            ("is_ok", []) => self
+                .builder
+                .build_not(is_err, "")
+                .map(|v| TypedValue::unsigned(v.into()))
+                .map_err(CodeGenError::from_llvm_err),
+            ("unwrap_or", [default]) => {
+                let ok_dt = Self::field_type(result.get_type(), RESULT_OK)?;
+                let default = self.impl_expr(default, built_func, ok_dt)?;
+                let value = self
+                    .builder
+                    .build_extract_value(result, RESULT_OK, "")
+                    .map_err(CodeGenError::from_llvm_err)?;
+                self.builder
+                    .build_select(is_err, default, value, "")
+                    .map(|v| TypedValue::new(v, Self::is_result_ok_signed(result.get_type())))
+                    .map_err(CodeGenError::from_llvm_err)
+            }
+            _ => Err(CodeGenError::new(
+                ErrorCode::UnknownMethod,
+                &format!(
+                    "No method `{}` taking {} arguments on Result",
+                    method.name,
+                    method.args.len()
+                ),
+            )),
+        }
+    }
+
+    /// Errors if a Result is discarded without being handled
+    pub(crate) fn check_result_used(
+        &self,
+        value: Option<BasicValueEnum<'ctx>>,
+        name: &str,
+    ) -> Result<(), CodeGenError> {
+        match value {
+            Some(value) if Self::is_result_type(value.get_type()) => Err(CodeGenError::new(
+                ErrorCode::UnusedResult,
+                &format!("Result returned by `{}` must be used", name),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diagnostics::{Diagnostic, ErrorCode, Span};
+
+    #[test]
+    fn test_result_try() {
+        let data = "
+func parse(a u32) Result<u32, i32> {
+    return ok(a)
+}
+
+func main() Result<u32, i32> {
+    let u32 a = parse(4)?
+    return err(a)
+}";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%"Result<u32, i32>" = type { i1, i32, i32 }
+
+define %"Result<u32, i32>" @parse(i32 %a) {
+entry:
+  %0 = insertvalue %"Result<u32, i32>" zeroinitializer, i32 %a, 1
+  ret %"Result<u32, i32>" %0
+}
+
+define %"Result<u32, i32>" @main() {
+entry:
+  %0 = call %"Result<u32, i32>" @parse(i32 4)
+  %1 = extractvalue %"Result<u32, i32>" %0, 0
+  br i1 %1, label %try_err, label %try_ok
+
+try_err:                                          ; preds = %entry
+  %2 = extractvalue %"Result<u32, i32>" %0, 2
+  %3 = insertvalue %"Result<u32, i32>" { i1 true, i32 0, i32 0 }, i32 %2, 2
+  ret %"Result<u32, i32>" %3
+
+try_ok:                                           ; preds = %entry
+  %4 = extractvalue %"Result<u32, i32>" %0, 1
+  %a = alloca i32, align 4
+  store i32 %4, ptr %a, align 4
+  %a1 = load i32, ptr %a, align 4
+  %5 = insertvalue %"Result<u32, i32>" { i1 true, i32 0, i32 0 }, i32 %a1, 2
+  ret %"Result<u32, i32>" %5
+}
+"#
+        )
+    }
+
+    #[test]
+    fn test_result_discarded() {
+        let data = "
+func parse(a u32) Result<u32, i32> {
+    return ok(a)
+}
+
+func main() {
+    parse(4)
+}";
+        let err = crate::get_codegen_for_string(data).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::UnusedResult);
+        assert_eq!(err.span(), Some(Span::line(6)));
+        assert_eq!(err.node(), Some("function call"));
+
+        let diag = Diagnostic::from(&err);
+        assert_eq!(diag.labels[0].span, Span::line(6));
+        assert_eq!(diag.labels[0].msg, "in this function call");
+    }
+
+    #[test]
+    fn test_try_outside_result_func() {
+        let data = "
+func parse(a u32) Result<u32, i32> {
+    return ok(a)
+}
+
+func main() u32 {
+    return parse(4)?
+}";
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    AddressSpace,
+    types::{BasicType, BasicTypeEnum, StructType},
+    values::{BasicValueEnum, FunctionValue, IntValue, PointerValue},
+};
+use lexer::types::Datatype;
+use parser::nodes;
+
+use crate::{CodeGen, CodeGenError, stmt::Variable, values::TypedValue};
+
+// A slice `T[..]` is lowered to a named struct `{ i64 len, ptr data }`, like a string, and
+// its elements are stored elsewhere. The element type isn't part of the struct, so it is
+// looked up by the name of the struct.
+
+const SLICE_LEN: u32 = 0;
+const SLICE_DATA: u32 = 1;
+
+impl<'ctx> CodeGen<'ctx> {
+    pub(crate) fn slice_type(&self, inner: &Datatype) -> Result<StructType<'ctx>, CodeGenError> {
+        let name = format!("slice<{}>", inner);
+        let element = self.parser_to_llvm_dt(inner)?;
+        self.slice_elements
+            .borrow_mut()
+            .insert(name.clone(), element);
+        if let Some(slice_ty) = self.context.get_struct_type(&name) {
+            return Ok(slice_ty);
+        }
+
+        let slice_ty = self.context.opaque_struct_type(&name);
+        slice_ty.set_body(
+            &[
+                self.context.i64_type().into(),
+                self.context.ptr_type(AddressSpace::default()).into(),
+            ],
+            false,
+        );
+        Ok(slice_ty)
+    }
+
+    /// The type of the elements, when `dt` is a slice
+    pub(crate) fn slice_element_type(
+        &self,
+        dt: BasicTypeEnum<'ctx>,
+    ) -> Option<BasicTypeEnum<'ctx>> {
+        let BasicTypeEnum::StructType(st) = dt else {
+            return None;
+        };
+        let name = st.get_name()?.to_str().ok()?;
+        self.slice_elements.borrow().get(name).copied()
+    }
+
+    /// Builds a slice of the `len` elements stored at `data`
+    pub(crate) fn build_slice(
+        &self,
+        slice_ty: StructType<'ctx>,
+        len: IntValue<'ctx>,
+        data: PointerValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        self.build_aggregate(slice_ty.into(), vec![len.into(), data.into()])
+    }
+
+    /// The argument of a slice parameter. An array is passed as a slice of its elements,
+    /// which are copied to the stack of the caller.
+    pub(crate) fn impl_slice_arg(
+        &self,
+        arg: &nodes::Expression,
+        built_func: FunctionValue<'ctx>,
+        slice_ty: StructType<'ctx>,
+        element: BasicTypeEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let value = match arg {
+            nodes::Expression::Array(items) => {
+                let array_ty = element.array_type(items.len() as u32);
+                self.impl_expr(arg, built_func, array_ty.into())?
+            }
+            _ => self.impl_expr(arg, built_func, slice_ty.into())?,
+        };
+        let BasicValueEnum::ArrayValue(array) = value else {
+            return Ok(value);
+        };
+        let ptr = self
+            .builder
+            .build_alloca(array.get_type(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_store(ptr, array)
+            .map_err(CodeGenError::from_llvm_err)?;
+        let len = array.get_type().len() as u64;
+        let len = self.context.i64_type().const_int(len, false);
+        self.build_slice(slice_ty, len, ptr)
+    }
+
+    fn load_slice_field(
+        &self,
+        slice: &Variable<'ctx>,
+        index: u32,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let slice_ty = slice.type_.into_struct_type();
+        let field_ty = Self::field_type(slice_ty, index)?;
+        let ptr = self
+            .builder
+            .build_struct_gep(slice_ty, slice.ptr, index, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_load(field_ty, ptr, "")
+            .map_err(CodeGenError::from_llvm_err)
+    }
+
+    /// The element of the slice at `index`. Unlike arrays, the number of elements is only
+    /// known at runtime, so it is loaded for the bounds check.
+    pub(crate) fn impl_slice_index(
+        &self,
+        slice: Variable<'ctx>,
+        element: BasicTypeEnum<'ctx>,
+        index: TypedValue<'ctx>,
+        line: usize,
+    ) -> Result<Variable<'ctx>, CodeGenError> {
+        if self.runtime_checks {
+            let len = self.load_slice_field(&slice, SLICE_LEN)?.into_int_value();
+            self.build_bounds_check(index, len, line)?;
+        }
+        let data = self
+            .load_slice_field(&slice, SLICE_DATA)?
+            .into_pointer_value();
+        let ptr = unsafe {
+            self.builder
+                .build_in_bounds_gep(element, data, &[index.value.into_int_value()], "")
+                .map_err(CodeGenError::from_llvm_err)
+        }?;
+        Ok(Variable {
+            ptr,
+            type_: element,
+            ..slice
+        })
+    }
+
+    pub(crate) fn impl_slice_method(
+        &self,
+        slice: Variable<'ctx>,
+        call: &nodes::FunctionCall,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        match call.name.as_str() {
+            "len" if call.args.is_empty() => self
+                .load_slice_field(&slice, SLICE_LEN)
+                .map(TypedValue::unsigned),
+            _ => Err(CodeGenError::new(
+                ErrorCode::UnknownMethod,
+                &format!(
+                    "No method `{}` taking {} arguments on slices",
+                    call.name,
+                    call.args.len()
+                ),
+            )),
+        }
+    }
+}
@@ -1,51 +1,58 @@
 use std::{cell::RefCell, collections::HashMap};
 
+use diagnostics::ErrorCode;
 use inkwell::{
     types::BasicTypeEnum,
-    values::{BasicValueEnum, FunctionValue, InstructionValue, PointerValue},
+    values::{FunctionValue, InstructionValue, PointerValue},
 };
-use parser::nodes::{self, ASTNodes};
+use parser::nodes::{self, ASTNodes, DefId};
 
-use crate::{CodeGen, CodeGenError};
+use crate::{CodeGen, CodeGenError, values::TypedValue};
 
+/// The allocas of the variables in the function being built, by the id the
+/// resolution pass gave their definition. Ids are unique, so shadowed and
+/// out of scope variables can never be confused with each other.
 #[derive(Debug, Default)]
 pub struct Variables<'ctx> {
-    vars: RefCell<HashMap<String, Variable<'ctx>>>,
+    vars: RefCell<HashMap<DefId, Variable<'ctx>>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Variable<'ctx> {
     pub ptr: PointerValue<'ctx>,
     pub type_: BasicTypeEnum<'ctx>,
+    pub signed: bool,
     pub mutable: bool,
 }
 
 impl<'ctx> Variables<'ctx> {
-    pub(crate) fn get(&self, name: &str) -> Option<Variable<'ctx>> {
+    pub(crate) fn get(&self, id: DefId) -> Option<Variable<'ctx>> {
         let borrow = self.vars.borrow();
-        let var = borrow.get(name)?;
+        let var = borrow.get(&id)?;
         Some(var.clone())
     }
 
     pub(crate) fn insert(
         &self,
-        name: &str,
+        id: DefId,
         ptr: PointerValue<'ctx>,
         type_: BasicTypeEnum<'ctx>,
+        signed: bool,
         mutable: bool,
     ) {
         self.vars.borrow_mut().insert(
-            name.to_string(),
+            id,
             Variable {
                 ptr,
                 type_,
+                signed,
                 mutable,
             },
         );
     }
 
-    pub(crate) fn _remove(&self, name: &str) {
-        self.vars.borrow_mut().remove(name);
+    pub(crate) fn _remove(&self, id: DefId) {
+        self.vars.borrow_mut().remove(&id);
     }
 
     pub(crate) fn clear(&self) {
@@ -53,16 +60,26 @@ impl<'ctx> Variables<'ctx> {
     }
 }
 
+/// The id of a definition, which the resolution pass must have set
+pub(crate) fn resolved_id(id: Option<DefId>, name: &str) -> Result<DefId, CodeGenError> {
+    id.ok_or(CodeGenError::new(
+        ErrorCode::Internal,
+        &format!("Unresolved variable {}", name),
+    ))
+}
+
 impl<'ctx> CodeGen<'ctx> {
     pub(crate) fn impl_let_stmt(
         &self,
         built_func: FunctionValue<'ctx>,
         stmt: &nodes::LetStmt,
     ) -> Result<PointerValue<'ctx>, CodeGenError> {
-        let dt = self.parser_to_llvm_dt(&stmt.datatype);
+        let dt = self.parser_to_llvm_dt(&stmt.datatype)?;
         let expr = self.impl_expr(&stmt.value, built_func, dt)?;
 
-        let ptr = if expr.is_pointer_value() {
+        // Strings are built on the stack, so the pointer to them is their storage. A
+        // cstring is itself a pointer, to characters that can't be the storage.
+        let ptr = if expr.is_pointer_value() && !dt.is_pointer_type() {
             expr.into_pointer_value()
         } else {
             let ptr = self
@@ -76,7 +93,14 @@ impl<'ctx> CodeGen<'ctx> {
 
             ptr
         };
-        self.var_ptrs.insert(&stmt.name, ptr, dt, stmt.mutable);
+        self.var_ptrs.insert(
+            resolved_id(stmt.id, &stmt.name)?,
+            ptr,
+            dt,
+            stmt.datatype.is_signed(),
+            stmt.mutable,
+        );
+        self.debug_variable(&stmt.name, &stmt.datatype, ptr, stmt.line, None, built_func)?;
         return Ok(ptr);
     }
 
@@ -85,10 +109,17 @@ impl<'ctx> CodeGen<'ctx> {
         built_func: FunctionValue<'ctx>,
         stmt: &nodes::AssignStmt,
     ) -> Result<InstructionValue, CodeGenError> {
+        if stmt.operator.is_some() {
+            return Err(CodeGenError::new(
+                ErrorCode::Internal,
+                "Compound assignments should have been lowered by the desugaring pass",
+            ));
+        }
         let var = self.resolve_var(built_func, &stmt.name).and_then(|op| {
-            op.mutable
-                .then_some(op)
-                .ok_or(CodeGenError::new("Variable not mutable"))
+            op.mutable.then_some(op).ok_or(CodeGenError::new(
+                ErrorCode::ImmutableAssignment,
+                "Variable not mutable",
+            ))
         })?;
         let expr = self.impl_expr(&stmt.value, built_func, var.type_)?;
 
@@ -105,11 +136,20 @@ impl<'ctx> CodeGen<'ctx> {
         match node {
             ASTNodes::Variable(var) => Ok(self
                 .var_ptrs
-                .get(&var.name)
-                .ok_or(CodeGenError::new("Variable not found"))?),
+                .get(resolved_id(var.id, &var.name)?)
+                .ok_or(CodeGenError::new(
+                    ErrorCode::UnknownVariable,
+                    "Variable not found",
+                ))?),
             ASTNodes::Attr(attr) => self.impl_attr_access(built_func, attr),
             ASTNodes::ArrayIndex(ind) => self.impl_array_index(built_func, ind),
-            _ => todo!("{:?}", node),
+            node => Err(CodeGenError::new(
+                ErrorCode::InvalidAssignment,
+                &format!(
+                    "Expected a variable, field or array element, found {}",
+                    node.kind()
+                ),
+            )),
         }
     }
 
@@ -119,14 +159,27 @@ impl<'ctx> CodeGen<'ctx> {
         index: &nodes::ArrayIndex,
     ) -> Result<Variable<'ctx>, CodeGenError> {
         let mut array_var = self.resolve_var(built_func, &index.array_var)?;
-        let index = self.impl_expr(&index.index, built_func, self.context.i32_type().into())?;
-        let inner_dt = if let BasicTypeEnum::ArrayType(at) = array_var.type_ {
-            at.get_element_type()
+        let line = index.line;
+        let index =
+            self.impl_typed_expr(&index.index, built_func, self.context.i32_type().into())?;
+        if let Some(element) = self.slice_element_type(array_var.type_) {
+            return self.impl_slice_index(array_var, element, index, line);
+        }
+        let (inner_dt, len) = if let BasicTypeEnum::ArrayType(at) = array_var.type_ {
+            (at.get_element_type(), at.len())
         } else if let BasicTypeEnum::VectorType(vt) = array_var.type_ {
-            vt.get_element_type()
+            (vt.get_element_type(), vt.get_size())
         } else {
-            unreachable!()
+            return Err(CodeGenError::new(
+                ErrorCode::NotIndexable,
+                &format!("Values of type `{}` cannot be indexed", array_var.type_),
+            ));
         };
+        if self.runtime_checks {
+            let len = self.context.i64_type().const_int(len as u64, false);
+            self.build_bounds_check(index, len, line)?;
+        }
+        let index = index.value;
         let ptr = unsafe {
             self.builder
                 .build_in_bounds_gep(
@@ -147,16 +200,21 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         built_func: FunctionValue<'ctx>,
         ind: &nodes::ArrayIndex,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
         let array_var = self.impl_array_index(built_func, ind)?;
         self.builder
             .build_load(array_var.type_, array_var.ptr, "")
+            .map(|v| TypedValue::new(v, array_var.signed))
             .map_err(CodeGenError::from_llvm_err)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use inkwell::context::Context;
+
+    use crate::CodeGen;
+
     #[test]
     fn test_codegen_let_stmt() {
         let data = "func main() { let u32 a = 5 }";
@@ -248,6 +306,33 @@ entry:
         )
     }
 
+    #[test]
+    fn test_codegen_runtime_array() {
+        let data = "func main(a u32) u32 {
+let u32[] arr = [a, 2]
+return arr[1]
+}";
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+define i32 @main(i32 %a) {
+entry:
+  %0 = insertvalue [2 x i32] zeroinitializer, i32 %a, 0
+  %1 = insertvalue [2 x i32] %0, i32 2, 1
+  %arr = alloca [2 x i32], align 4
+  store [2 x i32] %1, ptr %arr, align 4
+  %2 = getelementptr inbounds [2 x i32], ptr %arr, i32 0, i32 1
+  %3 = load i32, ptr %2, align 4
+  ret i32 %3
+}
+"#
+        )
+    }
+
     #[test]
     fn test_codegen_access_array_nested() {
         let data = "func main() u32 { 
@@ -271,6 +356,54 @@ entry:
   %3 = load i32, ptr %2, align 4
   ret i32 %3
 }
+"#
+        )
+    }
+
+    #[test]
+    fn test_codegen_access_array_bounds_check() {
+        let data = "func main(i u32) u32 {
+    let u32[] a = [1, 2, 3]
+    return a[i]
+}";
+        let context = Context::create();
+        let tokens = crate::check_program(data).unwrap();
+        let codegen = CodeGen::new(&context, tokens, false).with_runtime_checks(true);
+        codegen.codegen().unwrap();
+
+        assert_eq!(
+            codegen.ir_as_string(),
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [7 x i8] c"main:3\00", align 1
+
+define i32 @main(i32 %i) {
+entry:
+  %a = alloca [3 x i32], align 4
+  store [3 x i32] [i32 1, i32 2, i32 3], ptr %a, align 4
+  %0 = zext i32 %i to i64
+  %1 = icmp uge i64 %0, 3
+  br i1 %1, label %panic, label %panic_cont
+
+panic:                                            ; preds = %entry
+  %2 = alloca %string, align 8
+  store %string { i64 6, ptr @0 }, ptr %2, align 8
+  call void @__builtin__panic_bounds(i64 3, i64 %0, ptr %2)
+  unreachable
+
+panic_cont:                                       ; preds = %entry
+  %3 = getelementptr inbounds [3 x i32], ptr %a, i32 0, i32 %i
+  %4 = load i32, ptr %3, align 4
+  ret i32 %4
+}
+
+; Function Attrs: noreturn
+declare void @__builtin__panic_bounds(i64, i64, ptr) #0
+
+attributes #0 = { noreturn }
 "#
         )
     }
@@ -0,0 +1,250 @@
+use diagnostics::ErrorCode;
+use inkwell::{
+    AddressSpace,
+    context::Context,
+    types::{BasicTypeEnum, StructType},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue, StructValue},
+};
+use lexer::types::Operator;
+use parser::nodes;
+
+use crate::{CodeGen, CodeGenError, values::TypedValue};
+
+// A string is lowered to a named struct `{ i64 len, ptr data }`, matching `Str` in the stdlib.
+// String data is never mutated, so copies of a string share their data.
+
+pub(crate) fn get_string_type(context: &Context) -> StructType<'_> {
+    if let Some(string_ty) = context.get_struct_type("string") {
+        return string_ty;
+    }
+
+    let string_ty = context.opaque_struct_type("string");
+    string_ty.set_body(
+        &[
+            context.i64_type().into(),
+            context.ptr_type(AddressSpace::default()).into(),
+        ],
+        false,
+    );
+    string_ty
+}
+
+impl<'ctx> CodeGen<'ctx> {
+    pub(crate) fn string_type(&self) -> StructType<'ctx> {
+        get_string_type(self.context)
+    }
+
+    pub(crate) fn is_string_type(dt: BasicTypeEnum<'ctx>) -> bool {
+        matches!(dt, BasicTypeEnum::StructType(st) if st
+            .get_name()
+            .is_some_and(|name| name.to_bytes() == b"string"))
+    }
+
+    pub(crate) fn impl_string_literal(
+        &self,
+        string: &str,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let data = self
+            .builder
+            .build_global_string_ptr(string, "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        Ok(self
+            .string_type()
+            .const_named_struct(&[
+                self.context
+                    .i64_type()
+                    .const_int(string.len() as u64, false)
+                    .into(),
+                data.as_pointer_value().into(),
+            ])
+            .into())
+    }
+
+    /// Stores the string on the stack; the stdlib takes strings by pointer
+    pub(crate) fn string_to_ptr(
+        &self,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CodeGenError> {
+        let ptr = self
+            .builder
+            .build_alloca(self.string_type(), "")
+            .map_err(CodeGenError::from_llvm_err)?;
+        self.builder
+            .build_store(ptr, value)
+            .map_err(CodeGenError::from_llvm_err)?;
+        Ok(ptr)
+    }
+
+    fn call_string_builtin(
+        &self,
+        name: &str,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        self.call_builtin(name, args)?.ok_or(CodeGenError::new(
+            ErrorCode::NoValue,
+            &format!("Builtin {} returns nothing", name),
+        ))
+    }
+
+    pub(crate) fn impl_string_operation(
+        &self,
+        left: BasicValueEnum<'ctx>,
+        right: BasicValueEnum<'ctx>,
+        op: &Operator,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        if !Self::is_string_type(right.get_type()) {
+            return Err(CodeGenError::new(
+                ErrorCode::MismatchedTypes,
+                "Mismatched types; expected a string",
+            ));
+        }
+        let args = [
+            self.string_to_ptr(left)?.into(),
+            self.string_to_ptr(right)?.into(),
+        ];
+
+        match op {
+            Operator::PLUS => self.call_string_builtin("__builtin__str_concat", &args),
+            Operator::EQUAL
+            | Operator::NOT_EQUAL
+            | Operator::GREATER
+            | Operator::GREATER_EQUAL
+            | Operator::LESSER
+            | Operator::LESSER_EQUAL => {
+                let ordering = self
+                    .call_string_builtin("__builtin__str_cmp", &args)?
+                    .into_int_value();
+                let (predicate, _) = self.ops_to_llvm_predicate(op, true)?;
+                self.builder
+                    .build_int_compare(predicate, ordering, ordering.get_type().const_zero(), "")
+                    .map(|v| v.into())
+                    .map_err(CodeGenError::from_llvm_err)
+            }
+            _ => Err(CodeGenError::new(
+                ErrorCode::InvalidOperands,
+                &format!("Operator {:?} is not supported on strings", op),
+            )),
+        }
+    }
+
+    pub(crate) fn impl_string_method(
+        &self,
+        built_func: FunctionValue<'ctx>,
+        string: StructValue<'ctx>,
+        method: &nodes::FunctionCall,
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
+        match (method.name.as_str(), method.args.as_slice()) {
+            ("len", []) => self
+                .builder
+                .build_extract_value(string, 0, "")
+                .map(TypedValue::unsigned)
+                .map_err(CodeGenError::from_llvm_err),
+            ("slice", [start, end]) => {
+                let i64_type = self.context.i64_type().into();
+                let start = self.impl_typed_expr(start, built_func, i64_type)?;
+                let end = self.impl_typed_expr(end, built_func, i64_type)?;
+                self.call_string_builtin(
+                    "__builtin__str_slice",
+                    &[
+                        self.string_to_ptr(string.into())?.into(),
+                        self.impl_cast_expr(start, i64_type, false)?.value.into(),
+                        self.impl_cast_expr(end, i64_type, false)?.value.into(),
+                    ],
+                )
+                .map(TypedValue::unsigned)
+            }
+            ("find" | "starts_with", [arg]) => {
+                let arg = self.impl_expr(arg, built_func, self.string_type().into())?;
+                if !Self::is_string_type(arg.get_type()) {
+                    return Err(CodeGenError::new(
+                        ErrorCode::MismatchedTypes,
+                        "Mismatched types; expected a string",
+                    ));
+                }
+                // `find` returns -1 if the pattern is not found
+                self.call_string_builtin(
+                    &format!("__builtin__str_{}", method.name),
+                    &[
+                        self.string_to_ptr(string.into())?.into(),
+                        self.string_to_ptr(arg)?.into(),
+                    ],
+                )
+                .map(|v| TypedValue::new(v, method.name == "find"))
+            }
+            _ => Err(CodeGenError::new(
+                ErrorCode::UnknownMethod,
+                &format!(
+                    "No method `{}` taking {} arguments on string",
+                    method.name,
+                    method.args.len()
+                ),
+            )),
+        }
+    }
+
+    pub(crate) fn string_to_cstring(
+        &self,
+        string: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let string_ptr = self.string_to_ptr(string)?;
+        self.call_string_builtin("__builtin__str_to_cstr", &[string_ptr.into()])
+    }
+
+    pub(crate) fn cstring_to_string(
+        &self,
+        cstring: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        self.call_string_builtin("__builtin__cstr_to_str", &[cstring.into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_string_comparison() {
+        let data = r#"
+func main() bool {
+    let string a = "ab"
+    return a == "ab"
+}"#;
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%string = type { i64, ptr }
+
+@0 = private unnamed_addr constant [3 x i8] c"ab\00", align 1
+@1 = private unnamed_addr constant [3 x i8] c"ab\00", align 1
+
+define i1 @main() {
+entry:
+  %a = alloca %string, align 8
+  store %string { i64 2, ptr @0 }, ptr %a, align 8
+  %a1 = load %string, ptr %a, align 8
+  %0 = alloca %string, align 8
+  store %string %a1, ptr %0, align 8
+  %1 = alloca %string, align 8
+  store %string { i64 2, ptr @1 }, ptr %1, align 8
+  %2 = call i32 @__builtin__str_cmp(ptr %0, ptr %1)
+  %3 = icmp eq i32 %2, 0
+  ret i1 %3
+}
+
+declare i32 @__builtin__str_cmp(ptr, ptr)
+"#
+        )
+    }
+
+    #[test]
+    fn test_string_invalid_operator() {
+        let data = r#"
+func main() {
+    let string a = "ab"
+    let string b = a * "ab"
+}"#;
+        assert!(crate::get_codegen_for_string(data).is_err());
+    }
+}
@@ -1,12 +1,14 @@
 use std::{cell::RefCell, collections::HashMap};
 
+use diagnostics::ErrorCode;
 use inkwell::{
-    types::StructType,
-    values::{BasicValueEnum, FunctionValue},
+    types::{BasicTypeEnum, StructType},
+    values::FunctionValue,
 };
+use lexer::types::Datatype;
 use parser::nodes::{self, StructDef};
 
-use crate::{CodeGen, CodeGenError, stmt::Variable};
+use crate::{CodeGen, CodeGenError, stmt::Variable, values::TypedValue};
 
 #[derive(Debug, Default)]
 pub struct StructDefs<'ctx> {
@@ -16,12 +18,12 @@ pub struct StructDefs<'ctx> {
 #[derive(Debug)]
 pub struct StructData<'ctx> {
     ptr: StructType<'ctx>,
-    fields: Vec<String>,
+    fields: Vec<(String, Datatype)>,
 }
 
 impl<'ctx> StructDefs<'ctx> {
     pub(crate) fn add_struct(&self, def: &StructDef, ptr: StructType<'ctx>) {
-        let fields = def.fields.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+        let fields = def.fields.clone();
         self.items
             .borrow_mut()
             .insert(def.name.clone(), StructData { ptr, fields });
@@ -31,7 +33,19 @@ impl<'ctx> StructDefs<'ctx> {
         let structs = self.items.borrow();
         structs
             .get(name)
-            .map(|x| x.fields.iter().position(|x| x == field))?
+            .map(|x| x.fields.iter().position(|x| x.0 == field))?
+    }
+
+    pub(crate) fn get_field_names(&self, name: &str) -> Option<Vec<String>> {
+        let structs = self.items.borrow();
+        structs
+            .get(name)
+            .map(|x| x.fields.iter().map(|x| x.0.clone()).collect())
+    }
+
+    pub(crate) fn get_field_datatype(&self, name: &str, index: usize) -> Option<Datatype> {
+        let structs = self.items.borrow();
+        structs.get(name)?.fields.get(index).map(|x| x.1.clone())
     }
 
     pub(crate) fn get_struct_ptr(&self, name: &str) -> Option<StructType<'ctx>> {
@@ -41,20 +55,31 @@ impl<'ctx> StructDefs<'ctx> {
 }
 
 impl<'ctx> CodeGen<'ctx> {
-    pub(crate) fn def_struct(
+    /// Creates the struct type without its fields, which are set by `def_struct`
+    pub(crate) fn declare_struct(
         &self,
         r#struct: &StructDef,
     ) -> Result<StructType<'ctx>, CodeGenError> {
         let struct_def = self.context.opaque_struct_type(&r#struct.name);
+        self.struct_defs.add_struct(r#struct, struct_def);
+        Ok(struct_def)
+    }
+
+    pub(crate) fn def_struct(&self, r#struct: &StructDef) -> Result<(), CodeGenError> {
+        let struct_def =
+            self.struct_defs
+                .get_struct_ptr(&r#struct.name)
+                .ok_or(CodeGenError::internal(&format!(
+                    "Struct `{}` was not declared",
+                    r#struct.name
+                )))?;
         let fields = r#struct
             .fields
             .iter()
             .map(|field| self.parser_to_llvm_dt(&field.1))
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
         struct_def.set_body(&fields, false);
-
-        self.struct_defs.add_struct(r#struct, struct_def);
-        Ok(struct_def)
+        Ok(())
     }
 
     pub(crate) fn impl_attr_access(
@@ -63,15 +88,21 @@ impl<'ctx> CodeGen<'ctx> {
         attr: &nodes::Attr,
     ) -> Result<Variable<'ctx>, CodeGenError> {
         let mut struct_var = self.resolve_var(built_func, &attr.parent)?;
-        let struct_ty = struct_var.type_.into_struct_type();
+        let BasicTypeEnum::StructType(struct_ty) = struct_var.type_ else {
+            return Err(CodeGenError::new(
+                ErrorCode::UnknownField,
+                &format!("Values of type `{}` have no fields", struct_var.type_),
+            ));
+        };
 
+        let struct_name = Self::struct_name(struct_ty)?;
         let field_index = self
             .struct_defs
-            .get_field_index(
-                &struct_ty.get_name().unwrap().to_str().unwrap(),
-                &attr.name.name,
-            )
-            .ok_or(CodeGenError::new("Field not found"))?;
+            .get_field_index(&struct_name, &attr.name.name)
+            .ok_or(CodeGenError::new(
+                ErrorCode::UnknownField,
+                "Field not found",
+            ))?;
 
         let ptr = self
             .builder
@@ -79,9 +110,11 @@ impl<'ctx> CodeGen<'ctx> {
             .map_err(CodeGenError::from_llvm_err)?;
 
         struct_var.ptr = ptr;
-        struct_var.type_ = struct_ty
-            .get_field_type_at_index(field_index as u32)
-            .unwrap();
+        struct_var.signed = self
+            .struct_defs
+            .get_field_datatype(&struct_name, field_index)
+            .is_some_and(|dt| dt.is_signed());
+        struct_var.type_ = Self::field_type(struct_ty, field_index as u32)?;
         Ok(struct_var)
     }
 
@@ -89,11 +122,12 @@ impl<'ctx> CodeGen<'ctx> {
         &self,
         built_func: FunctionValue<'ctx>,
         attr: &nodes::Attr,
-    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+    ) -> Result<TypedValue<'ctx>, CodeGenError> {
         let struct_var = self.impl_attr_access(built_func, attr)?;
 
         self.builder
             .build_load(struct_var.type_, struct_var.ptr, "")
+            .map(|v| TypedValue::new(v, struct_var.signed))
             .map_err(CodeGenError::from_llvm_err)
     }
 }
@@ -221,6 +255,37 @@ entry:
   %6 = load i32, ptr %5, align 4
   ret i32 %6
 }
+"#
+        )
+    }
+    #[test]
+    fn test_codegen_struct_declared_later() {
+        let data = r#"
+        struct Line { start Point, end Point }
+        struct Point { x u32, y u32 }
+        func main() u32 {
+            let Line l = { start { x 1, y 2 }, end { x 3, y 4 } }
+            return l.end.y
+        }"#;
+        let result = crate::get_codegen_for_string(data).unwrap();
+
+        assert_eq!(
+            result,
+            r#"; ModuleID = 'main'
+source_filename = "main"
+
+%Line = type { %Point, %Point }
+%Point = type { i32, i32 }
+
+define i32 @main() {
+entry:
+  %l = alloca %Line, align 8
+  store %Line { %Point { i32 1, i32 2 }, %Point { i32 3, i32 4 } }, ptr %l, align 4
+  %0 = getelementptr inbounds %Line, ptr %l, i32 0, i32 1
+  %1 = getelementptr inbounds %Point, ptr %0, i32 0, i32 1
+  %2 = load i32, ptr %1, align 4
+  ret i32 %2
+}
 "#
         )
     }
@@ -1,15 +1,19 @@
+use diagnostics::ErrorCode;
 use inkwell::{
     AddressSpace,
-    types::{BasicType, BasicTypeEnum, FloatType, VectorType},
-    values::{ArrayValue, BasicValueEnum, VectorValue},
+    types::{BasicType, BasicTypeEnum, FloatType, StructType, VectorType},
+    values::{AggregateValueEnum, BasicValueEnum, VectorValue},
 };
 use lexer::types::Datatype;
 
-use crate::CodeGen;
+use crate::{CodeGen, CodeGenError};
 
 impl<'ctx> CodeGen<'ctx> {
-    pub(crate) fn parser_to_llvm_dt(&self, dt: &Datatype) -> BasicTypeEnum<'ctx> {
-        match dt {
+    pub(crate) fn parser_to_llvm_dt(
+        &self,
+        dt: &Datatype,
+    ) -> Result<BasicTypeEnum<'ctx>, CodeGenError> {
+        let dt = match dt {
             Datatype::U8 => self.context.i8_type().into(),
             Datatype::U16 => self.context.i16_type().into(),
             Datatype::U32 => self.context.i32_type().into(),
@@ -21,71 +25,75 @@ impl<'ctx> CodeGen<'ctx> {
             Datatype::BOOL => self.context.bool_type().into(),
             Datatype::F32 => self.context.f32_type().into(),
             Datatype::F64 => self.context.f64_type().into(),
-            Datatype::STRING(size) => self.context.i8_type().array_type(*size as u32).into(),
+            Datatype::STRING(_) => self.string_type().into(),
             Datatype::CSTRING(_) => self.context.ptr_type(AddressSpace::default()).into(),
             Datatype::NARRAY(dt, size) => {
-                self.parser_to_llvm_dt(dt).array_type(*size as u32).into()
+                self.parser_to_llvm_dt(dt)?.array_type(*size as u32).into()
             }
-            Datatype::SIMD(dt, size) => match self.parser_to_llvm_dt(dt) {
+            Datatype::SIMD(dt, size) => match self.parser_to_llvm_dt(dt)? {
                 BasicTypeEnum::IntType(it) => it.vec_type(*size as u32).into(),
                 BasicTypeEnum::FloatType(ft) => ft.vec_type(*size as u32).into(),
-                _ => unreachable!(),
+                _ => {
+                    return Err(CodeGenError::new(
+                        ErrorCode::MismatchedTypes,
+                        "Vectors can only hold integers and floats",
+                    ));
+                }
             },
-            Datatype::CUSTOM(name) => self.struct_defs.get_struct_ptr(name).unwrap().into(),
-            Datatype::NONE => unreachable!(),
-        }
+            Datatype::SLICE(dt) => self.slice_type(dt)?.into(),
+            Datatype::RESULT(ok, err) => self.result_type(ok, err)?.into(),
+            Datatype::CUSTOM(name) => self
+                .struct_defs
+                .get_struct_ptr(name)
+                .ok_or(CodeGenError::new(
+                    ErrorCode::UnknownType,
+                    &format!("Unknown type `{}`", name),
+                ))?
+                .into(),
+            Datatype::NONE => {
+                return Err(CodeGenError::new(
+                    ErrorCode::NoValue,
+                    "Values can't have the type `none`",
+                ));
+            }
+        };
+        Ok(dt)
     }
 
-    pub(crate) fn dt_to_array(
+    /// Builds an array or struct from the values of its elements. The builder folds
+    /// the insertions into a constant when every element is one.
+    pub(crate) fn build_aggregate(
         &self,
-        dt: &BasicTypeEnum<'ctx>,
+        dt: BasicTypeEnum<'ctx>,
         values: Vec<BasicValueEnum<'ctx>>,
-    ) -> ArrayValue<'ctx> {
-        match dt {
-            BasicTypeEnum::ArrayType(at) => at.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_array_value())
-                    .collect::<Vec<_>>(),
-            ),
-            BasicTypeEnum::IntType(it) => it.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_int_value())
-                    .collect::<Vec<_>>(),
-            ),
-            BasicTypeEnum::FloatType(ft) => ft.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_float_value())
-                    .collect::<Vec<_>>(),
-            ),
-            BasicTypeEnum::PointerType(pt) => pt.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_pointer_value())
-                    .collect::<Vec<_>>(),
-            ),
-            BasicTypeEnum::StructType(st) => st.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_struct_value())
-                    .collect::<Vec<_>>(),
-            ),
-            BasicTypeEnum::VectorType(vt) => vt.const_array(
-                &values
-                    .iter()
-                    .map(|v| v.into_vector_value())
-                    .collect::<Vec<_>>(),
-            ),
+    ) -> Result<BasicValueEnum<'ctx>, CodeGenError> {
+        let mut aggregate: AggregateValueEnum = match dt {
+            BasicTypeEnum::ArrayType(at) => at.const_zero().into(),
+            BasicTypeEnum::StructType(st) => st.const_zero().into(),
+            _ => {
+                return Err(CodeGenError::internal(&format!(
+                    "Cannot build a value of type `{}` from elements",
+                    dt
+                )));
+            }
+        };
+        for (index, value) in values.into_iter().enumerate() {
+            aggregate = self
+                .builder
+                .build_insert_value(aggregate, value, index as u32, "")
+                .map_err(CodeGenError::from_llvm_err)?;
         }
+        Ok(match aggregate {
+            AggregateValueEnum::ArrayValue(array) => array.into(),
+            AggregateValueEnum::StructValue(value) => value.into(),
+        })
     }
 
     pub(crate) fn dt_to_vector(
         &self,
         dt: &BasicTypeEnum<'ctx>,
         values: Vec<BasicValueEnum<'ctx>>,
-    ) -> VectorValue<'ctx> {
+    ) -> Result<VectorValue<'ctx>, CodeGenError> {
         let a: Vec<BasicValueEnum> = match dt {
             BasicTypeEnum::IntType(_) => values
                 .iter()
@@ -101,9 +109,34 @@ impl<'ctx> CodeGen<'ctx> {
                 .iter()
                 .map(|v| v.into_pointer_value().into())
                 .collect::<Vec<_>>(),
-            _ => unreachable!(),
+            _ => {
+                return Err(CodeGenError::new(
+                    ErrorCode::MismatchedTypes,
+                    "Vectors can only hold integers, floats and pointers",
+                ));
+            }
         };
-        VectorType::const_vector(&a)
+        Ok(VectorType::const_vector(&a))
+    }
+
+    /// The name of a struct type. Structs and Results are always created with one.
+    pub(crate) fn struct_name(st: StructType<'ctx>) -> Result<String, CodeGenError> {
+        st.get_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or(CodeGenError::internal("Struct type without a name"))
+    }
+
+    pub(crate) fn field_type(
+        st: StructType<'ctx>,
+        index: u32,
+    ) -> Result<BasicTypeEnum<'ctx>, CodeGenError> {
+        st.get_field_type_at_index(index).ok_or_else(|| {
+            CodeGenError::internal(&format!(
+                "Struct `{}` has no field {}",
+                Self::struct_name(st).unwrap_or_default(),
+                index
+            ))
+        })
     }
 
     pub(crate) fn get_float_size(&self, dt: FloatType<'ctx>) -> u32 {
@@ -0,0 +1,22 @@
+use inkwell::values::BasicValueEnum;
+
+/// A value together with the signedness of its source type. LLVM integer types
+/// don't carry signedness, so it is tracked alongside to pick the right
+/// extension, division, shift, comparison and conversion instructions.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TypedValue<'ctx> {
+    pub value: BasicValueEnum<'ctx>,
+    pub signed: bool,
+}
+
+impl<'ctx> TypedValue<'ctx> {
+    pub(crate) fn new(value: BasicValueEnum<'ctx>, signed: bool) -> Self {
+        Self { value, signed }
+    }
+
+    /// Literals, bools and sizes. Integer literals are unsigned, so a binary
+    /// operation takes the signedness of its other operand.
+    pub(crate) fn unsigned(value: BasicValueEnum<'ctx>) -> Self {
+        Self::new(value, false)
+    }
+}
@@ -1,11 +1,28 @@
 use std::{path::Path, process::exit};
 
+use backend_llvm::{OptLevel, TargetOptions};
+use diagnostics::ErrorCode;
+use passes::lints::{LintLevel, LintLevels};
+
+use crate::build::Emit;
+
 #[derive(Debug)]
 pub struct Args {
     pub parser_opts: ParserArgs,
     pub compiler_opts: CodeGenArgs,
+    pub lint_levels: LintLevels,
     pub path: Option<String>,
+    /// The arguments after `--`, passed to the program by `run` and `jit`
+    pub program_args: Vec<String>,
     pub dry_run: bool,
+    pub time_passes: bool,
+    pub error_format: ErrorFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
 }
 
 #[derive(Debug)]
@@ -18,6 +35,11 @@ pub struct ParserArgs {
 pub struct CodeGenArgs {
     pub jit: bool,
     pub run: bool,
+    pub runtime_checks: bool,
+    pub opt_level: OptLevel,
+    pub emit: Vec<Emit>,
+    pub target: TargetOptions,
+    pub debug_info: bool,
 }
 
 impl Default for Args {
@@ -30,26 +52,49 @@ impl Default for Args {
             compiler_opts: CodeGenArgs {
                 jit: false,
                 run: false,
+                runtime_checks: true,
+                opt_level: OptLevel::O0,
+                emit: vec![Emit::Exe],
+                target: TargetOptions::default(),
+                debug_info: false,
             },
+            lint_levels: LintLevels::default(),
             path: None,
+            program_args: vec![],
             dry_run: false,
+            time_passes: false,
+            error_format: ErrorFormat::Human,
         }
     }
 }
 
 const HELP_STRING: &str = r#"
-Usage: compette [COMMAND] <file> [OPTIONS]
+Usage: compette [COMMAND] <file> [OPTIONS] [-- <args>...]
 
 Commands:
     build                   Build the project
     run                     Run the project
     jit                     Run the project with LLVM's JIT
+    explain <code>          Describe an error code, such as E0042
 
 Options:
     --help, -h              Show this help message
     --print-lexer-output    Print the lexer output
     --print-ast-output      Print the ast output
     --dry-run               Run without invoking LLVM
+    --release               Disable overflow, division by zero and bounds checks
+    -g                      Emit debug information for debuggers like gdb and lldb
+    -O<level>               Optimize at level 0 (the default), 1, 2, 3 or s for size
+    --emit=<kinds>          Write llvm-ir, llvm-bc, asm, obj or exe files, separated by commas
+    --target <triple>       Compile for another target, such as aarch64-unknown-linux-gnu
+    --cpu <name>            Generate code for a specific CPU of the target
+    --features <list>       Enable or disable target features, such as +neon,-fp-armv8
+    --time-passes           Print how long each compiler pass took
+    --error-format=<fmt>    Print errors as `human` readable text or as `json`
+    -W <lint>               Warn about a lint
+    -A <lint>               Allow a lint
+    -D <lint>               Make a lint an error, `-D warnings` does this for all lints
+    -- <args>...            Pass the remaining arguments to the program, with run or jit
 "#;
 
 fn show_help() {
@@ -57,6 +102,23 @@ fn show_help() {
     exit(0);
 }
 
+fn explain(code: Option<&String>) -> ! {
+    let Some(code) = code else {
+        eprintln!("Usage: compette explain <code>");
+        exit(1);
+    };
+    match code.parse::<ErrorCode>() {
+        Ok(code) => {
+            print!("{}: {}", code, code.explain());
+            exit(0);
+        }
+        Err(msg) => {
+            eprintln!("{}", msg);
+            exit(1);
+        }
+    }
+}
+
 fn show_usage() {
     eprintln!("Usage: compette [COMMAND] <file> [OPTIONS]");
     exit(1);
@@ -71,6 +133,7 @@ pub fn parse_args(args: &Vec<String>) -> Args {
             "build" => result.compiler_opts.run = false,
             "run" => result.compiler_opts.run = true,
             "jit" => result.compiler_opts.jit = true,
+            "explain" => explain(args.get(2)),
             _ => show_usage(),
         },
         None => show_usage(),
@@ -90,11 +153,42 @@ pub fn parse_args(args: &Vec<String>) -> Args {
     if args.len() < 3 {
         return result;
     }
-    for arg in args.get(2..).unwrap() {
+    let mut args = args.get(2..).unwrap().iter();
+    while let Some(arg) = args.next() {
         match arg.as_str() {
+            "--" => {
+                result.program_args = args.by_ref().cloned().collect();
+                break;
+            }
             "--help" | "-h" => show_help(),
             "--print-lexer-output" => result.parser_opts.print_lexer_ouput = true,
             "--print-ast-output" => result.parser_opts.print_ast_output = true,
+            "--release" => result.compiler_opts.runtime_checks = false,
+            "--time-passes" => result.time_passes = true,
+            "-g" => result.compiler_opts.debug_info = true,
+            arg if arg.starts_with("-O") => match arg[2..].parse() {
+                Ok(level) => result.compiler_opts.opt_level = level,
+                Err(msg) => {
+                    eprintln!("{}", msg);
+                    exit(1);
+                }
+            },
+            arg if arg.starts_with("--emit=") => {
+                let kinds = arg["--emit=".len()..].split(',').map(str::parse).collect();
+                match kinds {
+                    Ok(kinds) => result.compiler_opts.emit = kinds,
+                    Err(msg) => {
+                        eprintln!("{}", msg);
+                        exit(1);
+                    }
+                }
+            }
+            "--error-format=human" => result.error_format = ErrorFormat::Human,
+            "--error-format=json" => result.error_format = ErrorFormat::Json,
+            arg if arg.starts_with("--error-format") => {
+                eprintln!("--error-format must be `human` or `json`");
+                exit(1);
+            }
             "--dry-run" => {
                 if !result.compiler_opts.run {
                     result.dry_run = true
@@ -103,8 +197,48 @@ pub fn parse_args(args: &Vec<String>) -> Args {
                     exit(1);
                 }
             }
+            "--target" | "--cpu" | "--features" => {
+                let Some(value) = args.next() else {
+                    eprintln!("{} requires a value", arg);
+                    exit(1);
+                };
+                let target = &mut result.compiler_opts.target;
+                match arg.as_str() {
+                    "--target" => target.triple = Some(value.to_string()),
+                    "--cpu" => target.cpu = Some(value.to_string()),
+                    _ => target.features = Some(value.to_string()),
+                }
+            }
+            "-W" | "-A" | "-D" => {
+                let level = match arg.as_str() {
+                    "-W" => LintLevel::Warn,
+                    "-A" => LintLevel::Allow,
+                    _ => LintLevel::Deny,
+                };
+                let Some(name) = args.next() else {
+                    eprintln!("{} requires a lint name", arg);
+                    exit(1);
+                };
+                if let Err(msg) = result.lint_levels.set(name, level) {
+                    eprintln!("{}", msg);
+                    exit(1);
+                }
+            }
             _ => (),
         }
     }
+    let runs = result.compiler_opts.run || result.compiler_opts.jit;
+    if !result.program_args.is_empty() && !runs {
+        eprintln!("Arguments after `--` are only passed to the program by run and jit");
+        exit(1);
+    }
+    if result.compiler_opts.jit && result.compiler_opts.target != TargetOptions::default() {
+        eprintln!("--target, --cpu and --features can't be used with jit");
+        exit(1);
+    }
+    // Running needs the executable, whatever else is written
+    if result.compiler_opts.run && !result.compiler_opts.emit.contains(&Emit::Exe) {
+        result.compiler_opts.emit.push(Emit::Exe);
+    }
     result
 }
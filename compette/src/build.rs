@@ -1,66 +1,195 @@
-use std::{fs, path::PathBuf, process::Command};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
 
+use backend_llvm::{CodeGen, TargetOptions};
+use diagnostics::{Diagnostic, ErrorCode};
+
+/// The stdlib archive for the host, or for the target triple when cross compiling
 #[cfg(debug_assertions)]
-const STDLIB_PATH: &'static str = "target/debug/libstdlib.a";
+fn stdlib_path(triple: Option<&str>) -> PathBuf {
+    match triple {
+        Some(triple) => PathBuf::from("target")
+            .join(triple)
+            .join("debug/libstdlib.a"),
+        None => PathBuf::from("target/debug/libstdlib.a"),
+    }
+}
 #[cfg(not(debug_assertions))]
-const STDLIB_PATH: &'static str = ".build/stdlib.a";
-
-fn output_path() -> PathBuf {
-    let path = PathBuf::from(".build/");
-    if !path.exists() {
-        fs::create_dir(&path).unwrap();
+fn stdlib_path(triple: Option<&str>) -> PathBuf {
+    match triple {
+        Some(triple) => PathBuf::from(".build").join(triple).join("stdlib.a"),
+        None => PathBuf::from(".build/stdlib.a"),
     }
-    path
 }
 
-fn llvm_ir_path() -> PathBuf {
-    let path = output_path().join("ir/");
-    if !path.exists() {
-        fs::create_dir(&path).unwrap();
+/// Linkers tried in order. C compilers are used rather than `ld` itself, as they know
+/// where libc and the startup files are.
+const LINKERS: [&str; 3] = ["cc", "clang", "gcc"];
+/// Only clang can link for other targets without a separate toolchain for each
+const CROSS_LINKERS: [&str; 1] = ["clang"];
+
+/// A kind of file written by `build`, chosen with `--emit`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Emit {
+    LlvmIr,
+    LlvmBc,
+    Asm,
+    Obj,
+    Exe,
+}
+
+impl FromStr for Emit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "llvm-ir" => Ok(Emit::LlvmIr),
+            "llvm-bc" => Ok(Emit::LlvmBc),
+            "asm" => Ok(Emit::Asm),
+            "obj" => Ok(Emit::Obj),
+            "exe" => Ok(Emit::Exe),
+            _ => Err(format!(
+                "Unknown output `{}`, expected llvm-ir, llvm-bc, asm, obj or exe",
+                s
+            )),
+        }
     }
-    path
 }
 
-fn output_binary_path() -> PathBuf {
-    output_path().join("output")
+fn create_dir(path: PathBuf) -> Result<PathBuf, Diagnostic> {
+    fs::create_dir_all(&path).map_err(|err| {
+        Diagnostic::error(&format!("Cannot create `{}`: {}", path.display(), err))
+            .with_code(ErrorCode::WriteFailed)
+    })?;
+    Ok(path)
 }
 
-pub fn build(source: PathBuf, ir: String) -> Result<PathBuf, ()> {
-    let dir = llvm_ir_path();
-    let path = dir.join(source.file_stem().unwrap()).with_extension("ll");
-    let output_path = output_binary_path();
+fn output_path() -> Result<PathBuf, Diagnostic> {
+    create_dir(PathBuf::from(".build/"))
+}
 
-    fs::write(&path, ir).unwrap();
+fn llvm_ir_path() -> Result<PathBuf, Diagnostic> {
+    create_dir(output_path()?.join("ir/"))
+}
 
-    let clang_build = Command::new("clang")
-        .arg(path)
-        .arg(STDLIB_PATH)
-        .arg("-o")
-        .arg(&output_path)
-        .output();
+fn output_binary_path() -> Result<PathBuf, Diagnostic> {
+    Ok(output_path()?.join("output"))
+}
 
-    if let Ok(output) = clang_build {
-        if output.status.success() {
-            Ok(output_path)
-        } else {
-            eprintln!(
-                "> Error [{}] while building the binary:\n{}",
-                output.status,
-                std::str::from_utf8(&output.stderr).unwrap()
-            );
-            Err(())
+/// Writes the outputs of `emit` to `.build`. Returns the path of the executable, if
+/// one was linked.
+pub fn build(
+    source: &Path,
+    codegen: &CodeGen,
+    emit: &[Emit],
+    target: &TargetOptions,
+) -> Result<Option<PathBuf>, Diagnostic> {
+    let stem = source.file_stem().unwrap_or(source.as_os_str());
+    let mut binary = None;
+    for kind in emit {
+        match kind {
+            Emit::LlvmIr => {
+                let path = llvm_ir_path()?.join(stem).with_extension("ll");
+                codegen.write_llvm_ir(&path)
+            }
+            Emit::LlvmBc => {
+                let path = llvm_ir_path()?.join(stem).with_extension("bc");
+                codegen.write_llvm_bc(&path)
+            }
+            Emit::Asm => codegen.write_asm(&output_path()?.join(stem).with_extension("s")),
+            Emit::Obj => codegen.write_object(&output_path()?.join(stem).with_extension("o")),
+            Emit::Exe => {
+                let object = output_path()?.join(stem).with_extension("o");
+                codegen
+                    .write_object(&object)
+                    .map_err(|err| Diagnostic::from(&err))?;
+                let output = output_binary_path()?;
+                link(&object, &output, target.triple.as_deref())?;
+                binary = Some(output);
+                Ok(())
+            }
         }
-    } else {
-        eprintln!("> Error running clang to build the binary.");
-        Err(())
+        .map_err(|err| Diagnostic::from(&err))?;
     }
+    Ok(binary)
 }
 
-pub fn run(output: std::path::PathBuf) {
-    let output = std::process::Command::new(output)
+/// Links the object file with the stdlib into an executable
+fn link(object: &Path, output: &Path, triple: Option<&str>) -> Result<(), Diagnostic> {
+    let stdlib = stdlib_path(triple);
+    if !stdlib.exists() {
+        let build = match triple {
+            Some(triple) => format!("cargo build -p stdlib --target {}", triple),
+            None => "cargo build -p stdlib".to_string(),
+        };
+        return Err(Diagnostic::error(&format!(
+            "The stdlib was not found at `{}`",
+            stdlib.display()
+        ))
+        .with_code(ErrorCode::LinkFailed)
+        .with_help(&format!("build it with `{}`", build)));
+    }
+
+    let linkers = match triple {
+        Some(_) => CROSS_LINKERS.as_slice(),
+        None => LINKERS.as_slice(),
+    };
+    for linker in linkers {
+        let mut command = Command::new(linker);
+        if let Some(triple) = triple {
+            command.arg(format!("--target={}", triple));
+        }
+        let result = command
+            .arg(object)
+            .arg(&stdlib)
+            .arg("-o")
+            .arg(output)
+            .output();
+        return match result {
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => Err(
+                Diagnostic::error(&format!("Cannot run `{}`: {}", linker, err))
+                    .with_code(ErrorCode::LinkFailed),
+            ),
+            Ok(result) if result.status.success() => Ok(()),
+            Ok(result) => Err(Diagnostic::error(&format!(
+                "`{}` failed to link `{}` [{}]",
+                linker,
+                output.display(),
+                result.status
+            ))
+            .with_code(ErrorCode::LinkFailed)
+            .with_note(String::from_utf8_lossy(&result.stderr).trim_end())),
+        };
+    }
+    Err(Diagnostic::error("No linker found")
+        .with_code(ErrorCode::LinkFailed)
+        .with_help(&format!(
+            "install one of {}, or use `--emit=obj` and link the object file yourself",
+            linkers
+                .iter()
+                .map(|linker| format!("`{}`", linker))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+}
+
+/// Runs the executable with the arguments, sharing stdin, stdout and stderr with it.
+/// Returns its exit code, which is 128 plus the signal when a signal killed it, as
+/// shells report it.
+pub fn run(output: &Path, args: &[String]) -> Result<i32, Diagnostic> {
+    let status = Command::new(output)
+        .args(args)
         .status()
-        .unwrap()
-        .code()
-        .unwrap();
-    println!("Exit Code: {}", output);
+        .map_err(|err| Diagnostic::error(&format!("Cannot run `{}`: {}", output.display(), err)))?;
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return Ok(128 + signal);
+    }
+    Ok(status.code().unwrap_or(1))
 }
@@ -1,7 +1,15 @@
-use std::{env::args, fs};
+use std::{
+    env::args,
+    fs,
+    io::{IsTerminal, stderr},
+    path::Path,
+    process::exit,
+};
 
+use args::ErrorFormat;
 use backend_llvm::CodeGen;
 use build::{build, run};
+use diagnostics::{Diagnostic, ErrorCode, Renderer};
 use inkwell::context::Context;
 use lexer::lexer::Lexer;
 use parser::Parser;
@@ -18,6 +26,12 @@ fn main() {
         let path = parsed_args.path.unwrap();
         let contents = fs::read_to_string(&path).unwrap();
 
+        let renderer = Renderer::new(&path, &contents).with_color(stderr().is_terminal());
+        let report = |diag: Diagnostic| match parsed_args.error_format {
+            ErrorFormat::Human => eprintln!("{}", renderer.render(&diag)),
+            ErrorFormat::Json => eprintln!("{}", renderer.render_json(&diag)),
+        };
+
         let lexer = Lexer::new(&contents).tokenize();
         if parsed_args.parser_opts.print_lexer_ouput {
             println!("{:#?}", lexer);
@@ -25,29 +39,84 @@ fn main() {
 
         let context = Context::create();
 
-        let mut parser = Parser::new(lexer).parse().unwrap();
+        let mut parser = match Parser::new(lexer).parse() {
+            Ok(ast) => ast,
+            Err(err) => {
+                report((&err).into());
+                exit(1);
+            }
+        };
 
         if parsed_args.parser_opts.print_ast_output {
             println!("{:#?}", parser);
         }
 
-        let mut pass_manager = PassManager::new(&mut parser);
-        pass_manager.invoke();
-
-        let codegen = CodeGen::new(&context, parser, parsed_args.compiler_opts.jit);
-        codegen.codegen().unwrap();
-
-        parsed_args
-            .compiler_opts
-            .jit
-            .then(|| {
-                let exit_code = codegen.run_with_jit();
-                println!("Exit Code: {}", exit_code.unwrap());
-            })
-            .or_else(|| {
-                let ir = codegen.ir_as_string();
-                let output = build(path.into(), ir).unwrap();
-                parsed_args.compiler_opts.run.then(|| run(output))
-            });
+        let mut pass_manager = PassManager::new(&mut parser).with_lints(parsed_args.lint_levels);
+        let result = pass_manager.invoke();
+        if parsed_args.time_passes {
+            for (name, duration) in pass_manager.timings() {
+                eprintln!("time: {:>12.3?}  {}", duration, name);
+            }
+        }
+        match result {
+            Ok(warnings) => {
+                for warning in warnings.iter() {
+                    report(warning.into());
+                }
+            }
+            Err(errors) => {
+                for err in errors.iter() {
+                    report(err.into());
+                }
+                exit(1);
+            }
+        }
+
+        let codegen = CodeGen::new(&context, parser, parsed_args.compiler_opts.jit)
+            .with_runtime_checks(parsed_args.compiler_opts.runtime_checks)
+            .with_opt_level(parsed_args.compiler_opts.opt_level)
+            .with_target(parsed_args.compiler_opts.target.clone())
+            .with_source_file_name(&path)
+            .with_debug_info(parsed_args.compiler_opts.debug_info);
+        if let Err(err) = codegen.codegen().and_then(|_| codegen.optimize()) {
+            report((&err).into());
+            exit(1);
+        }
+
+        let opts = &parsed_args.compiler_opts;
+        if opts.jit {
+            // Like in C, the arguments of the program start with its name
+            let mut argv = vec![path.clone()];
+            argv.extend(parsed_args.program_args.iter().cloned());
+            match codegen.run_with_jit(&argv) {
+                Ok(Some(code)) => exit(code),
+                Ok(None) => {
+                    report(
+                        Diagnostic::error("No `main` function to run")
+                            .with_code(ErrorCode::UnknownFunction),
+                    );
+                    exit(1);
+                }
+                Err(err) => {
+                    report((&err).into());
+                    exit(1);
+                }
+            }
+        }
+
+        match build(Path::new(&path), &codegen, &opts.emit, &opts.target) {
+            Ok(Some(output)) if opts.run => match run(&output, &parsed_args.program_args) {
+                Ok(code) => exit(code),
+                Err(diag) => {
+                    report(diag);
+                    exit(1);
+                }
+            },
+            Ok(_) => {}
+            Err(diag) => {
+                report(diag);
+                exit(1);
+            }
+        }
     }
 }
@@ -0,0 +1,878 @@
+use std::{fmt::Display, str::FromStr};
+
+/// The kind of an error, shown as a stable code such as `E0042` that can be
+/// searched for in logs and explained with `compette explain`.
+///
+/// Codes are never reused: new kinds get new numbers, and the numbers of removed
+/// kinds are left unused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    UnexpectedToken,
+    UnexpectedEof,
+    InvalidStatement,
+    MisplacedAttribute,
+    UnknownAttribute,
+    DeniedLint,
+    UnknownVariable,
+    UnknownFunction,
+    UnknownType,
+    UnknownModule,
+    UnknownField,
+    UnknownMethod,
+    DuplicateDefinition,
+    RecursiveType,
+    UnsizedArray,
+    MismatchedTypes,
+    InvalidOperands,
+    InvalidCast,
+    WrongArgumentCount,
+    MissingField,
+    NotIndexable,
+    NotIterable,
+    NoValue,
+    InvalidTry,
+    UnusedResult,
+    InvalidFormat,
+    ImmutableAssignment,
+    InvalidAssignment,
+    MissingReturn,
+    InvalidReturn,
+    BreakOutsideLoop,
+    ConstantOverflow,
+    DivisionByZero,
+    Unsupported,
+    WriteFailed,
+    LinkFailed,
+    UnknownTarget,
+    Internal,
+}
+
+/// The long form description of an error code
+pub struct Explanation {
+    pub summary: &'static str,
+    pub description: &'static str,
+    /// Code that causes the error, and the same code without it
+    pub example: Option<(&'static str, &'static str)>,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 38] = [
+        ErrorCode::UnexpectedToken,
+        ErrorCode::UnexpectedEof,
+        ErrorCode::InvalidStatement,
+        ErrorCode::MisplacedAttribute,
+        ErrorCode::UnknownAttribute,
+        ErrorCode::DeniedLint,
+        ErrorCode::UnknownVariable,
+        ErrorCode::UnknownFunction,
+        ErrorCode::UnknownType,
+        ErrorCode::UnknownModule,
+        ErrorCode::UnknownField,
+        ErrorCode::UnknownMethod,
+        ErrorCode::DuplicateDefinition,
+        ErrorCode::RecursiveType,
+        ErrorCode::UnsizedArray,
+        ErrorCode::MismatchedTypes,
+        ErrorCode::InvalidOperands,
+        ErrorCode::InvalidCast,
+        ErrorCode::WrongArgumentCount,
+        ErrorCode::MissingField,
+        ErrorCode::NotIndexable,
+        ErrorCode::NotIterable,
+        ErrorCode::NoValue,
+        ErrorCode::InvalidTry,
+        ErrorCode::UnusedResult,
+        ErrorCode::InvalidFormat,
+        ErrorCode::ImmutableAssignment,
+        ErrorCode::InvalidAssignment,
+        ErrorCode::MissingReturn,
+        ErrorCode::InvalidReturn,
+        ErrorCode::BreakOutsideLoop,
+        ErrorCode::ConstantOverflow,
+        ErrorCode::DivisionByZero,
+        ErrorCode::Unsupported,
+        ErrorCode::WriteFailed,
+        ErrorCode::LinkFailed,
+        ErrorCode::UnknownTarget,
+        ErrorCode::Internal,
+    ];
+
+    pub fn number(&self) -> u32 {
+        match self {
+            ErrorCode::UnexpectedToken => 1,
+            ErrorCode::UnexpectedEof => 2,
+            ErrorCode::InvalidStatement => 3,
+            ErrorCode::MisplacedAttribute => 4,
+            ErrorCode::UnknownAttribute => 5,
+            ErrorCode::DeniedLint => 6,
+            ErrorCode::UnknownVariable => 10,
+            ErrorCode::UnknownFunction => 11,
+            ErrorCode::UnknownType => 12,
+            ErrorCode::UnknownModule => 13,
+            ErrorCode::UnknownField => 14,
+            ErrorCode::UnknownMethod => 15,
+            ErrorCode::DuplicateDefinition => 16,
+            ErrorCode::RecursiveType => 17,
+            ErrorCode::UnsizedArray => 18,
+            ErrorCode::MismatchedTypes => 20,
+            ErrorCode::InvalidOperands => 21,
+            ErrorCode::InvalidCast => 22,
+            ErrorCode::WrongArgumentCount => 23,
+            ErrorCode::MissingField => 24,
+            ErrorCode::NotIndexable => 25,
+            ErrorCode::NotIterable => 26,
+            ErrorCode::NoValue => 27,
+            ErrorCode::InvalidTry => 28,
+            ErrorCode::UnusedResult => 29,
+            ErrorCode::InvalidFormat => 30,
+            ErrorCode::ImmutableAssignment => 40,
+            ErrorCode::InvalidAssignment => 41,
+            ErrorCode::MissingReturn => 42,
+            ErrorCode::InvalidReturn => 43,
+            ErrorCode::BreakOutsideLoop => 44,
+            ErrorCode::ConstantOverflow => 50,
+            ErrorCode::DivisionByZero => 51,
+            ErrorCode::Unsupported => 60,
+            ErrorCode::WriteFailed => 70,
+            ErrorCode::LinkFailed => 71,
+            ErrorCode::UnknownTarget => 72,
+            ErrorCode::Internal => 99,
+        }
+    }
+
+    pub fn explain(&self) -> Explanation {
+        let (summary, description, example) = match self {
+            ErrorCode::UnexpectedToken => (
+                "A token that doesn't fit the syntax was found",
+                "The parser expected a different token at this point, for example a name after the \
+type of a `let` statement, or a closing parenthesis at the end of an expression.",
+                Some((
+                    "func main() u32 {
+    let u32 = 1
+    return 0
+}",
+                    "func main() u32 {
+    let u32 a = 1
+    return a
+}",
+                )),
+            ),
+            ErrorCode::UnexpectedEof => (
+                "The file ended in the middle of an expression",
+                "The end of the file was reached while an expression was still being parsed.",
+                Some((
+                    "func main() u32 {
+    return 1 +",
+                    "func main() u32 {
+    return 1 + 2
+}",
+                )),
+            ),
+            ErrorCode::InvalidStatement => (
+                "A statement or item cannot start with this token",
+                "Only imports, `extern` declarations, structs, functions and attributes can be \
+written at the top level of a file. Blocks contain `let` statements, assignments, calls, \
+conditionals, loops, `break` and `return`; other expressions cannot be used as statements.",
+                Some((
+                    "let u32 limit = 10
+
+func main() u32 {
+    return limit
+}",
+                    "func main() u32 {
+    let u32 limit = 10
+    return limit
+}",
+                )),
+            ),
+            ErrorCode::MisplacedAttribute => (
+                "An attribute is not followed by a function or a struct",
+                "Attributes such as `#[allow(unused_variables)]` apply to the function or struct \
+that follows them, and cannot be placed before anything else.",
+                Some((
+                    "#[allow(unused_imports)]
+import std::io
+
+func main() u32 {
+    return 0
+}",
+                    "import std::io
+
+#[allow(unused_imports)]
+func main() u32 {
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::UnknownAttribute => (
+                "An attribute or its arguments are not known",
+                "The supported attributes are `allow`, `warn` and `deny`, which take the names of \
+lints, such as `unused_variables`, as arguments.",
+                Some((
+                    "#[inline(always)]
+func main() u32 {
+    return 0
+}",
+                    "#[allow(unused_variables)]
+func main() u32 {
+    let u32 a = 1
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::DeniedLint => (
+                "A lint that is set to `deny` found something",
+                "Lints only warn by default. Under `#[deny(...)]` or `-D <lint>` the warnings of the \
+lint become errors that stop compilation; the lint they come from is named at the end of the message.",
+                Some((
+                    "#[deny(unused_variables)]
+func main() u32 {
+    let u32 a = 1
+    return 0
+}",
+                    "#[deny(unused_variables)]
+func main() u32 {
+    let u32 a = 1
+    return a
+}",
+                )),
+            ),
+            ErrorCode::UnknownVariable => (
+                "A variable is used where it is not defined",
+                "Variables can be used after their `let` statement, until the end of the block \
+they are defined in. Parameters can be used in the whole function.",
+                Some((
+                    "func main() u32 {
+    if true {
+        let u32 a = 1
+    }
+    return a
+}",
+                    "func main() u32 {
+    let u32 a = 1
+    return a
+}",
+                )),
+            ),
+            ErrorCode::UnknownFunction => (
+                "A called function is not defined",
+                "Functions have to be defined in the same file, declared with `extern`, or be one \
+of the builtin functions. Functions of the standard library are called through their module, \
+such as `io::println`.",
+                Some((
+                    "func main() u32 {
+    return double(2)
+}",
+                    "func double(a u32) u32 {
+    return a * 2
+}
+
+func main() u32 {
+    return double(2)
+}",
+                )),
+            ),
+            ErrorCode::UnknownType => (
+                "A type is not defined",
+                "Types are either builtin, such as `u32` and `string`, or structs defined in the \
+same file.",
+                Some((
+                    "func main() u32 {
+    let Point p = { x 1, y 2 }
+    return 0
+}",
+                    "struct Point { x u32, y u32 }
+
+func main() u32 {
+    let Point p = { x 1, y 2 }
+    return p.x
+}",
+                )),
+            ),
+            ErrorCode::UnknownModule => (
+                "A module is not imported or does not exist",
+                "Functions of the standard library can only be called through modules that are \
+imported with `import` at the top of the file. The standard library has the modules `std::io`, \
+`std::fs` and `std::str`.",
+                Some((
+                    "func main() u32 {
+    io::println(\"hello\")
+    return 0
+}",
+                    "import std::io
+
+func main() u32 {
+    io::println(\"hello\")
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::UnknownField => (
+                "A struct has no field with this name",
+                "Only the fields listed in the definition of a struct can be accessed or set.",
+                Some((
+                    "struct Point { x u32, y u32 }
+
+func main() u32 {
+    let Point p = { x 1, y 2 }
+    return p.z
+}",
+                    "struct Point { x u32, y u32 }
+
+func main() u32 {
+    let Point p = { x 1, y 2 }
+    return p.y
+}",
+                )),
+            ),
+            ErrorCode::UnknownMethod => (
+                "A type has no method with this name and number of arguments",
+                "Integers, strings, arrays and Results have builtin methods, such as `wrapping_add` \
+on integers or `len` on strings and arrays. Other types have no methods.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 1
+    return a.rotating_add(1)
+}",
+                    "func main() u32 {
+    let u32 a = 1
+    return a.wrapping_add(1)
+}",
+                )),
+            ),
+            ErrorCode::DuplicateDefinition => (
+                "A name is defined twice",
+                "A variable cannot be defined again in the same block, and the parameters of a \
+function need different names. Variables of inner blocks can shadow the ones outside of them.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 1
+    let u32 a = 2
+    return a
+}",
+                    "func main() u32 {
+    let u32 a = 1
+    let u32 b = 2
+    return a + b
+}",
+                )),
+            ),
+            ErrorCode::RecursiveType => (
+                "A struct contains itself",
+                "A struct cannot have a field of its own type, directly or through the fields of \
+other structs and arrays, as its values would have an infinite size. The value can be stored \
+elsewhere, such as in an array, and referred to by its index.",
+                Some((
+                    "struct Node { value u32, next Node }
+
+func main() u32 {
+    return 0
+}",
+                    "struct Node { value u32, next u32 }
+
+func main() u32 {
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::UnsizedArray => (
+                "An array type has no size",
+                "Arrays have a fixed size, which is part of their type. The size can only be left \
+out in a `let` statement initialized with an array literal, as in `let u32[] a = [1, 2]`, and in \
+the parameters of functions, where `u32[]` takes a slice `u32[..]` of an array of any size. The \
+types of return values, struct fields and the parameters of `extern` functions need it, as in \
+`u32[2]`.",
+                Some((
+                    "func pair() u32[] {
+    return [1, 2]
+}
+
+func main() u32 {
+    let u32[2] values = pair()
+    return values[0]
+}",
+                    "func pair() u32[2] {
+    return [1, 2]
+}
+
+func main() u32 {
+    let u32[2] values = pair()
+    return values[0]
+}",
+                )),
+            ),
+            ErrorCode::MismatchedTypes => (
+                "A value has a different type than expected",
+                "Values are only converted between similar types, such as integers of different \
+sizes. Use `->` to cast between other types.",
+                Some((
+                    "func main() u32 {
+    let f32 a = 1.5
+    let u32 b = a
+    return b
+}",
+                    "func main() u32 {
+    let f32 a = 1.5
+    let u32 b = a -> u32
+    return b
+}",
+                )),
+            ),
+            ErrorCode::InvalidOperands => (
+                "An operator cannot be applied to these values",
+                "Arithmetic works on numbers, bitwise operators on integers, and strings only \
+support `+` and comparisons.",
+                Some((
+                    "func main() u32 {
+    let bool a = true
+    let bool b = a + a
+    return 0
+}",
+                    "func main() u32 {
+    let u32 a = 1
+    let u32 b = a + a
+    return b
+}",
+                )),
+            ),
+            ErrorCode::InvalidCast => (
+                "A value cannot be cast to this type",
+                "`->` converts between numbers and bools, and between strings and cstrings. \
+Arrays, structs and Results cannot be cast.",
+                Some((
+                    "func main() u32 {
+    let u32[] a = [1, 2]
+    return a -> u32
+}",
+                    "func main() u32 {
+    let u32[] a = [1, 2]
+    return a[0]
+}",
+                )),
+            ),
+            ErrorCode::WrongArgumentCount => (
+                "A function is called with the wrong number of arguments",
+                "Calls have to pass one argument for every parameter of the function.",
+                Some((
+                    "func add(a u32, b u32) u32 {
+    return a + b
+}
+
+func main() u32 {
+    return add(1)
+}",
+                    "func add(a u32, b u32) u32 {
+    return a + b
+}
+
+func main() u32 {
+    return add(1, 2)
+}",
+                )),
+            ),
+            ErrorCode::MissingField => (
+                "A struct is created without one of its fields",
+                "Every field of a struct has to be given a value when the struct is created.",
+                Some((
+                    "struct Point { x u32, y u32 }
+
+func main() u32 {
+    let Point p = { x 1 }
+    return p.x
+}",
+                    "struct Point { x u32, y u32 }
+
+func main() u32 {
+    let Point p = { x 1, y 0 }
+    return p.x
+}",
+                )),
+            ),
+            ErrorCode::NotIndexable => (
+                "A value that isn't an array is indexed",
+                "Only arrays can be indexed with `[]`, and their index has to be an integer.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 1
+    return a[0]
+}",
+                    "func main() u32 {
+    let u32[] a = [1]
+    return a[0]
+}",
+                )),
+            ),
+            ErrorCode::NotIterable => (
+                "`loop range` is used on a value that isn't an array",
+                "`loop range` iterates over arrays stored in variables.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 3
+    loop range val, i = a {
+    }
+    return 0
+}",
+                    "func main() u32 {
+    let u32[] a = [1, 2, 3]
+    loop range val, i = a {
+    }
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::NoValue => (
+                "A call that returns nothing is used as a value",
+                "Functions without a return type don't produce a value, so they can only be \
+called as statements.",
+                Some((
+                    "func log() {
+}
+
+func main() u32 {
+    let u32 a = log()
+    return a
+}",
+                    "func log() {
+}
+
+func main() u32 {
+    log()
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::InvalidTry => (
+                "The `?` operator is used where it cannot return the error",
+                "`?` can only be applied to Results, inside functions that return a Result with \
+the same error type.",
+                Some((
+                    "func half(a u32) Result<u32, u32> {
+    return ok(a / 2)
+}
+
+func main() u32 {
+    return half(4)?
+}",
+                    "func half(a u32) Result<u32, u32> {
+    return ok(a / 2)
+}
+
+func main() u32 {
+    let Result<u32, u32> r = half(4)
+    return r.unwrap_or(0)
+}",
+                )),
+            ),
+            ErrorCode::UnusedResult => (
+                "A Result is silently discarded",
+                "The errors of functions returning a Result have to be handled, by storing the \
+Result, returning it or using `?`.",
+                Some((
+                    "func check(a u32) Result<u32, u32> {
+    return ok(a)
+}
+
+func main() u32 {
+    check(1)
+    return 0
+}",
+                    "func check(a u32) Result<u32, u32> {
+    return ok(a)
+}
+
+func main() u32 {
+    let Result<u32, u32> r = check(1)
+    return r.unwrap_or(0)
+}",
+                )),
+            ),
+            ErrorCode::InvalidFormat => (
+                "A format string doesn't match its arguments",
+                "Every `{}` in the format string of `io::print` and `io::println` takes one \
+argument, `{name}` needs a variable in scope, and `{:.N}` only formats floats. Results cannot \
+be formatted.",
+                Some((
+                    "import std::io
+
+func main() u32 {
+    io::println(\"{} and {}\", 1)
+    return 0
+}",
+                    "import std::io
+
+func main() u32 {
+    io::println(\"{} and {}\", 1, 2)
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::ImmutableAssignment => (
+                "An immutable variable is assigned to",
+                "Variables and parameters can only be changed when a `!` follows their type. \
+This includes setting their fields and elements. The variables of `loop range` cannot be \
+assigned to.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 1
+    a = 2
+    return a
+}",
+                    "func main() u32 {
+    let u32! a = 1
+    a = 2
+    return a
+}",
+                )),
+            ),
+            ErrorCode::InvalidAssignment => (
+                "The left-hand side of an assignment cannot be assigned to",
+                "Only variables, and fields and elements of them, can be assigned to.",
+                Some((
+                    "func main() u32 {
+    let u32! a = 1
+    a.wrapping_add(1) = 2
+    return a
+}",
+                    "func main() u32 {
+    let u32! a = 1
+    a = a.wrapping_add(1)
+    return a
+}",
+                )),
+            ),
+            ErrorCode::MissingReturn => (
+                "A function with a return type does not return a value on every path",
+                "Functions that have a return type must end every path through their body with a \
+`return`, a panic, or a loop that never ends.",
+                Some((
+                    "func sign(a i32) i32 {
+    if a < 0 {
+        return 0 - 1
+    }
+}
+
+func main() i32 {
+    return sign(1)
+}",
+                    "func sign(a i32) i32 {
+    if a < 0 {
+        return 0 - 1
+    }
+    return 1
+}
+
+func main() i32 {
+    return sign(1)
+}",
+                )),
+            ),
+            ErrorCode::InvalidReturn => (
+                "A `return` doesn't match the return type of the function",
+                "Functions with a return type must return a value, and functions without one \
+cannot return a value.",
+                Some((
+                    "func zero() {
+    return 0
+}
+
+func main() u32 {
+    zero()
+    return 0
+}",
+                    "func zero() u32 {
+    return 0
+}
+
+func main() u32 {
+    return zero()
+}",
+                )),
+            ),
+            ErrorCode::BreakOutsideLoop => (
+                "A `break` is not inside a loop",
+                "`break` exits the innermost loop it is written in, so it cannot be used outside \
+of one. Use `return` to leave a function early.",
+                Some((
+                    "func main() u32 {
+    break
+    return 0
+}",
+                    "func main() u32 {
+    loop {
+        break
+    }
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::ConstantOverflow => (
+                "An expression computed at compile time overflows its type",
+                "Expressions whose values are known at compile time are computed by the compiler, \
+which rejects results that don't fit in the type of the expression. Use a larger type, or \
+the `wrapping_` methods if wrapping around is intended.",
+                Some((
+                    "func main() u8 {
+    let u8 a = 200 + 100
+    return a
+}",
+                    "func main() u16 {
+    let u16 a = 200 + 100
+    return a
+}",
+                )),
+            ),
+            ErrorCode::DivisionByZero => (
+                "An expression computed at compile time divides by zero",
+                "Divisions and remainders whose divisor is known at compile time are rejected \
+when the divisor is zero.",
+                Some((
+                    "func main() u32 {
+    let u32 a = 10 / 0
+    return a
+}",
+                    "func main() u32 {
+    let u32 a = 10 / 2
+    return a
+}",
+                )),
+            ),
+            ErrorCode::Unsupported => (
+                "The code is valid, but the compiler can't generate code for it yet",
+                "Some constructs pass every check but aren't supported when generating code, \
+like methods on floats or imported functions used without calling them. The error names \
+the construct, rewriting the code without it avoids the error.",
+                Some((
+                    "import std::io
+
+func main() u32 {
+    io::printint
+    return 0
+}",
+                    "import std::io
+
+func main() u32 {
+    io::printint(0)
+    return 0
+}",
+                )),
+            ),
+            ErrorCode::WriteFailed => (
+                "An output file could not be written",
+                "The compiler writes LLVM IR, bitcode, assembly and object files to the `.build` \
+directory. Check that the directory can be created and written to, and that there is space left \
+on the disk.",
+                None,
+            ),
+            ErrorCode::LinkFailed => (
+                "The program could not be linked into an executable",
+                "Executables are linked by a C compiler such as `cc`, `clang` or `gcc`, which \
+has to be installed. Use `--emit=obj` to only write the object file and link it yourself.",
+                None,
+            ),
+            ErrorCode::UnknownTarget => (
+                "The target given with `--target` is not supported",
+                "`--target` takes a target triple such as `aarch64-unknown-linux-gnu` or \
+`wasm32-unknown-unknown`, and `--cpu` and `--features` have to exist for that target. The \
+targets that can be used are the ones LLVM was built with.",
+                None,
+            ),
+            ErrorCode::Internal => (
+                "The compiler reached a state it should not be in",
+                "This is a bug in the compiler, for example an error from LLVM or a construct \
+that an earlier pass should have rejected or lowered. Please report it along with the code \
+that caused it.",
+                None,
+            ),
+        };
+        Explanation {
+            summary,
+            description,
+            example,
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "E{:04}", self.number())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .into_iter()
+            .find(|code| code.to_string().eq_ignore_ascii_case(s))
+            .ok_or(format!("Unknown error code `{}`", s))
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}\n\n{}", self.summary, self.description)?;
+        if let Some((failing, fixed)) = self.example {
+            let indent = |code: &str| {
+                code.lines()
+                    .map(|line| format!("    {}", line).trim_end().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            write!(
+                f,
+                "\nErroneous code example:\n\n{}\n\nFixed:\n\n{}\n",
+                indent(failing),
+                indent(fixed)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::ErrorCode;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let numbers: HashSet<u32> = ErrorCode::ALL.iter().map(|code| code.number()).collect();
+        assert_eq!(numbers.len(), ErrorCode::ALL.len());
+        for code in ErrorCode::ALL {
+            assert_eq!(code.to_string().parse(), Ok(code));
+        }
+        assert_eq!("e0042".parse(), Ok(ErrorCode::MissingReturn));
+        assert_eq!(
+            "E9999".parse::<ErrorCode>(),
+            Err("Unknown error code `E9999`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(
+            ErrorCode::DivisionByZero.explain().to_string(),
+            "\
+An expression computed at compile time divides by zero
+
+Divisions and remainders whose divisor is known at compile time are rejected when the divisor is zero.
+
+Erroneous code example:
+
+    func main() u32 {
+        let u32 a = 10 / 0
+        return a
+    }
+
+Fixed:
+
+    func main() u32 {
+        let u32 a = 10 / 2
+        return a
+    }
+"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Diagnostic, Renderer};
+
+impl Renderer<'_> {
+    /// One line of JSON, for tools that read the output of the compiler. Lines and
+    /// columns are counted from one, `end_column` is the column after the span, and
+    /// `rendered` holds the output of `render` without colors.
+    pub fn render_json(&self, diag: &Diagnostic) -> String {
+        let spans: Vec<String> = diag
+            .labels
+            .iter()
+            .map(|label| {
+                let (column, len) = self.resolve(label.span);
+                format!(
+                    r#"{{"line":{},"column":{},"end_column":{},"label":{},"primary":{}}}"#,
+                    label.span.line + 1,
+                    column + 1,
+                    column + len + 1,
+                    string(&label.msg),
+                    label.primary
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"level":{},"code":{},"message":{},"file":{},"spans":[{}],"notes":{},"help":{},"rendered":{}}}"#,
+            string(&diag.level.to_string()),
+            diag.code
+                .map_or("null".to_string(), |code| string(&code.to_string())),
+            string(&diag.msg),
+            string(self.path),
+            spans.join(","),
+            array(&diag.notes),
+            array(&diag.help),
+            string(&self.clone().with_color(false).render(diag))
+        )
+    }
+}
+
+fn array(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| string(item)).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn string(value: &str) -> String {
+    let mut out = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Diagnostic, Renderer, Span};
+
+    #[test]
+    fn test_render_json() {
+        let source = "func main() {\n    let str a = \"\\t\"\n}";
+        let diag = Diagnostic::warning("Unused `a`")
+            .with_primary(Span::line(1), "")
+            .with_label(Span::new(1, 12, 1), "here")
+            .with_note("`unused_variables` lint");
+        assert_eq!(
+            Renderer::new("dir/main.slpe", source)
+                .with_color(true)
+                .render_json(&diag),
+            concat!(
+                r#"{"level":"warning","code":null,"message":"Unused `a`","file":"dir/main.slpe","#,
+                r#""spans":[{"line":2,"column":5,"end_column":21,"label":"","primary":true},"#,
+                r#"{"line":2,"column":13,"end_column":14,"label":"here","primary":false}],"#,
+                r#""notes":["`unused_variables` lint"],"help":[],"#,
+                r#""rendered":"warning: Unused `a`\n --> dir/main.slpe:2:5\n  |\n"#,
+                r#"2 |     let str a = \"\\t\"\n  |     ^^^^^^^^^^^^^^^^\n  |             - here\n"#,
+                r#"  |\n  = note: `unused_variables` lint\n"}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Errors and warnings as they are shown to the user.
+//!
+//! The crates of the compiler turn their errors into a [`Diagnostic`], which a
+//! [`Renderer`] prints next to the source it points at, or as JSON.
+
+mod codes;
+mod json;
+mod render;
+
+use std::fmt::Display;
+
+pub use codes::{ErrorCode, Explanation};
+pub use render::Renderer;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Error => write!(f, "error"),
+            Level::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A range of the source. Lines and columns are counted from zero, like the lexer does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    /// The whole line, without its indentation, when this is `None`
+    pub column: Option<usize>,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, len: usize) -> Self {
+        Self {
+            line,
+            column: Some(column),
+            len: len.max(1),
+        }
+    }
+
+    /// For errors that only know the line they were found on
+    pub fn line(line: usize) -> Self {
+        Self {
+            line,
+            column: None,
+            len: 0,
+        }
+    }
+}
+
+/// Underlines a span, with `^` for the primary label and `-` for the others
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub msg: String,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub code: Option<ErrorCode>,
+    pub msg: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, msg: &str) -> Self {
+        Self {
+            level,
+            code: None,
+            msg: msg.to_string(),
+            labels: vec![],
+            notes: vec![],
+            help: vec![],
+        }
+    }
+
+    pub fn error(msg: &str) -> Self {
+        Self::new(Level::Error, msg)
+    }
+
+    pub fn warning(msg: &str) -> Self {
+        Self::new(Level::Warning, msg)
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Points at the cause of the diagnostic, the first primary span is shown in the header
+    pub fn with_primary(mut self, span: Span, msg: &str) -> Self {
+        self.labels.push(Label {
+            span,
+            msg: msg.to_string(),
+            primary: true,
+        });
+        self
+    }
+
+    /// Points at code related to the cause
+    pub fn with_label(mut self, span: Span, msg: &str) -> Self {
+        self.labels.push(Label {
+            span,
+            msg: msg.to_string(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help.push(help.to_string());
+        self
+    }
+
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .map(|label| label.span)
+    }
+}
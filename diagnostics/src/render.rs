@@ -0,0 +1,237 @@
+use std::fmt::Write;
+
+use crate::{Diagnostic, Label, Level, Span};
+
+const RED: &str = "1;31";
+const YELLOW: &str = "1;33";
+const BLUE: &str = "1;34";
+const BOLD: &str = "1";
+
+/// Prints diagnostics the way rustc does: a header with the location of the
+/// primary span, then the lines the labels point at, then the notes.
+#[derive(Debug, Clone)]
+pub struct Renderer<'a> {
+    pub(crate) path: &'a str,
+    lines: Vec<&'a str>,
+    color: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(path: &'a str, source: &'a str) -> Self {
+        Self {
+            path,
+            lines: source.lines().collect(),
+            color: false,
+        }
+    }
+
+    /// Uses ANSI escape codes, for output to a terminal
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let level_color = match diag.level {
+            Level::Error => RED,
+            Level::Warning => YELLOW,
+        };
+        let width = diag
+            .labels
+            .iter()
+            .map(|label| (label.span.line + 1).to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+        let mut out = String::new();
+
+        let level = match diag.code {
+            Some(code) => format!("{}[{}]", diag.level, code),
+            None => diag.level.to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(level_color, &level),
+            self.paint(BOLD, &format!(": {}", diag.msg))
+        );
+        let location = match diag.primary_span().or(diag.labels.first().map(|l| l.span)) {
+            Some(span) => {
+                let (column, _) = self.resolve(span);
+                format!("{}:{}:{}", self.path, span.line + 1, column + 1)
+            }
+            None => self.path.to_string(),
+        };
+        let _ = writeln!(out, "{}{} {}", pad, self.paint(BLUE, "-->"), location);
+
+        let mut lines: Vec<usize> = diag.labels.iter().map(|label| label.span.line).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        if !lines.is_empty() {
+            let _ = writeln!(out, "{} {}", pad, self.paint(BLUE, "|"));
+        }
+        let mut previous: Option<usize> = None;
+        for line in lines {
+            match previous {
+                // A single line between two labels is shown rather than elided
+                Some(prev) if line == prev + 2 => self.source_line(&mut out, prev + 1, width),
+                Some(prev) if line > prev + 2 => {
+                    let _ = writeln!(out, "{}", self.paint(BLUE, "..."));
+                }
+                _ => {}
+            }
+            self.source_line(&mut out, line, width);
+            for label in diag.labels.iter().filter(|label| label.span.line == line) {
+                self.underline(&mut out, label, &pad, level_color);
+            }
+            previous = Some(line);
+        }
+
+        if !diag.notes.is_empty() || !diag.help.is_empty() {
+            if previous.is_some() {
+                let _ = writeln!(out, "{} {}", pad, self.paint(BLUE, "|"));
+            }
+            let notes = diag.notes.iter().map(|note| ("note", note));
+            for (kind, msg) in notes.chain(diag.help.iter().map(|help| ("help", help))) {
+                let _ = writeln!(
+                    out,
+                    "{} {} {}: {}",
+                    pad,
+                    self.paint(BLUE, "="),
+                    self.paint(BOLD, kind),
+                    msg
+                );
+            }
+        }
+        out
+    }
+
+    fn source_line(&self, out: &mut String, line: usize, width: usize) {
+        let number = self.paint(BLUE, &format!("{:>width$} |", line + 1));
+        let _ = match self.lines.get(line).copied().unwrap_or_default() {
+            "" => writeln!(out, "{}", number),
+            text => writeln!(out, "{} {}", number, text),
+        };
+    }
+
+    fn underline(&self, out: &mut String, label: &Label, pad: &str, level_color: &str) {
+        let (column, len) = self.resolve(label.span);
+        let text = self.lines.get(label.span.line).copied().unwrap_or_default();
+        // Tabs are kept so that the marks line up with the source
+        let indent: String = match text.get(..column) {
+            Some(prefix) => prefix
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect(),
+            None => " ".repeat(column),
+        };
+        let (mark, color) = if label.primary {
+            ("^", level_color)
+        } else {
+            ("-", BLUE)
+        };
+        let mut marks = mark.repeat(len);
+        if !label.msg.is_empty() {
+            marks = format!("{} {}", marks, label.msg);
+        }
+        let _ = writeln!(
+            out,
+            "{} {} {}{}",
+            pad,
+            self.paint(BLUE, "|"),
+            indent,
+            self.paint(color, &marks)
+        );
+    }
+
+    /// The column and length of the span, spans of a whole line cover the
+    /// line without its indentation
+    pub(crate) fn resolve(&self, span: Span) -> (usize, usize) {
+        let text = self.lines.get(span.line).copied().unwrap_or_default();
+        match span.column {
+            Some(column) => (column.min(text.len()), span.len.max(1)),
+            None => {
+                let trimmed = text.trim();
+                (text.len() - text.trim_start().len(), trimmed.len().max(1))
+            }
+        }
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Renderer;
+    use crate::{Diagnostic, ErrorCode, Span};
+
+    const SOURCE: &str = "func main() u32 {
+    let u32 a = 1
+    let u32 b = 2
+    let u32 c = 3
+    a = 4
+\tc = 5
+    return a
+}";
+
+    #[test]
+    fn test_render_labels() {
+        let diag = Diagnostic::error("Cannot assign to immutable variable `a`")
+            .with_code(ErrorCode::ImmutableAssignment)
+            .with_primary(Span::line(4), "cannot be assigned twice")
+            .with_label(Span::new(1, 12, 1), "`a` defined here")
+            .with_help("make `a` mutable: `let u32! a`");
+        let expected = "\
+error[E0040]: Cannot assign to immutable variable `a`
+ --> main.slpe:5:5
+  |
+2 |     let u32 a = 1
+  |             - `a` defined here
+...
+5 |     a = 4
+  |     ^^^^^ cannot be assigned twice
+  |
+  = help: make `a` mutable: `let u32! a`
+";
+        assert_eq!(Renderer::new("main.slpe", SOURCE).render(&diag), expected);
+
+        let diag = Diagnostic::warning("Unused")
+            .with_primary(Span::new(3, 12, 1), "")
+            .with_primary(Span::line(5), "")
+            .with_note("`unused_variables` lint");
+        let expected = "\
+warning: Unused
+ --> main.slpe:4:13
+  |
+4 |     let u32 c = 3
+  |             ^
+5 |     a = 4
+6 | \tc = 5
+  | \t^^^^^
+  |
+  = note: `unused_variables` lint
+";
+        assert_eq!(Renderer::new("main.slpe", SOURCE).render(&diag), expected);
+    }
+
+    #[test]
+    fn test_render_without_span() {
+        let diag = Diagnostic::error("Function not found");
+        assert_eq!(
+            Renderer::new("main.slpe", SOURCE).render(&diag),
+            "error: Function not found\n --> main.slpe\n"
+        );
+        assert_eq!(
+            Renderer::new("main.slpe", SOURCE)
+                .with_color(true)
+                .render(&diag),
+            "\x1b[1;31merror\x1b[0m\x1b[1m: Function not found\x1b[0m\n \x1b[1;34m-->\x1b[0m main.slpe\n"
+        );
+    }
+}
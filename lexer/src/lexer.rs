@@ -123,19 +123,21 @@ impl<'a> Lexer<'a> {
     fn tokenize_symbols(&mut self, char: u8, tokens: &mut Vec<Token>) -> Option<Token> {
         return Some(Token::new(
             match char {
-                b'+' => Types::OPERATOR(Operator::PLUS),
-                b'*' => Types::OPERATOR(Operator::MULTIPLY),
+                b'+' => self.with_assign(Operator::PLUS, Operator::PLUS_ASSIGN),
+                b'*' => self.with_assign(Operator::MULTIPLY, Operator::MULTIPLY_ASSIGN),
                 b',' => Types::DELIMITER(Delimiter::COMMA),
                 b';' => Types::DELIMITER(Delimiter::SEMICOLON),
                 b'(' => Types::DELIMITER(Delimiter::LPAREN),
                 b')' => Types::DELIMITER(Delimiter::RPAREN),
                 b'[' => Types::DELIMITER(Delimiter::LBRACKET),
                 b']' => Types::DELIMITER(Delimiter::RBRACKET),
+                b'#' => Types::DELIMITER(Delimiter::HASH),
                 b'.' => Types::OPERATOR(Operator::DOT),
                 b'{' => Types::DELIMITER(Delimiter::LBRACE),
-                b'^' => Types::OPERATOR(Operator::BITWISE_XOR),
-                b'&' => Types::OPERATOR(Operator::BITWISE_AND),
-                b'|' => Types::OPERATOR(Operator::BITWISE_OR),
+                b'^' => self.with_assign(Operator::BITWISE_XOR, Operator::BITWISE_XOR_ASSIGN),
+                b'&' => self.with_assign(Operator::BITWISE_AND, Operator::BITWISE_AND_ASSIGN),
+                b'|' => self.with_assign(Operator::BITWISE_OR, Operator::BITWISE_OR_ASSIGN),
+                b'?' => Types::OPERATOR(Operator::TRY),
                 b'}' => {
                     self.pop_nl(tokens);
                     Types::DELIMITER(Delimiter::RBRACE)
@@ -163,13 +165,22 @@ impl<'a> Lexer<'a> {
         }
 
         return Some(Token::new(
-            Types::OPERATOR(Operator::DIVIDE),
+            self.with_assign(Operator::DIVIDE, Operator::DIVIDE_ASSIGN),
             None,
             self.line,
             self.column,
         ));
     }
 
+    /// `assign` if the operator is followed by `=`, like `+=`, or else `op`
+    fn with_assign(&mut self, op: Operator, assign: Operator) -> Types {
+        if self.peek_byte() == Some(b'=') {
+            self.index += 1;
+            return Types::OPERATOR(assign);
+        }
+        Types::OPERATOR(op)
+    }
+
     fn check_multi_char_type(&mut self) -> Option<Types> {
         let first_char = self.current_byte();
         let second_char = self.next_byte()?;
@@ -180,9 +191,14 @@ impl<'a> Lexer<'a> {
             (b'<', b'=') => return Some(Types::OPERATOR(Operator::LESSER_EQUAL)),
             (b'>', b'=') => return Some(Types::OPERATOR(Operator::GREATER_EQUAL)),
             (b'-', b'>') => return Some(Types::OPERATOR(Operator::CAST)),
+            (b'-', b'=') => return Some(Types::OPERATOR(Operator::MINUS_ASSIGN)),
             (b':', b':') => return Some(Types::OPERATOR(Operator::PATH)),
-            (b'>', b'>') => return Some(Types::OPERATOR(Operator::RSHIFT)),
-            (b'<', b'<') => return Some(Types::OPERATOR(Operator::LSHIFT)),
+            (b'>', b'>') => {
+                return Some(self.with_assign(Operator::RSHIFT, Operator::RSHIFT_ASSIGN));
+            }
+            (b'<', b'<') => {
+                return Some(self.with_assign(Operator::LSHIFT, Operator::LSHIFT_ASSIGN));
+            }
             _ => self.previous_byte(),
         };
 
@@ -245,6 +261,13 @@ impl<'a> Lexer<'a> {
                 Types::DATATYPE(Datatype::SIMD(Box::new(Datatype::U32), 0)),
                 None,
             ),
+            "Result" => (
+                Types::DATATYPE(Datatype::RESULT(
+                    Box::new(Datatype::NONE),
+                    Box::new(Datatype::NONE),
+                )),
+                None,
+            ),
             _ => {
                 if self.peek_byte()? == b'(' {
                     (Types::IDENTIFIER_FUNC, Some(result))
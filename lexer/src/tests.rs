@@ -100,4 +100,101 @@ let u32! b = 2
             ]
         );
     }
+
+    #[test]
+    fn check_lexer_4() {
+        let contents = r#"
+            func parse() Result<u32, i32> {
+                let u32 a = read()?
+            }
+            "#;
+        let tokens = Lexer::new(contents).tokenize();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(KEYWORD(Keyword::FUNCTION), None, 0, 0),
+                Token::new(IDENTIFIER_FUNC, Some("parse".to_string()), 0, 0),
+                Token::new(DELIMITER(Delimiter::LPAREN), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::RPAREN), None, 0, 0),
+                Token::new(
+                    DATATYPE(Datatype::RESULT(
+                        Box::new(Datatype::NONE),
+                        Box::new(Datatype::NONE)
+                    )),
+                    None,
+                    0,
+                    0
+                ),
+                Token::new(OPERATOR(Operator::LESSER), None, 0, 0),
+                Token::new(DATATYPE(Datatype::U32), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::COMMA), None, 0, 0),
+                Token::new(DATATYPE(Datatype::I32), None, 0, 0),
+                Token::new(OPERATOR(Operator::GREATER), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::LBRACE), None, 0, 0),
+                Token::new(KEYWORD(Keyword::LET), None, 0, 0),
+                Token::new(DATATYPE(Datatype::U32), None, 0, 0),
+                Token::new(IDENTIFIER, Some("a".to_string()), 0, 0),
+                Token::new(OPERATOR(Operator::ASSIGN), None, 0, 0),
+                Token::new(IDENTIFIER_FUNC, Some("read".to_string()), 0, 0),
+                Token::new(DELIMITER(Delimiter::LPAREN), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::RPAREN), None, 0, 0),
+                Token::new(OPERATOR(Operator::TRY), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::RBRACE), None, 0, 0),
+                Token::new(NL, None, 0, 0),
+                Token::new(EOF, None, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_lexer_attribute() {
+        let contents = "#[allow(unused_variables, unused_mut)]";
+        let tokens = Lexer::new(contents).tokenize();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(DELIMITER(Delimiter::HASH), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::LBRACKET), None, 0, 0),
+                Token::new(IDENTIFIER_FUNC, Some("allow".to_string()), 0, 0),
+                Token::new(DELIMITER(Delimiter::LPAREN), None, 0, 0),
+                Token::new(IDENTIFIER, Some("unused_variables".to_string()), 0, 0),
+                Token::new(DELIMITER(Delimiter::COMMA), None, 0, 0),
+                Token::new(IDENTIFIER, Some("unused_mut".to_string()), 0, 0),
+                Token::new(DELIMITER(Delimiter::RPAREN), None, 0, 0),
+                Token::new(DELIMITER(Delimiter::RBRACKET), None, 0, 0),
+                Token::new(EOF, None, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_lexer_compound_assign() {
+        let contents = "a += b -= c *= d /= e &= f |= g ^= h <<= i >>= j + k";
+        let tokens = Lexer::new(contents).tokenize();
+
+        let operators = tokens
+            .into_iter()
+            .filter_map(|token| match token.r#type {
+                OPERATOR(op) => Some(op),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operators,
+            vec![
+                Operator::PLUS_ASSIGN,
+                Operator::MINUS_ASSIGN,
+                Operator::MULTIPLY_ASSIGN,
+                Operator::DIVIDE_ASSIGN,
+                Operator::BITWISE_AND_ASSIGN,
+                Operator::BITWISE_OR_ASSIGN,
+                Operator::BITWISE_XOR_ASSIGN,
+                Operator::LSHIFT_ASSIGN,
+                Operator::RSHIFT_ASSIGN,
+                Operator::PLUS,
+            ]
+        );
+    }
 }
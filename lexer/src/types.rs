@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[derive(Debug, PartialEq, Clone)]
 #[allow(non_camel_case_types)]
 pub enum Types {
@@ -38,6 +40,35 @@ pub enum Operator {
     BITWISE_XOR,
     LSHIFT,
     RSHIFT,
+    TRY, // ?
+    // Compound assignments, like `+=`
+    PLUS_ASSIGN,
+    MINUS_ASSIGN,
+    MULTIPLY_ASSIGN,
+    DIVIDE_ASSIGN,
+    BITWISE_AND_ASSIGN,
+    BITWISE_OR_ASSIGN,
+    BITWISE_XOR_ASSIGN,
+    LSHIFT_ASSIGN,
+    RSHIFT_ASSIGN,
+}
+
+impl Operator {
+    /// The operator a compound assignment applies, `+` for `+=`
+    pub fn compound_operator(&self) -> Option<Operator> {
+        match self {
+            Operator::PLUS_ASSIGN => Some(Operator::PLUS),
+            Operator::MINUS_ASSIGN => Some(Operator::MINUS),
+            Operator::MULTIPLY_ASSIGN => Some(Operator::MULTIPLY),
+            Operator::DIVIDE_ASSIGN => Some(Operator::DIVIDE),
+            Operator::BITWISE_AND_ASSIGN => Some(Operator::BITWISE_AND),
+            Operator::BITWISE_OR_ASSIGN => Some(Operator::BITWISE_OR),
+            Operator::BITWISE_XOR_ASSIGN => Some(Operator::BITWISE_XOR),
+            Operator::LSHIFT_ASSIGN => Some(Operator::LSHIFT),
+            Operator::RSHIFT_ASSIGN => Some(Operator::RSHIFT),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -50,6 +81,7 @@ pub enum Delimiter {
     RBRACE,
     LBRACKET,
     RBRACKET,
+    HASH, // # starts an attribute
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -84,6 +116,81 @@ pub enum Datatype {
     CSTRING(usize),
     NARRAY(Box<Datatype>, usize),
     SIMD(Box<Datatype>, usize),
+    /// `T[..]`, elements whose number is only known at runtime
+    SLICE(Box<Datatype>),
+    RESULT(Box<Datatype>, Box<Datatype>),
     CUSTOM(String),
     NONE,
 }
+
+impl Datatype {
+    /// Whether values of this type, or of its elements, are signed
+    pub fn is_signed(&self) -> bool {
+        match self {
+            Datatype::I8
+            | Datatype::I16
+            | Datatype::I32
+            | Datatype::I64
+            | Datatype::F32
+            | Datatype::F64 => true,
+            Datatype::NARRAY(dt, _) | Datatype::SIMD(dt, _) | Datatype::SLICE(dt) => dt.is_signed(),
+            _ => false,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Datatype::U8
+                | Datatype::U16
+                | Datatype::U32
+                | Datatype::U64
+                | Datatype::I8
+                | Datatype::I16
+                | Datatype::I32
+                | Datatype::I64
+        )
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Datatype::F32 | Datatype::F64)
+    }
+
+    /// The size in bits of integers and floats
+    pub fn bit_width(&self) -> Option<u32> {
+        match self {
+            Datatype::U8 | Datatype::I8 => Some(8),
+            Datatype::U16 | Datatype::I16 => Some(16),
+            Datatype::U32 | Datatype::I32 | Datatype::F32 => Some(32),
+            Datatype::U64 | Datatype::I64 | Datatype::F64 => Some(64),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Datatype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Datatype::U8 => write!(f, "u8"),
+            Datatype::U16 => write!(f, "u16"),
+            Datatype::U32 => write!(f, "u32"),
+            Datatype::U64 => write!(f, "u64"),
+            Datatype::I8 => write!(f, "i8"),
+            Datatype::I16 => write!(f, "i16"),
+            Datatype::I32 => write!(f, "i32"),
+            Datatype::I64 => write!(f, "i64"),
+            Datatype::F32 => write!(f, "f32"),
+            Datatype::F64 => write!(f, "f64"),
+            Datatype::BOOL => write!(f, "bool"),
+            Datatype::STRING(_) => write!(f, "string"),
+            Datatype::CSTRING(_) => write!(f, "cstring"),
+            Datatype::NARRAY(dt, 0) => write!(f, "{}[]", dt),
+            Datatype::NARRAY(dt, size) => write!(f, "{}[{}]", dt, size),
+            Datatype::SIMD(dt, size) => write!(f, "simd<{}, {}>", dt, size),
+            Datatype::SLICE(dt) => write!(f, "{}[..]", dt),
+            Datatype::RESULT(ok, err) => write!(f, "Result<{}, {}>", ok, err),
+            Datatype::CUSTOM(name) => write!(f, "{}", name),
+            Datatype::NONE => write!(f, "none"),
+        }
+    }
+}
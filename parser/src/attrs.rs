@@ -0,0 +1,105 @@
+use lexer::types::{Delimiter, Types};
+
+use crate::{Parser, Result, nodes::Attribute};
+
+impl Parser {
+    /// Parses `[name(arg, ...)]` after the `#` of an attribute
+    pub(crate) fn parse_attribute(&mut self) -> Result<Attribute> {
+        self.next_with_type(Types::DELIMITER(Delimiter::LBRACKET))?;
+        let name = self.next_with_type(Types::IDENTIFIER_FUNC)?;
+        self.next_with_type(Types::DELIMITER(Delimiter::LPAREN))?;
+
+        let mut args = vec![];
+        while self
+            .next_if_type(Types::DELIMITER(Delimiter::RPAREN))
+            .is_none()
+        {
+            if !args.is_empty() {
+                self.next_with_type(Types::DELIMITER(Delimiter::COMMA))?;
+            }
+            args.push(self.next_with_type(Types::IDENTIFIER)?.value.unwrap());
+        }
+        self.next_with_type(Types::DELIMITER(Delimiter::RBRACKET))?;
+
+        Ok(Attribute {
+            line: name.line,
+            column: name.column,
+            name: name.value.unwrap(),
+            args,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diagnostics::ErrorCode;
+    use lexer::lexer::Lexer;
+
+    use crate::{
+        Parser,
+        nodes::{ASTNodes, Attribute},
+    };
+
+    fn attrs(code: &str) -> Vec<Vec<Attribute>> {
+        let mut lexer = Lexer::new(code);
+        Parser::new(lexer.tokenize())
+            .parse()
+            .unwrap()
+            .into_iter()
+            .filter_map(|node| match node {
+                ASTNodes::Function(func) => Some(func.attrs),
+                ASTNodes::StructDef(def) => Some(def.attrs),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let code = "
+#[allow(unused_variables, unused_mut)]
+#[deny(unreachable_code)]
+func main() {
+}
+
+#[warn(unused_fields)]
+struct Point { x u32 }
+
+func other() {
+}";
+        assert_eq!(
+            attrs(code),
+            vec![
+                vec![
+                    Attribute {
+                        name: "allow".to_string(),
+                        args: vec!["unused_variables".to_string(), "unused_mut".to_string()],
+                        line: 1,
+                        column: 3,
+                    },
+                    Attribute {
+                        name: "deny".to_string(),
+                        args: vec!["unreachable_code".to_string()],
+                        line: 2,
+                        column: 3,
+                    },
+                ],
+                vec![Attribute {
+                    name: "warn".to_string(),
+                    args: vec!["unused_fields".to_string()],
+                    line: 6,
+                    column: 3,
+                }],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_misplaced_attribute() {
+        let code = "#[allow(unused_imports)]\nimport std::io";
+        let mut lexer = Lexer::new(code);
+        let err = Parser::new(lexer.tokenize()).parse().unwrap_err();
+        assert_eq!(err.code(), ErrorCode::MisplacedAttribute);
+    }
+}
@@ -1,6 +1,6 @@
 use lexer::types::{Datatype, Delimiter, Operator, Types};
 
-use crate::nodes::{ASTNodes, ArrayIndex, Attr, Method, Variable};
+use crate::nodes::{ASTNodes, ArrayIndex, Attr, Method, Try, Variable};
 
 use super::{Parser, ParserError, Result};
 
@@ -20,23 +20,46 @@ impl Parser {
             self.next_with_type(Types::OPERATOR(Operator::LESSER))?;
             let dt = self.parse_datatype()?;
             self.next_with_type(Types::DELIMITER(Delimiter::COMMA))?;
-            let size = self
-                .next()
-                .unwrap()
+            let size = self.next_with_type(Types::NUMBER)?;
+            let size = size
                 .value
-                .unwrap()
-                .parse::<usize>()
-                .unwrap();
+                .as_ref()
+                .and_then(|value| value.parse::<usize>().ok())
+                .ok_or(ParserError::unexpected_token_err(size))?;
             self.next_with_type(Types::OPERATOR(Operator::GREATER))?;
             return Ok(Datatype::SIMD(Box::new(dt), size));
         }
 
+        if let Datatype::RESULT(_, _) = dt {
+            self.next_with_type(Types::OPERATOR(Operator::LESSER))?;
+            let ok = self.parse_datatype()?;
+            self.next_with_type(Types::DELIMITER(Delimiter::COMMA))?;
+            let err = self.parse_datatype()?;
+            self.next_with_type(Types::OPERATOR(Operator::GREATER))?;
+            dt = Datatype::RESULT(Box::new(ok), Box::new(err));
+        }
+
+        // Arrays without a size take the size of the literal they are initialized with
         while self
             .next_if_type(Types::DELIMITER(Delimiter::LBRACKET))
             .is_some()
         {
+            if self.next_if_type(Types::OPERATOR(Operator::DOT)).is_some() {
+                self.next_with_type(Types::OPERATOR(Operator::DOT))?;
+                self.next_with_type(Types::DELIMITER(Delimiter::RBRACKET))?;
+                dt = Datatype::SLICE(Box::new(dt));
+                continue;
+            }
+            let size = match self.next_if_type(Types::NUMBER) {
+                Some(size) => size
+                    .value
+                    .as_ref()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .ok_or(ParserError::unexpected_token_err(size))?,
+                None => 0,
+            };
             self.next_with_type(Types::DELIMITER(Delimiter::RBRACKET))?;
-            dt = Datatype::NARRAY(Box::new(dt), 0);
+            dt = Datatype::NARRAY(Box::new(dt), size);
         }
         Ok(dt)
     }
@@ -44,7 +67,7 @@ impl Parser {
     pub(crate) fn parse_variable(&mut self) -> Result<Variable> {
         let ident = self.next_with_type(Types::IDENTIFIER)?;
         let name = ident.value.unwrap();
-        Ok(Variable { name })
+        Ok(Variable { name, id: None })
     }
 
     /// Returns a variable, attribute or method call
@@ -54,6 +77,7 @@ impl Parser {
         } else {
             ASTNodes::Variable(Variable {
                 name: self.current_with_type(Types::IDENTIFIER)?.value.unwrap(),
+                id: None,
             })
         };
 
@@ -63,33 +87,46 @@ impl Parser {
                 .map(|call| ASTNodes::ImportCall(call));
         }
 
-        while self.next_if_type(Types::OPERATOR(Operator::DOT)).is_some() {
-            parent = if self.next_if_type(Types::IDENTIFIER_FUNC).is_some() {
-                let method = self.parse_function_call()?;
-                ASTNodes::Method(Method {
-                    func: method,
-                    parent: Box::new(parent),
-                })
+        // Fields, methods and indexes can follow each other in any order, as in
+        // `grid[i].cells[j].value`
+        loop {
+            if self.next_if_type(Types::OPERATOR(Operator::DOT)).is_some() {
+                parent = if self.next_if_type(Types::IDENTIFIER_FUNC).is_some() {
+                    let method = self.parse_function_call()?;
+                    ASTNodes::Method(Method {
+                        func: method,
+                        parent: Box::new(parent),
+                    })
+                } else {
+                    ASTNodes::Attr(Attr {
+                        name: self.parse_variable()?,
+                        parent: Box::new(parent),
+                    })
+                };
+            } else if let Some(bracket) = self.next_if_type(Types::DELIMITER(Delimiter::LBRACKET)) {
+                parent = ASTNodes::ArrayIndex(ArrayIndex {
+                    array_var: Box::new(parent),
+                    index: self.parse_expression(vec![Types::DELIMITER(Delimiter::RBRACKET)])?,
+                    line: bracket.line,
+                    column: bracket.column,
+                });
+                self.next_with_type(Types::DELIMITER(Delimiter::RBRACKET))?;
             } else {
-                ASTNodes::Attr(Attr {
-                    name: self.parse_variable()?,
-                    parent: Box::new(parent),
-                })
-            };
+                break;
+            }
         }
 
-        while self
-            .next_if_type(Types::DELIMITER(Delimiter::LBRACKET))
-            .is_some()
-        {
-            parent = ASTNodes::ArrayIndex(ArrayIndex {
-                array_var: Box::new(parent),
-                index: self.parse_expression(vec![Types::DELIMITER(Delimiter::RBRACKET)])?,
+        Ok(parent)
+    }
+
+    /// Wraps the node in a `Try` if it is followed by a `?`
+    pub(crate) fn parse_try(&mut self, node: ASTNodes) -> ASTNodes {
+        if self.next_if_type(Types::OPERATOR(Operator::TRY)).is_some() {
+            return ASTNodes::Try(Try {
+                value: Box::new(node),
             });
-            self.next_with_type(Types::DELIMITER(Delimiter::RBRACKET))?;
         }
-
-        Ok(parent)
+        node
     }
 }
 
@@ -115,6 +152,40 @@ mod tests {
         assert_eq!(ast, Datatype::NARRAY(Box::new(Datatype::U32), 0));
     }
 
+    #[test]
+    fn test_parse_sized_array_datatype() {
+        let mut lexer = Lexer::new("u32[3][2] ");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse_datatype().unwrap();
+        assert_eq!(
+            ast,
+            Datatype::NARRAY(Box::new(Datatype::NARRAY(Box::new(Datatype::U32), 3)), 2)
+        );
+    }
+
+    #[test]
+    fn test_parse_slice_datatype() {
+        let mut lexer = Lexer::new("string[..] ");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse_datatype().unwrap();
+        assert_eq!(ast, Datatype::SLICE(Box::new(Datatype::STRING(0))));
+        assert_eq!(ast.to_string(), "string[..]");
+    }
+
+    #[test]
+    fn test_parse_result_datatype() {
+        let mut lexer = Lexer::new("Result<u32, i32[]> ");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse_datatype().unwrap();
+        assert_eq!(
+            ast,
+            Datatype::RESULT(
+                Box::new(Datatype::U32),
+                Box::new(Datatype::NARRAY(Box::new(Datatype::I32), 0))
+            )
+        );
+    }
+
     #[test]
     fn test_parse_custom_datatype() {
         let mut lexer = Lexer::new("Test ");
@@ -134,10 +205,13 @@ mod tests {
             ASTNodes::Method(Method {
                 func: FunctionCall {
                     name: "test".to_string(),
-                    args: vec![]
+                    args: vec![],
+                    line: 0,
+                    column: 6,
                 },
                 parent: Box::new(ASTNodes::Variable(Variable {
-                    name: "Test".to_string()
+                    name: "Test".to_string(),
+                    id: None,
                 }))
             })
         );
@@ -153,10 +227,12 @@ mod tests {
             ast,
             ASTNodes::Attr(Attr {
                 name: Variable {
-                    name: "test".to_string()
+                    name: "test".to_string(),
+                    id: None,
                 },
                 parent: Box::new(ASTNodes::Variable(Variable {
-                    name: "Test".to_string()
+                    name: "Test".to_string(),
+                    id: None,
                 }))
             })
         );
@@ -173,11 +249,15 @@ mod tests {
             ASTNodes::Method(Method {
                 func: FunctionCall {
                     name: "test2".to_string(),
-                    args: vec![]
+                    args: vec![],
+                    line: 0,
+                    column: 8,
                 },
                 parent: Box::new(ASTNodes::FunctionCall(FunctionCall {
                     name: "test".to_string(),
-                    args: vec![]
+                    args: vec![],
+                    line: 0,
+                    column: 1,
                 }))
             })
         );
@@ -201,15 +281,19 @@ mod tests {
                         })),
                         right: None,
                         operator: None
-                    }]
+                    }],
+                    line: 0,
+                    column: 37,
                 },
                 parent: Box::new(ASTNodes::Attr(Attr {
                     name: Variable {
-                        name: "test4".to_string()
+                        name: "test4".to_string(),
+                        id: None,
                     },
                     parent: Box::new(ASTNodes::Attr(Attr {
                         name: Variable {
-                            name: "test3".to_string()
+                            name: "test3".to_string(),
+                            id: None,
                         },
                         parent: Box::new(ASTNodes::Method(Method {
                             func: FunctionCall {
@@ -231,15 +315,20 @@ mod tests {
                                         right: None,
                                         operator: None
                                     }
-                                ]
+                                ],
+                                line: 0,
+                                column: 13,
                             },
                             parent: Box::new(ASTNodes::Method(Method {
                                 func: FunctionCall {
                                     name: "test".to_string(),
-                                    args: vec![]
+                                    args: vec![],
+                                    line: 0,
+                                    column: 6,
                                 },
                                 parent: Box::new(ASTNodes::Variable(Variable {
-                                    name: "Test".to_string()
+                                    name: "Test".to_string(),
+                                    id: None,
                                 })),
                             })),
                         })),
@@ -267,15 +356,19 @@ mod tests {
                         })),
                         right: None,
                         operator: None
-                    }]
+                    }],
+                    line: 0,
+                    column: 32,
                 },
                 parent: Box::new(ASTNodes::Attr(Attr {
                     name: Variable {
-                        name: "test4".to_string()
+                        name: "test4".to_string(),
+                        id: None,
                     },
                     parent: Box::new(ASTNodes::Attr(Attr {
                         name: Variable {
-                            name: "test3".to_string()
+                            name: "test3".to_string(),
+                            id: None,
                         },
                         parent: Box::new(ASTNodes::Method(Method {
                             func: FunctionCall {
@@ -297,11 +390,15 @@ mod tests {
                                         right: None,
                                         operator: None
                                     }
-                                ]
+                                ],
+                                line: 0,
+                                column: 8,
                             },
                             parent: Box::new(ASTNodes::FunctionCall(FunctionCall {
                                 name: "test".to_string(),
-                                args: vec![]
+                                args: vec![],
+                                line: 0,
+                                column: 1,
                             },)),
                         })),
                     })),
@@ -320,7 +417,8 @@ mod tests {
             ast,
             ASTNodes::ArrayIndex(ArrayIndex {
                 array_var: Box::new(ASTNodes::Variable(Variable {
-                    name: "test".to_string()
+                    name: "test".to_string(),
+                    id: None,
                 })),
                 index: Expression::Simple {
                     left: Box::new(ASTNodes::Literal(Literal {
@@ -329,7 +427,9 @@ mod tests {
                     })),
                     right: None,
                     operator: None
-                }
+                },
+                line: 0,
+                column: 5,
             })
         );
     }
@@ -346,6 +446,7 @@ mod tests {
                 array_var: Box::new(ASTNodes::ArrayIndex(ArrayIndex {
                     array_var: Box::new(ASTNodes::Variable(Variable {
                         name: "test".to_string(),
+                        id: None,
                     })),
                     index: Expression::Simple {
                         left: Box::new(ASTNodes::Literal(Literal {
@@ -355,6 +456,8 @@ mod tests {
                         right: None,
                         operator: None
                     },
+                    line: 0,
+                    column: 5,
                 })),
                 index: Expression::Simple {
                     left: Box::new(ASTNodes::Literal(Literal {
@@ -363,7 +466,42 @@ mod tests {
                     })),
                     right: None,
                     operator: None
-                }
+                },
+                line: 0,
+                column: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_index_then_attr() {
+        let mut lexer = Lexer::new("grid[0].cells ");
+        let mut parser = Parser::new(lexer.tokenize());
+        parser.next();
+        let ast = parser.parse_complex_variable().unwrap();
+        assert_eq!(
+            ast,
+            ASTNodes::Attr(Attr {
+                name: Variable {
+                    name: "cells".to_string(),
+                    id: None,
+                },
+                parent: Box::new(ASTNodes::ArrayIndex(ArrayIndex {
+                    array_var: Box::new(ASTNodes::Variable(Variable {
+                        name: "grid".to_string(),
+                        id: None,
+                    })),
+                    index: Expression::Simple {
+                        left: Box::new(ASTNodes::Literal(Literal {
+                            value: "0".to_string(),
+                            r#type: Types::NUMBER
+                        })),
+                        right: None,
+                        operator: None
+                    },
+                    line: 0,
+                    column: 5,
+                })),
             })
         );
     }
@@ -1,3 +1,4 @@
+use diagnostics::ErrorCode;
 use lexer::types::{Delimiter, Keyword, Operator, Types};
 
 use super::{
@@ -8,14 +9,43 @@ use super::{
 impl Parser {
     pub(crate) fn parse_source(&mut self) -> Result<Vec<ASTNodes>> {
         let mut ast = Vec::new();
+        // Attributes apply to the next function or struct
+        let mut attrs = vec![];
 
         while let Some(token) = self.next() {
+            if !attrs.is_empty()
+                && !matches!(
+                    token.r#type,
+                    Types::NL
+                        | Types::DELIMITER(Delimiter::HASH)
+                        | Types::KEYWORD(Keyword::FUNCTION)
+                        | Types::KEYWORD(Keyword::STRUCT)
+                )
+            {
+                return Err(ParserError::new(
+                    ErrorCode::MisplacedAttribute,
+                    "Attributes can only be applied to functions and structs",
+                    token,
+                ));
+            }
             let object = match token.r#type {
                 Types::NL => continue,
+                Types::DELIMITER(Delimiter::HASH) => {
+                    attrs.push(self.parse_attribute()?);
+                    continue;
+                }
                 Types::KEYWORD(Keyword::EXTERN) => ASTNodes::Extern(self.parse_extern()?),
                 Types::KEYWORD(Keyword::IMPORT) => ASTNodes::ImportDef(self.parse_import_def()?),
-                Types::KEYWORD(Keyword::FUNCTION) => ASTNodes::Function(self.parse_function_def()?),
-                Types::KEYWORD(Keyword::STRUCT) => ASTNodes::StructDef(self.parse_struct_def()?),
+                Types::KEYWORD(Keyword::FUNCTION) => {
+                    let mut func = self.parse_function_def()?;
+                    func.attrs = std::mem::take(&mut attrs);
+                    ASTNodes::Function(func)
+                }
+                Types::KEYWORD(Keyword::STRUCT) => {
+                    let mut def = self.parse_struct_def()?;
+                    def.attrs = std::mem::take(&mut attrs);
+                    ASTNodes::StructDef(def)
+                }
                 Types::EOF => break,
                 _ => return Err(ParserError::unimplemented(token)),
             };
@@ -36,12 +66,23 @@ impl Parser {
                 Types::KEYWORD(Keyword::LET) => ASTNodes::LetStmt(self.parse_statement()?),
                 Types::KEYWORD(Keyword::IF) => ASTNodes::Conditional(self.parse_if()?),
                 Types::KEYWORD(Keyword::LOOP) => self.parse_loop()?,
+                Types::KEYWORD(Keyword::BREAK) if self.loops == 0 => {
+                    return Err(ParserError::new(
+                        ErrorCode::BreakOutsideLoop,
+                        "`break` can only be used inside a loop",
+                        token,
+                    ));
+                }
                 Types::KEYWORD(Keyword::BREAK) => ASTNodes::Break,
-                Types::IDENTIFIER_FUNC => ASTNodes::FunctionCall(self.parse_function_call()?),
+                Types::IDENTIFIER_FUNC => {
+                    let call = ASTNodes::FunctionCall(self.parse_function_call()?);
+                    self.parse_try(call)
+                }
                 Types::IDENTIFIER
                     if self.peek_if_type(Types::OPERATOR(Operator::PATH)).is_some() =>
                 {
-                    ASTNodes::ImportCall(self.parse_import_call()?)
+                    let call = ASTNodes::ImportCall(self.parse_import_call()?);
+                    self.parse_try(call)
                 }
                 Types::IDENTIFIER => ASTNodes::AssignStmt(self.parse_assign_stmt()?),
                 Types::DELIMITER(Delimiter::RBRACE) => break,
@@ -4,6 +4,8 @@ use crate::{Parser, Result, nodes::Conditional};
 
 impl Parser {
     pub(crate) fn parse_if(&mut self) -> Result<Conditional> {
+        let line = self.current_line();
+        let column = self.current_column();
         let condition = self.parse_expression(vec![Types::DELIMITER(Delimiter::LBRACE)])?;
         let block = self.parse_scoped_block()?;
 
@@ -11,6 +13,8 @@ impl Parser {
             condition,
             body: block,
             else_body: self.parse_else()?.map(|b| Box::new(b)),
+            line,
+            column,
         })
     }
 
@@ -66,12 +70,19 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 0,
+                                column: 29,
                             })]
                         },
                         else_body: None,
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -106,7 +117,9 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 0,
+                                column: 29,
                             })]
                         },
                         else_body: Some(Box::new(Conditional::Else {
@@ -119,12 +132,19 @@ mod tests {
                                         })),
                                         right: None,
                                         operator: None
-                                    })
+                                    }),
+                                    line: 0,
+                                    column: 47,
                                 })]
                             }
                         })),
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -161,7 +181,9 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 0,
+                                column: 29,
                             })]
                         },
                         else_body: Some(Box::new(Conditional::If {
@@ -182,7 +204,9 @@ mod tests {
                                         })),
                                         right: None,
                                         operator: None
-                                    })
+                                    }),
+                                    line: 0,
+                                    column: 56,
                                 })]
                             },
                             else_body: Some(Box::new(Conditional::If {
@@ -203,7 +227,9 @@ mod tests {
                                             })),
                                             right: None,
                                             operator: None
-                                        })
+                                        }),
+                                        line: 0,
+                                        column: 82,
                                     })]
                                 },
                                 else_body: Some(Box::new(Conditional::Else {
@@ -216,14 +242,25 @@ mod tests {
                                                 })),
                                                 right: None,
                                                 operator: None
-                                            })
+                                            }),
+                                            line: 0,
+                                            column: 100,
                                         })]
                                     }
-                                }))
-                            }))
-                        }))
+                                })),
+                                line: 0,
+                                column: 72,
+                            })),
+                            line: 0,
+                            column: 45,
+                        })),
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
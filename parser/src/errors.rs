@@ -1,12 +1,16 @@
 use std::{error::Error, fmt::Display};
 
+use diagnostics::{Diagnostic, ErrorCode, Span};
 use lexer::{lexer::Token, types::Types};
 
 #[derive(Debug)]
 pub struct ParserError {
+    pub(crate) code: ErrorCode,
     pub(crate) msg: String,
     pub(crate) line: usize,
     pub(crate) column: usize,
+    /// The length of the token, when it is known
+    pub(crate) len: usize,
 }
 
 impl Error for ParserError {}
@@ -14,46 +18,77 @@ impl Error for ParserError {}
 impl Default for ParserError {
     fn default() -> Self {
         Self {
+            code: ErrorCode::UnexpectedToken,
             msg: "Unknown error while parsing".to_string(),
             line: 0,
             column: 0,
+            len: 1,
         }
     }
 }
 
 impl ParserError {
-    pub(crate) fn new(msg: &str, token: Token) -> Self {
+    pub(crate) fn new(code: ErrorCode, msg: &str, token: Token) -> Self {
         Self {
+            code,
             msg: msg.to_string(),
             line: token.line,
             column: token.column,
+            len: token.value.map_or(1, |value| value.len()),
         }
     }
 
     pub(crate) fn expected_token_err(token: Token, expected: Types) -> Self {
         Self::new(
+            Self::unexpected(&token),
             &format!("Expected token {:?}, got {:?}", expected, token.r#type),
             token,
         )
     }
 
     pub(crate) fn unexpected_token_err(token: Token) -> Self {
-        Self::new(&format!("Unexpected token {:?}", token.r#type), token)
+        Self::new(
+            Self::unexpected(&token),
+            &format!("Unexpected token {:?}", token.r#type),
+            token,
+        )
+    }
+
+    fn unexpected(token: &Token) -> ErrorCode {
+        match token.r#type {
+            Types::EOF => ErrorCode::UnexpectedEof,
+            _ => ErrorCode::UnexpectedToken,
+        }
     }
 
     pub(crate) fn unexpected_eof(token: Option<Token>) -> Self {
         if let Some(token) = token {
             Self::new(
+                ErrorCode::UnexpectedEof,
                 &format!("Expected token {:?}, got eof", token.r#type),
                 token,
             )
         } else {
-            Self::new("Expected token, got eof", Token::default())
+            Self::new(
+                ErrorCode::UnexpectedEof,
+                "Expected token, got eof",
+                Token::default(),
+            )
         }
     }
 
     pub(crate) fn unimplemented(token: Token) -> Self {
-        Self::new(&format!("Unimplemented token {:?}", token.r#type), token)
+        Self::new(
+            ErrorCode::InvalidStatement,
+            &format!("Unimplemented token {:?}", token.r#type),
+            token,
+        )
+    }
+}
+
+impl ParserError {
+    pub fn code(&self) -> ErrorCode {
+        self.code
     }
 }
 
@@ -63,4 +98,14 @@ impl Display for ParserError {
     }
 }
 
+// Columns are counted from one by the lexer
+impl From<&ParserError> for Diagnostic {
+    fn from(err: &ParserError) -> Self {
+        let span = Span::new(err.line, err.column.saturating_sub(1), err.len);
+        Diagnostic::error(&err.msg)
+            .with_code(err.code)
+            .with_primary(span, "")
+    }
+}
+
 pub(crate) type Result<T> = std::result::Result<T, ParserError>;
@@ -1,3 +1,4 @@
+use diagnostics::ErrorCode;
 use lexer::types::{Datatype, Delimiter, Operator, Types};
 
 use super::{
@@ -14,7 +15,15 @@ impl Parser {
         {
             return self.parse_array();
         } else if let Types::DATATYPE(Datatype::STRING(_)) = self.peek().unwrap().r#type {
-            return Ok(Expression::String(self.next().unwrap().value.unwrap()));
+            // A lone string literal; otherwise it is an operand of a larger expression
+            // The `string` type has no value
+            let string = self.next().unwrap();
+            if let Some(value) = &string.value
+                && delim.contains(&self.peek().unwrap().r#type)
+            {
+                return Ok(Expression::String(value.clone()));
+            }
+            self.prev();
         } else if self
             .next_if_type(Types::DELIMITER(Delimiter::LBRACE))
             .is_some()
@@ -28,21 +37,38 @@ impl Parser {
         'outer: loop {
             let token = self.next().ok_or(ParserError::unexpected_eof(None))?;
             match token.r#type {
-                Types::NUMBER | Types::BOOL => operands.push(ASTNodes::Literal(Literal {
-                    value: token.value.unwrap(),
-                    r#type: token.r#type,
-                })),
+                Types::NUMBER | Types::BOOL | Types::DATATYPE(Datatype::STRING(_)) => {
+                    let Some(value) = token.value.clone() else {
+                        return Err(ParserError::unexpected_token_err(token));
+                    };
+                    operands.push(ASTNodes::Literal(Literal {
+                        value,
+                        r#type: token.r#type,
+                    }))
+                }
                 Types::IDENTIFIER => {
-                    operands.push(self.parse_complex_variable()?);
+                    let node = self.parse_complex_variable()?;
+                    operands.push(self.parse_try(node));
                 }
                 Types::OPERATOR(Operator::CAST) => {
                     operands.push(ASTNodes::Token(Types::DATATYPE(self.parse_cast()?)));
                     operators.push(token.r#type);
                 }
+                Types::OPERATOR(Operator::TRY) => {
+                    return Err(ParserError::new(
+                        ErrorCode::UnexpectedToken,
+                        "Unexpected '?'",
+                        token,
+                    ));
+                }
                 Types::OPERATOR(ref op) => {
+                    // Only binary operators can appear here
+                    let Some(precedence) = self.get_precedence(&token.r#type) else {
+                        return Err(ParserError::unexpected_token_err(token));
+                    };
                     while !operators.is_empty() {
                         let pop_op = operators.last().unwrap();
-                        if self.get_precedence(&token.r#type) > self.get_precedence(pop_op) {
+                        if Some(precedence) > self.get_precedence(pop_op) {
                             break;
                         }
                         let pop = operators.pop().unwrap();
@@ -65,12 +91,17 @@ impl Parser {
                             self.prev();
                             break 'outer;
                         } else {
-                            return Err(ParserError::new("Unexpected LPAREN", token));
+                            return Err(ParserError::new(
+                                ErrorCode::UnexpectedToken,
+                                "Unexpected LPAREN",
+                                token,
+                            ));
                         }
                     }
                 },
                 Types::IDENTIFIER_FUNC => {
-                    operands.push(ASTNodes::FunctionCall(self.parse_function_call()?));
+                    let node = ASTNodes::FunctionCall(self.parse_function_call()?);
+                    operands.push(self.parse_try(node));
                 }
                 ty if delim.contains(&ty) => {
                     self.prev();
@@ -95,7 +126,8 @@ impl Parser {
     fn postfix_to_tree(&self, operands: &mut Vec<ASTNodes>) -> Result<Expression> {
         let op = if operands.len() > 1 {
             let value = operands.pop().unwrap();
-            self.value_to_operator(value).unwrap()
+            self.value_to_operator(value)
+                .ok_or_else(|| self.invalid_expression("Expected an operator"))?
         } else if operands.len() == 0 {
             todo!()
             // errors::parser_error(self, "Invalid postfix expression");
@@ -109,7 +141,9 @@ impl Parser {
         };
 
         let right = {
-            let last_op = operands.last().unwrap();
+            let last_op = operands
+                .last()
+                .ok_or_else(|| self.invalid_expression("Expected an operand"))?;
             if let ASTNodes::Token(Types::OPERATOR(_)) = last_op {
                 ASTNodes::Expression(self.postfix_to_tree(operands)?)
             } else {
@@ -118,7 +152,9 @@ impl Parser {
         };
 
         let left = {
-            let last_op = operands.last().unwrap();
+            let last_op = operands
+                .last()
+                .ok_or_else(|| self.invalid_expression("Expected an operand"))?;
             if let ASTNodes::Token(Types::OPERATOR(_)) = last_op {
                 ASTNodes::Expression(self.postfix_to_tree(operands)?)
             } else {
@@ -133,9 +169,19 @@ impl Parser {
         })
     }
 
-    fn get_precedence(&self, operator: &Types) -> usize {
+    /// For expressions like `if > 2` or `if 5 2`, which only turn out to be
+    /// invalid once all of their tokens have been read
+    fn invalid_expression(&self, msg: &str) -> ParserError {
+        ParserError::new(
+            ErrorCode::UnexpectedToken,
+            msg,
+            self.current().unwrap_or_default(),
+        )
+    }
+
+    fn get_precedence(&self, operator: &Types) -> Option<usize> {
         use Operator::*;
-        match operator {
+        let precedence = match operator {
             Types::OPERATOR(CAST) => 10,
             Types::OPERATOR(MULTIPLY | DIVIDE | MODULO) => 8,
             Types::OPERATOR(PLUS | MINUS) => 7,
@@ -146,8 +192,9 @@ impl Parser {
             Types::OPERATOR(BITWISE_XOR) => 2,
             Types::OPERATOR(BITWISE_OR) => 1,
             Types::DELIMITER(Delimiter::LPAREN) => 0,
-            _ => unreachable!(),
-        }
+            _ => return None,
+        };
+        Some(precedence)
     }
 
     fn value_to_operator(&self, value: ASTNodes) -> Option<Operator> {
@@ -208,7 +255,7 @@ impl Parser {
 
 #[cfg(test)]
 mod tests {
-    use crate::nodes::Variable;
+    use crate::nodes::{FunctionCall, Try, Variable};
 
     use super::*;
     use lexer::lexer::Lexer;
@@ -315,6 +362,27 @@ mod tests {
         assert_eq!(ast, Expression::String("Hello World".to_string()));
     }
 
+    #[test]
+    fn test_parse_string_concat() {
+        let mut lexer = Lexer::new("\"ab\" + name ");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse_expression(vec![Types::EOF]).unwrap();
+        assert_eq!(
+            ast,
+            Expression::Simple {
+                left: Box::new(ASTNodes::Literal(Literal {
+                    value: "ab".to_string(),
+                    r#type: Types::DATATYPE(Datatype::STRING(2))
+                })),
+                right: Some(Box::new(ASTNodes::Variable(Variable {
+                    name: "name".to_string(),
+                    id: None,
+                }))),
+                operator: Some(Operator::PLUS)
+            }
+        );
+    }
+
     #[test]
     fn test_parse_struct() {
         let mut lexer = Lexer::new(" { a 4, b 7 }");
@@ -391,7 +459,8 @@ mod tests {
             ast,
             Expression::Simple {
                 left: Box::new(ASTNodes::Variable(Variable {
-                    name: "array_var".to_string()
+                    name: "array_var".to_string(),
+                    id: None,
                 })),
                 right: Some(Box::new(ASTNodes::Token(Types::DATATYPE(Datatype::SIMD(
                     Box::new(Datatype::U32),
@@ -401,4 +470,37 @@ mod tests {
             },
         )
     }
+
+    #[test]
+    fn test_parse_try() {
+        let mut lexer = Lexer::new("read()? + 1 ");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse_expression(vec![Types::EOF]).unwrap();
+
+        assert_eq!(
+            ast,
+            Expression::Simple {
+                left: Box::new(ASTNodes::Try(Try {
+                    value: Box::new(ASTNodes::FunctionCall(FunctionCall {
+                        name: "read".to_string(),
+                        args: vec![],
+                        line: 0,
+                        column: 1,
+                    }))
+                })),
+                right: Some(Box::new(ASTNodes::Literal(Literal {
+                    value: "1".to_string(),
+                    r#type: Types::NUMBER
+                }))),
+                operator: Some(Operator::PLUS)
+            },
+        )
+    }
+
+    #[test]
+    fn test_parse_stray_try() {
+        let mut lexer = Lexer::new("1 + ? ");
+        let mut parser = Parser::new(lexer.tokenize());
+        assert!(parser.parse_expression(vec![Types::EOF]).is_err());
+    }
 }
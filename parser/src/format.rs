@@ -0,0 +1,126 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+pub enum FormatPiece {
+    Str(String),
+    Arg {
+        name: Option<String>,
+        precision: Option<u32>,
+    },
+}
+
+/// Splits a format string into literal pieces and `{}`, `{:.N}`, `{name}` or `{name:.N}` args.
+/// Braces are escaped by doubling them.
+pub fn parse_format_string(fmt: &str) -> Result<Vec<FormatPiece>, String> {
+    let mut pieces = vec![];
+    let mut current = String::new();
+    let mut chars = fmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                current.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                current.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("Unclosed '{' in format string".to_string()),
+                    }
+                }
+                let (name, format) = spec.split_once(':').unwrap_or((&spec, ""));
+                let precision = match format {
+                    "" => None,
+                    _ => Some(
+                        format
+                            .strip_prefix('.')
+                            .and_then(|p| p.parse::<u32>().ok())
+                            .ok_or(format!("Invalid format spec '{}'", format))?,
+                    ),
+                };
+                let name = name.trim();
+                if !name.is_empty() && !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(format!("Invalid argument name '{}' in format string", name));
+                }
+
+                if !current.is_empty() {
+                    pieces.push(FormatPiece::Str(std::mem::take(&mut current)));
+                }
+                pieces.push(FormatPiece::Arg {
+                    name: (!name.is_empty()).then(|| name.to_string()),
+                    precision,
+                });
+            }
+            '}' => return Err("Unmatched '}' in format string".to_string()),
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(FormatPiece::Str(current));
+    }
+    Ok(pieces)
+}
+
+/// Writes the piece back in format string syntax, escaping braces
+impl Display for FormatPiece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatPiece::Str(s) => write!(f, "{}", s.replace('{', "{{").replace('}', "}}")),
+            FormatPiece::Arg { name, precision } => {
+                write!(f, "{{{}", name.as_deref().unwrap_or(""))?;
+                if let Some(precision) = precision {
+                    write!(f, ":.{}", precision)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatPiece, parse_format_string};
+
+    #[test]
+    fn test_parse_format_string() {
+        let pieces = parse_format_string("x = {}, y = {y:.2} {{}}").unwrap();
+        assert_eq!(
+            pieces,
+            vec![
+                FormatPiece::Str("x = ".to_string()),
+                FormatPiece::Arg {
+                    name: None,
+                    precision: None
+                },
+                FormatPiece::Str(", y = ".to_string()),
+                FormatPiece::Arg {
+                    name: Some("y".to_string()),
+                    precision: Some(2)
+                },
+                FormatPiece::Str(" {}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_format_string() {
+        assert!(parse_format_string("x = {").is_err());
+        assert!(parse_format_string("x = }").is_err());
+        assert!(parse_format_string("x = {:x}").is_err());
+    }
+
+    #[test]
+    fn test_display_format_pieces() {
+        let fmt = "x = {}, y = {y:.2} {{}}";
+        let pieces = parse_format_string(fmt).unwrap();
+        let displayed = pieces.iter().map(|p| p.to_string()).collect::<String>();
+        assert_eq!(displayed, fmt);
+    }
+}
@@ -1,17 +1,28 @@
-use lexer::types::{Datatype, Delimiter, Types};
+use lexer::types::{Datatype, Delimiter, Operator, Types};
 
 use crate::nodes::{Expression, FunctionCall};
 
 use super::{
     Parser, Result,
     errors::ParserError,
-    nodes::{Function, Return},
+    nodes::{Function, Param, Return},
 };
 
 impl Parser {
     pub(crate) fn parse_function_def(&mut self) -> Result<Function> {
         let name = self.next_with_type(Types::IDENTIFIER_FUNC)?;
-        let args = self.parse_function_args()?;
+        // An unsized array parameter `T[]` takes a slice, so arrays of any size can be passed
+        let args = self
+            .parse_function_args()?
+            .into_iter()
+            .map(|mut param| {
+                param.datatype = match param.datatype {
+                    Datatype::NARRAY(inner, 0) => Datatype::SLICE(inner),
+                    dt => dt,
+                };
+                param
+            })
+            .collect();
         let return_type = if self
             .peek_if_type(Types::DELIMITER(Delimiter::LBRACE))
             .is_some()
@@ -23,15 +34,18 @@ impl Parser {
         let body = self.parse_scoped_block()?;
 
         Ok(Function {
+            line: name.line,
+            column: name.column,
             name: name.value.unwrap(),
             args,
             return_type,
             body,
+            attrs: vec![],
         })
     }
 
-    pub(crate) fn parse_function_args(&mut self) -> Result<Vec<(String, Datatype)>> {
-        let mut args: Vec<(String, Datatype)> = vec![];
+    pub(crate) fn parse_function_args(&mut self) -> Result<Vec<Param>> {
+        let mut args: Vec<Param> = vec![];
         self.next_with_type(Types::DELIMITER(Delimiter::LPAREN))?;
 
         match self.peek().ok_or(ParserError::default())?.r#type {
@@ -45,8 +59,14 @@ impl Parser {
         loop {
             let var_name = self.next_with_type(Types::IDENTIFIER)?;
             let var_type = self.parse_datatype()?;
+            let mutable = self.next_if_type(Types::OPERATOR(Operator::NOT)).is_some();
 
-            args.push((var_name.value.unwrap(), var_type));
+            args.push(Param {
+                name: var_name.value.unwrap(),
+                datatype: var_type,
+                mutable,
+                id: None,
+            });
 
             match self.next().ok_or(ParserError::default())?.r#type {
                 Types::DELIMITER(Delimiter::RPAREN) => break,
@@ -59,11 +79,21 @@ impl Parser {
     }
 
     pub(crate) fn parse_return(&mut self) -> Result<Return> {
+        let line = self.current_line();
+        let column = self.current_column();
         let val = self.parse_expression(vec![Types::DELIMITER(Delimiter::RBRACE), Types::NL])?;
         if val == Expression::None {
-            return Ok(Return { value: None });
+            return Ok(Return {
+                value: None,
+                line,
+                column,
+            });
         }
-        return Ok(Return { value: Some(val) });
+        return Ok(Return {
+            value: Some(val),
+            line,
+            column,
+        });
     }
 
     pub(crate) fn parse_function_call(&mut self) -> Result<FunctionCall> {
@@ -77,6 +107,8 @@ impl Parser {
             return Ok(FunctionCall {
                 name: name.value.unwrap(),
                 args: vec![],
+                line: name.line,
+                column: name.column,
             });
         }
 
@@ -99,6 +131,8 @@ impl Parser {
         return Ok(FunctionCall {
             name: name.value.unwrap(),
             args,
+            line: name.line,
+            column: name.column,
         });
     }
 }
@@ -127,6 +161,9 @@ mod tests {
                 args: vec![],
                 return_type: Some(Datatype::U32),
                 body: Block { body: vec![] },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -143,6 +180,9 @@ mod tests {
                 args: vec![],
                 return_type: None,
                 body: Block { body: vec![] },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -159,8 +199,15 @@ mod tests {
                 args: vec![],
                 return_type: Some(Datatype::U32),
                 body: Block {
-                    body: vec![ASTNodes::Return(Return { value: None })]
+                    body: vec![ASTNodes::Return(Return {
+                        value: None,
+                        line: 0,
+                        column: 19,
+                    })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             }),]
         );
     }
@@ -185,9 +232,14 @@ mod tests {
                             })),
                             right: None,
                             operator: None
-                        })
+                        }),
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             }),]
         );
     }
@@ -202,11 +254,88 @@ mod tests {
             vec![ASTNodes::Function(Function {
                 name: "main".to_string(),
                 args: vec![
-                    ("a".to_string(), Datatype::U32),
-                    ("b".to_string(), Datatype::U32)
+                    Param {
+                        name: "a".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    },
+                    Param {
+                        name: "b".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    }
                 ],
                 return_type: Some(Datatype::U32),
                 body: Block { body: vec![] },
+                line: 0,
+                column: 6,
+                attrs: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_function_mutable_args() {
+        let mut lexer = Lexer::new("func main(a u32!, b u32) {}");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse().unwrap();
+        assert_eq!(
+            ast,
+            vec![ASTNodes::Function(Function {
+                name: "main".to_string(),
+                args: vec![
+                    Param {
+                        name: "a".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: true,
+                        id: None,
+                    },
+                    Param {
+                        name: "b".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    }
+                ],
+                return_type: None,
+                body: Block { body: vec![] },
+                line: 0,
+                column: 6,
+                attrs: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_function_unsized_array_args() {
+        let mut lexer = Lexer::new("func main(a u32[], b u32[2]) {}");
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse().unwrap();
+        assert_eq!(
+            ast,
+            vec![ASTNodes::Function(Function {
+                name: "main".to_string(),
+                args: vec![
+                    Param {
+                        name: "a".to_string(),
+                        datatype: Datatype::SLICE(Box::new(Datatype::U32)),
+                        mutable: false,
+                        id: None,
+                    },
+                    Param {
+                        name: "b".to_string(),
+                        datatype: Datatype::NARRAY(Box::new(Datatype::U32), 2),
+                        mutable: false,
+                        id: None,
+                    }
+                ],
+                return_type: None,
+                body: Block { body: vec![] },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -225,9 +354,14 @@ mod tests {
                 body: Block {
                     body: vec![ASTNodes::FunctionCall(FunctionCall {
                         name: "call".to_string(),
-                        args: vec![]
+                        args: vec![],
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -256,15 +390,23 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                }]
+                                }],
+                                line: 0,
+                                column: 31,
                             })),
                             right: None,
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 0,
+                        column: 19,
+                        id: None,
                     }),]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -7,6 +7,8 @@ use crate::{
 
 impl Parser {
     pub(crate) fn parse_import_def(&mut self) -> Result<ImportDef> {
+        let line = self.current_line();
+        let column = self.current_column();
         let mut path = Vec::new();
         loop {
             let subpath = self.next_with_type(Types::IDENTIFIER)?;
@@ -17,7 +19,7 @@ impl Parser {
             }
         }
 
-        return Ok(ImportDef { path });
+        return Ok(ImportDef { path, line, column });
     }
 
     pub(crate) fn parse_import_call(&mut self) -> Result<ImportCall> {
@@ -49,6 +51,8 @@ impl Parser {
         let return_type = self.parse_datatype().ok();
 
         Ok(Extern {
+            line: name.line,
+            column: name.column,
             name: name.value.unwrap(),
             args,
             return_type,
@@ -59,7 +63,7 @@ impl Parser {
 #[cfg(test)]
 mod tests {
     use crate::nodes::{
-        ASTNodes, Block, Expression, Extern, Function, FunctionCall, LetStmt, Literal,
+        ASTNodes, Block, Expression, Extern, Function, FunctionCall, LetStmt, Literal, Param,
     };
 
     use super::*;
@@ -73,7 +77,9 @@ mod tests {
         assert_eq!(
             ast,
             vec![ASTNodes::ImportDef(ImportDef {
-                path: vec!["std".to_string(), "io".to_string()]
+                path: vec!["std".to_string(), "io".to_string()],
+                line: 0,
+                column: 1,
             })]
         );
     }
@@ -111,16 +117,24 @@ mod tests {
                                             right: None,
                                             operator: None
                                         }
-                                    ]
+                                    ],
+                                    line: 0,
+                                    column: 40,
                                 }))
                             })),
                             right: None,
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 0,
+                        column: 19,
+                        id: None,
                     })]
-                }
+                },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -135,10 +149,22 @@ mod tests {
             vec![ASTNodes::Extern(Extern {
                 name: "add".to_string(),
                 args: vec![
-                    ("a".to_string(), Datatype::U32),
-                    ("b".to_string(), Datatype::U32)
+                    Param {
+                        name: "a".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    },
+                    Param {
+                        name: "b".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    }
                 ],
                 return_type: Some(Datatype::U32),
+                line: 0,
+                column: 13,
             })]
         );
     }
@@ -8,6 +8,7 @@ use lexer::{
     types::{Delimiter, Types},
 };
 
+mod attrs;
 mod basics;
 mod block;
 mod cond;
@@ -18,6 +19,7 @@ mod loops;
 mod stmt;
 
 mod errors;
+pub mod format;
 pub mod nodes;
 #[cfg(test)]
 mod test;
@@ -25,6 +27,8 @@ mod test;
 pub struct Parser {
     tokens: Vec<Token>,
     index: usize,
+    /// How many loops the statement being parsed is in
+    loops: usize,
 }
 
 const SKIP_NL_FOR: [Types; 3] = [
@@ -54,6 +58,7 @@ impl Parser {
         Self {
             tokens: lexer_tokens,
             index: 0,
+            loops: 0,
         }
     }
 
@@ -69,6 +74,16 @@ impl Parser {
         }
     }
 
+    /// The line of the current token, which starts the node being parsed
+    pub(crate) fn current_line(&self) -> usize {
+        self.current().map_or(0, |token| token.line)
+    }
+
+    /// The column of the current token, counted from one like the lexer does
+    pub(crate) fn current_column(&self) -> usize {
+        self.current().map_or(0, |token| token.column)
+    }
+
     pub(crate) fn prev(&mut self) -> Option<Token> {
         self.index -= 1;
         self.current()
@@ -2,11 +2,13 @@ use lexer::types::{Delimiter, Keyword, Operator, Types};
 
 use crate::{
     Parser, Result,
-    nodes::{ASTNodes, ForLoop, Loop},
+    nodes::{ASTNodes, Block, ForLoop, Loop},
 };
 
 impl Parser {
     pub(crate) fn parse_loop(&mut self) -> Result<ASTNodes> {
+        let line = self.current_line();
+        let column = self.current_column();
         if self.next_if_type(Types::KEYWORD(Keyword::RANGE)).is_some() {
             return self.parse_for_loop().map(|f| ASTNodes::ForLoop(f));
         }
@@ -16,20 +18,34 @@ impl Parser {
         {
             return Ok(ASTNodes::Loop(Loop {
                 condition: None,
-                body: self.parse_scoped_block()?,
+                body: self.parse_loop_body()?,
+                line,
+                column,
             }));
         }
 
         let condition = self.parse_expression(vec![Types::DELIMITER(Delimiter::LBRACE)])?;
-        let body = self.parse_scoped_block()?;
+        let body = self.parse_loop_body()?;
 
         Ok(ASTNodes::Loop(Loop {
             condition: Some(condition),
             body,
+            line,
+            column,
         }))
     }
 
+    /// Parses the body of a loop, in which `break` can be used
+    fn parse_loop_body(&mut self) -> Result<Block> {
+        self.loops += 1;
+        let body = self.parse_scoped_block();
+        self.loops -= 1;
+        body
+    }
+
     pub(crate) fn parse_for_loop(&mut self) -> Result<ForLoop> {
+        let line = self.current_line();
+        let column = self.current_column();
         let step = if self
             .next_if_type(Types::DELIMITER(Delimiter::LBRACKET))
             .is_some()
@@ -42,7 +58,6 @@ impl Parser {
         } else {
             None
         };
-        println!("{:?}", step);
         let value = self.parse_variable()?;
         self.next_with_type(Types::DELIMITER(Delimiter::COMMA))?;
         let increment = self.parse_variable()?;
@@ -52,8 +67,10 @@ impl Parser {
             value,
             increment,
             iterator,
-            body: self.parse_scoped_block()?,
+            body: self.parse_loop_body()?,
             step,
+            line,
+            column,
         })
     }
 }
@@ -94,11 +111,18 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 0,
+                                column: 26,
                             })]
                         },
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -136,11 +160,18 @@ mod tests {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 0,
+                                column: 32,
                             })]
                         },
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -161,13 +192,16 @@ mod tests {
                     body: vec![ASTNodes::ForLoop(ForLoop {
                         value: Variable {
                             name: "val".to_string(),
+                            id: None,
                         },
                         increment: Variable {
                             name: "index".to_string(),
+                            id: None,
                         },
                         iterator: Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "array".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
@@ -177,10 +211,12 @@ mod tests {
                             body: vec![ASTNodes::AssignStmt(AssignStmt {
                                 name: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 value: Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "i".to_string(),
+                                        id: None,
                                     })),
                                     right: Some(Box::new(ASTNodes::Literal(Literal {
                                         value: "2".to_string(),
@@ -188,10 +224,18 @@ mod tests {
                                     }))),
                                     operator: Some(Operator::MULTIPLY)
                                 },
+                                operator: None,
+                                line: 0,
+                                column: 51,
                             })]
-                        }
+                        },
+                        line: 0,
+                        column: 24,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -217,9 +261,14 @@ mod tests {
                         condition: None,
                         body: Block {
                             body: vec![ASTNodes::Break]
-                        }
+                        },
+                        line: 1,
+                        column: 5,
                     })]
-                }
+                },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
@@ -240,13 +289,16 @@ mod tests {
                     body: vec![ASTNodes::ForLoop(ForLoop {
                         value: Variable {
                             name: "val".to_string(),
+                            id: None,
                         },
                         increment: Variable {
                             name: "index".to_string(),
+                            id: None,
                         },
                         iterator: Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "array".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
@@ -263,10 +315,12 @@ mod tests {
                             body: vec![ASTNodes::AssignStmt(AssignStmt {
                                 name: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 value: Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "i".to_string(),
+                                        id: None,
                                     })),
                                     right: Some(Box::new(ASTNodes::Literal(Literal {
                                         value: "2".to_string(),
@@ -274,11 +328,35 @@ mod tests {
                                     }))),
                                     operator: Some(Operator::MULTIPLY)
                                 },
+                                operator: None,
+                                line: 0,
+                                column: 56,
                             })]
-                        }
+                        },
+                        line: 0,
+                        column: 24,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         )
     }
+
+    #[test]
+    fn test_break_outside_loop() {
+        let code = "func main() {
+    loop {
+        if true {
+            break
+        }
+    }
+    break
+}";
+        let mut lexer = Lexer::new(code);
+        let err = Parser::new(lexer.tokenize()).parse().unwrap_err();
+        assert_eq!(err.code(), diagnostics::ErrorCode::BreakOutsideLoop);
+        assert_eq!(err.to_string(), "`break` can only be used inside a loop");
+    }
 }
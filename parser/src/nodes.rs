@@ -1,7 +1,6 @@
-use lexer::types::Operator;
-pub use lexer::types::{Datatype, Types};
+pub use lexer::types::{Datatype, Operator, Types};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ASTNodes {
     AssignStmt(AssignStmt),
     ArrayIndex(ArrayIndex),
@@ -24,27 +23,126 @@ pub enum ASTNodes {
     Variable(Variable),
     Break,
     Extern(Extern),
+    Try(Try),
 }
 
-#[derive(Debug, PartialEq)]
+impl ASTNodes {
+    /// What the node is, as error messages refer to it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ASTNodes::AssignStmt(_) => "assignment",
+            ASTNodes::ArrayIndex(_) => "array index",
+            ASTNodes::Attr(_) => "field access",
+            ASTNodes::Block(_) => "block",
+            ASTNodes::Conditional(_) => "`if` statement",
+            ASTNodes::Expression(expr) => expr.kind(),
+            ASTNodes::Function(_) => "function definition",
+            ASTNodes::FunctionCall(_) => "function call",
+            ASTNodes::ImportDef(_) => "import",
+            ASTNodes::ImportCall(_) => "imported function call",
+            ASTNodes::LetStmt(_) => "`let` statement",
+            ASTNodes::Literal(_) => "literal",
+            ASTNodes::Loop(_) => "loop",
+            ASTNodes::ForLoop(_) => "`loop range`",
+            ASTNodes::Method(_) => "method call",
+            ASTNodes::Return(_) => "`return` statement",
+            ASTNodes::StructDef(_) => "struct definition",
+            ASTNodes::Token(_) => "token",
+            ASTNodes::Variable(_) => "variable",
+            ASTNodes::Break => "`break`",
+            ASTNodes::Extern(_) => "extern function",
+            ASTNodes::Try(_) => "`?` expression",
+        }
+    }
+
+    /// The line the node starts on, for the nodes that record it
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ASTNodes::AssignStmt(node) => Some(node.line),
+            ASTNodes::ArrayIndex(node) => Some(node.line),
+            ASTNodes::Conditional(Conditional::If { line, .. }) => Some(*line),
+            ASTNodes::Function(node) => Some(node.line),
+            ASTNodes::FunctionCall(node) => Some(node.line),
+            ASTNodes::ImportDef(node) => Some(node.line),
+            ASTNodes::ImportCall(node) => node.ident.line(),
+            ASTNodes::LetStmt(node) => Some(node.line),
+            ASTNodes::Loop(node) => Some(node.line),
+            ASTNodes::ForLoop(node) => Some(node.line),
+            ASTNodes::Method(node) => Some(node.func.line),
+            ASTNodes::Return(node) => Some(node.line),
+            ASTNodes::StructDef(node) => Some(node.line),
+            ASTNodes::Extern(node) => Some(node.line),
+            ASTNodes::Try(node) => node.value.line(),
+            _ => None,
+        }
+    }
+
+    /// The column of the token the node starts at, counted from one like the lexer
+    /// does, for the nodes that record their line
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            ASTNodes::AssignStmt(node) => Some(node.column),
+            ASTNodes::ArrayIndex(node) => Some(node.column),
+            ASTNodes::Conditional(Conditional::If { column, .. }) => Some(*column),
+            ASTNodes::Function(node) => Some(node.column),
+            ASTNodes::FunctionCall(node) => Some(node.column),
+            ASTNodes::ImportDef(node) => Some(node.column),
+            ASTNodes::ImportCall(node) => node.ident.column(),
+            ASTNodes::LetStmt(node) => Some(node.column),
+            ASTNodes::Loop(node) => Some(node.column),
+            ASTNodes::ForLoop(node) => Some(node.column),
+            ASTNodes::Method(node) => Some(node.func.column),
+            ASTNodes::Return(node) => Some(node.column),
+            ASTNodes::StructDef(node) => Some(node.column),
+            ASTNodes::Extern(node) => Some(node.column),
+            ASTNodes::Try(node) => node.value.column(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Function {
     pub name: String,
-    pub args: Vec<(String, Datatype)>,
+    pub args: Vec<Param>,
     pub return_type: Option<Datatype>,
     pub body: Block,
+    pub line: usize,
+    pub column: usize,
+    pub attrs: Vec<Attribute>,
+}
+
+/// An attribute like `#[allow(unused_variables)]`, which applies to the function or
+/// struct after it
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Param {
+    pub name: String,
+    pub datatype: Datatype,
+    pub mutable: bool,
+    pub id: Option<DefId>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Block {
     pub body: Vec<ASTNodes>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Return {
     pub value: Option<Expression>,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     Simple {
         left: Box<ASTNodes>,
@@ -64,104 +162,149 @@ impl Expression {
         }
         false
     }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Expression::Simple { .. } => "expression",
+            Expression::Array(_) => "array literal",
+            Expression::String(_) => "string literal",
+            Expression::Struct(_) => "struct literal",
+            Expression::None => "empty expression",
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Literal {
     pub value: String,
     pub r#type: Types,
 }
 
-#[derive(Debug, PartialEq)]
+/// Identifies the `let`, parameter or loop variable a name refers to. Assigned by the
+/// resolution pass in `passes`, the parser leaves every id as `None`.
+pub type DefId = usize;
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Variable {
     pub name: String,
+    pub id: Option<DefId>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Attr {
     pub name: Variable,
     pub parent: Box<ASTNodes>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Method {
     pub func: FunctionCall,
     pub parent: Box<ASTNodes>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct LetStmt {
     pub name: String,
     pub value: Expression,
     pub datatype: Datatype,
     pub mutable: bool,
+    pub line: usize,
+    pub column: usize,
+    pub id: Option<DefId>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct FunctionCall {
     pub name: String,
     pub args: Vec<Expression>,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct StructDef {
     pub name: String,
     pub fields: Vec<(String, Datatype)>,
+    pub line: usize,
+    pub column: usize,
+    pub attrs: Vec<Attribute>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ImportDef {
     pub path: Vec<String>,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct AssignStmt {
     pub name: Box<ASTNodes>,
     pub value: Expression,
+    /// The operator of a compound assignment, `+` for `a += 1`. Expanded to
+    /// `a = a + 1` by the desugaring pass in `passes`.
+    pub operator: Option<Operator>,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Conditional {
     If {
         condition: Expression,
         body: Block,
         else_body: Option<Box<Conditional>>,
+        line: usize,
+        column: usize,
     },
     Else {
         body: Block,
     },
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Loop {
     pub condition: Option<Expression>,
     pub body: Block,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ForLoop {
     pub value: Variable,
     pub increment: Variable,
     pub iterator: Expression,
     pub body: Block,
     pub step: Option<Expression>,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ImportCall {
     pub path: Vec<String>,
     pub ident: Box<ASTNodes>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ArrayIndex {
     pub array_var: Box<ASTNodes>,
     pub index: Expression,
+    pub line: usize,
+    pub column: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Extern {
     pub name: String,
-    pub args: Vec<(String, Datatype)>,
+    pub args: Vec<Param>,
     pub return_type: Option<Datatype>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Try {
+    pub value: Box<ASTNodes>,
 }
@@ -7,6 +7,8 @@ use crate::{
 
 impl Parser {
     pub(crate) fn parse_statement(&mut self) -> Result<LetStmt> {
+        let line = self.current_line();
+        let column = self.current_column();
         let mut datatype = self.parse_datatype()?;
         let mutable = self.next_if_type(Types::OPERATOR(Operator::NOT)).is_some();
         let name = self.next_with_type(Types::IDENTIFIER)?;
@@ -20,6 +22,9 @@ impl Parser {
             value,
             datatype,
             mutable,
+            line,
+            column,
+            id: None,
         })
     }
 
@@ -59,19 +64,36 @@ impl Parser {
         }
 
         return Ok(StructDef {
+            line: name.line,
+            column: name.column,
             name: name.value.unwrap(),
             fields: args,
+            attrs: vec![],
         });
     }
 
     pub(crate) fn parse_assign_stmt(&mut self) -> Result<AssignStmt> {
+        let line = self.current_line();
+        let column = self.current_column();
         let name = self.parse_complex_variable()?;
-        self.next_with_type(Types::OPERATOR(Operator::ASSIGN))?;
+        let operator = match self.peek().map(|token| token.r#type) {
+            Some(Types::OPERATOR(op)) if op.compound_operator().is_some() => {
+                self.next();
+                op.compound_operator()
+            }
+            _ => {
+                self.next_with_type(Types::OPERATOR(Operator::ASSIGN))?;
+                None
+            }
+        };
         let value = self.parse_expression(vec![Types::NL, Types::DELIMITER(Delimiter::RBRACE)])?;
 
         Ok(AssignStmt {
             name: Box::new(name),
             value,
+            operator,
+            line,
+            column,
         })
     }
 }
@@ -108,9 +130,15 @@ mod tests {
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 0,
+                        column: 19,
+                        id: None,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -133,14 +161,21 @@ mod tests {
                         value: Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "a".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: true
+                        mutable: true,
+                        line: 0,
+                        column: 19,
+                        id: None,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -158,7 +193,10 @@ mod tests {
                 fields: vec![
                     ("a".to_string(), Datatype::U32),
                     ("b".to_string(), Datatype::U32)
-                ]
+                ],
+                line: 0,
+                column: 8,
+                attrs: vec![],
             })]
         );
     }
@@ -179,6 +217,7 @@ mod tests {
                     body: vec![ASTNodes::AssignStmt(AssignStmt {
                         name: Box::new(ASTNodes::Variable(Variable {
                             name: "a".to_string(),
+                            id: None,
                         })),
                         value: Expression::Simple {
                             left: Box::new(ASTNodes::Literal(Literal {
@@ -191,8 +230,52 @@ mod tests {
                             }))),
                             operator: Some(Operator::PLUS)
                         },
+                        operator: None,
+                        line: 0,
+                        column: 19,
+                    })]
+                },
+                line: 0,
+                column: 6,
+                attrs: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_compound_assign() {
+        let mut lexer = Lexer::new("func main() u32 { a <<= 2 }");
+
+        let mut parser = Parser::new(lexer.tokenize());
+        let ast = parser.parse().unwrap();
+        assert_eq!(
+            ast,
+            vec![ASTNodes::Function(Function {
+                name: "main".to_string(),
+                args: vec![],
+                return_type: Some(Datatype::U32),
+                body: Block {
+                    body: vec![ASTNodes::AssignStmt(AssignStmt {
+                        name: Box::new(ASTNodes::Variable(Variable {
+                            name: "a".to_string(),
+                            id: None,
+                        })),
+                        value: Expression::Simple {
+                            left: Box::new(ASTNodes::Literal(Literal {
+                                value: "2".to_string(),
+                                r#type: lexer::types::Types::NUMBER
+                            })),
+                            right: None,
+                            operator: None
+                        },
+                        operator: Some(Operator::LSHIFT),
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
@@ -213,10 +296,12 @@ mod tests {
                     body: vec![ASTNodes::AssignStmt(AssignStmt {
                         name: Box::new(ASTNodes::Attr(Attr {
                             name: Variable {
-                                name: "b".to_string()
+                                name: "b".to_string(),
+                                id: None,
                             },
                             parent: Box::new(ASTNodes::Variable(Variable {
-                                name: "a".to_string()
+                                name: "a".to_string(),
+                                id: None,
                             }))
                         })),
                         value: Expression::Simple {
@@ -230,8 +315,14 @@ mod tests {
                             }))),
                             operator: Some(Operator::PLUS)
                         },
+                        operator: None,
+                        line: 0,
+                        column: 19,
                     })]
                 },
+                line: 0,
+                column: 6,
+                attrs: vec![],
             })]
         );
     }
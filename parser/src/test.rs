@@ -7,7 +7,8 @@ use crate::{
     Parser,
     nodes::{
         ASTNodes, AssignStmt, Block, Conditional, Expression, ForLoop, Function, FunctionCall,
-        ImportCall, ImportDef, LetStmt, Literal, Loop, Method, Return, StructDef, Variable,
+        ImportCall, ImportDef, LetStmt, Literal, Loop, Method, Param, Return, StructDef, Try,
+        Variable,
     },
 };
 
@@ -42,7 +43,10 @@ fn test_parse_full_1() {
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 2,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::LetStmt(LetStmt {
                         name: "b".to_string(),
@@ -55,10 +59,16 @@ fn test_parse_full_1() {
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 3,
+                        column: 9,
+                        id: None,
                     })
                 ]
             },
+            line: 1,
+            column: 10,
+            attrs: vec![],
         })]
     );
 }
@@ -86,8 +96,18 @@ fn test_parse_full_2() {
             ASTNodes::Function(Function {
                 name: "num".to_string(),
                 args: vec![
-                    ("e".to_string(), Datatype::U32),
-                    ("f".to_string(), Datatype::U32)
+                    Param {
+                        name: "e".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    },
+                    Param {
+                        name: "f".to_string(),
+                        datatype: Datatype::U32,
+                        mutable: false,
+                        id: None,
+                    }
                 ],
                 return_type: Some(Datatype::U32),
                 body: Block {
@@ -95,14 +115,21 @@ fn test_parse_full_2() {
                         value: Some(Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "e".to_string(),
+                                id: None,
                             })),
                             right: Some(Box::new(ASTNodes::Variable(Variable {
                                 name: "f".to_string(),
+                                id: None,
                             }))),
                             operator: Some(Operator::PLUS)
-                        })
+                        }),
+                        line: 2,
+                        column: 9,
                     })]
-                }
+                },
+                line: 1,
+                column: 10,
+                attrs: vec![],
             }),
             ASTNodes::Function(Function {
                 name: "main".to_string(),
@@ -121,7 +148,10 @@ fn test_parse_full_2() {
                                 operator: None
                             },
                             datatype: Datatype::U32,
-                            mutable: false
+                            mutable: false,
+                            line: 6,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::LetStmt(LetStmt {
                             name: "b".to_string(),
@@ -146,28 +176,40 @@ fn test_parse_full_2() {
                                                 right: None,
                                                 operator: None
                                             }
-                                        ]
+                                        ],
+                                        line: 7,
+                                        column: 21,
                                     }
                                 )),
                                 right: None,
                                 operator: None,
                             },
                             datatype: Datatype::U32,
-                            mutable: false
+                            mutable: false,
+                            line: 7,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::Return(Return {
                             value: Some(Expression::Simple {
                                 left: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 right: Some(Box::new(ASTNodes::Variable(Variable {
                                     name: "b".to_string(),
+                                    id: None,
                                 }))),
                                 operator: Some(Operator::MULTIPLY)
-                            })
+                            }),
+                            line: 8,
+                            column: 9,
                         })
                     ]
-                }
+                },
+                line: 5,
+                column: 10,
+                attrs: vec![],
             })
         ]
     )
@@ -217,10 +259,14 @@ fn test_parse_full_3() {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 3,
+                                column: 13,
                             })]
                         },
                         else_body: None,
+                        line: 2,
+                        column: 9,
                     }),
                     ASTNodes::Conditional(Conditional::If {
                         condition: Expression::Simple {
@@ -240,10 +286,14 @@ fn test_parse_full_3() {
                                     })),
                                     right: None,
                                     operator: None
-                                })
+                                }),
+                                line: 7,
+                                column: 13,
                             })]
                         },
                         else_body: None,
+                        line: 6,
+                        column: 9,
                     }),
                     ASTNodes::Return(Return {
                         value: Some(Expression::Simple {
@@ -253,10 +303,15 @@ fn test_parse_full_3() {
                             })),
                             right: None,
                             operator: None
-                        })
+                        }),
+                        line: 9,
+                        column: 9,
                     })
                 ]
             },
+            line: 1,
+            column: 10,
+            attrs: vec![],
         })]
     );
 }
@@ -296,6 +351,9 @@ fn test_parse_full_4() {
                         },
                         datatype: Datatype::U32,
                         mutable: true,
+                        line: 2,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::Loop(Loop {
                         condition: None,
@@ -303,10 +361,12 @@ fn test_parse_full_4() {
                             body: vec![ASTNodes::AssignStmt(AssignStmt {
                                 name: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 value: Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "a".to_string(),
+                                        id: None,
                                     })),
                                     right: Some(Box::new(ASTNodes::Literal(Literal {
                                         value: "1".to_string(),
@@ -314,20 +374,31 @@ fn test_parse_full_4() {
                                     }))),
                                     operator: Some(Operator::PLUS)
                                 },
+                                operator: None,
+                                line: 4,
+                                column: 13,
                             })]
                         },
+                        line: 3,
+                        column: 9,
                     }),
                     ASTNodes::Return(Return {
                         value: Some(Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "a".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
-                        })
+                        }),
+                        line: 6,
+                        column: 9,
                     })
                 ]
             },
+            line: 1,
+            column: 10,
+            attrs: vec![],
         })]
     );
 }
@@ -382,7 +453,10 @@ fn test_parse_full_5() {
                             }
                         ]),
                         datatype: Datatype::NARRAY(Box::new(Datatype::U32), 3),
-                        mutable: false
+                        mutable: false,
+                        line: 2,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::LetStmt(LetStmt {
                         name: "b".to_string(),
@@ -390,29 +464,41 @@ fn test_parse_full_5() {
                             left: Box::new(ASTNodes::Method(Method {
                                 func: FunctionCall {
                                     name: "len".to_string(),
-                                    args: vec![]
+                                    args: vec![],
+                                    line: 3,
+                                    column: 23,
                                 },
                                 parent: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 }))
                             })),
                             right: None,
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: false
+                        mutable: false,
+                        line: 3,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::Return(Return {
                         value: Some(Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "b".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
-                        })
+                        }),
+                        line: 4,
+                        column: 9,
                     })
                 ]
             },
+            line: 1,
+            column: 10,
+            attrs: vec![],
         })]
     );
 }
@@ -436,6 +522,8 @@ fn test_parse_full_6() {
         vec![
             ASTNodes::ImportDef(ImportDef {
                 path: vec!["std".to_string(), "io".to_string()],
+                line: 1,
+                column: 5,
             }),
             ASTNodes::Function(Function {
                 name: "main".to_string(),
@@ -448,6 +536,8 @@ fn test_parse_full_6() {
                             ident: Box::new(ASTNodes::FunctionCall(FunctionCall {
                                 name: "println".to_string(),
                                 args: vec![Expression::String("Hello World".to_string())],
+                                line: 4,
+                                column: 13,
                             })),
                         }),
                         ASTNodes::Return(Return {
@@ -459,9 +549,14 @@ fn test_parse_full_6() {
                                 right: None,
                                 operator: None
                             }),
+                            line: 5,
+                            column: 9,
                         }),
                     ],
                 },
+                line: 3,
+                column: 10,
+                attrs: vec![],
             })
         ]
     )
@@ -495,7 +590,10 @@ fn test_parse_full_7() {
                 fields: vec![
                     ("a".to_string(), Datatype::U32),
                     ("b".to_string(), Datatype::U32),
-                ]
+                ],
+                line: 1,
+                column: 12,
+                attrs: vec![],
             }),
             ASTNodes::Function(Function {
                 name: "main".to_string(),
@@ -530,19 +628,28 @@ fn test_parse_full_7() {
                                 )
                             ]),
                             datatype: Datatype::CUSTOM("Test".to_string()),
-                            mutable: false
+                            mutable: false,
+                            line: 7,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::Return(Return {
                             value: Some(Expression::Simple {
                                 left: Box::new(ASTNodes::Variable(Variable {
                                     name: "t".to_string(),
+                                    id: None,
                                 })),
                                 right: None,
                                 operator: None
-                            })
+                            }),
+                            line: 11,
+                            column: 9,
                         })
                     ]
-                }
+                },
+                line: 6,
+                column: 10,
+                attrs: vec![],
             })
         ]
     )
@@ -574,6 +681,8 @@ fn test_parse_full_8() {
         vec![
             ASTNodes::ImportDef(ImportDef {
                 path: vec!["std".to_string(), "io".to_string()],
+                line: 1,
+                column: 5,
             }),
             ASTNodes::Function(Function {
                 name: "main".to_string(),
@@ -592,13 +701,17 @@ fn test_parse_full_8() {
                                 operator: None
                             },
                             datatype: Datatype::F32,
-                            mutable: false
+                            mutable: false,
+                            line: 4,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::LetStmt(LetStmt {
                             name: "b".to_string(),
                             value: Expression::Simple {
                                 left: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 right: Some(Box::new(ASTNodes::Token(Types::DATATYPE(
                                     Datatype::U32
@@ -606,13 +719,17 @@ fn test_parse_full_8() {
                                 operator: Some(Operator::CAST)
                             },
                             datatype: Datatype::U32,
-                            mutable: false
+                            mutable: false,
+                            line: 5,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::LetStmt(LetStmt {
                             name: "c".to_string(),
                             value: Expression::Simple {
                                 left: Box::new(ASTNodes::Variable(Variable {
                                     name: "b".to_string(),
+                                    id: None,
                                 })),
                                 right: Some(Box::new(ASTNodes::Token(Types::DATATYPE(
                                     Datatype::F32
@@ -620,7 +737,10 @@ fn test_parse_full_8() {
                                 operator: Some(Operator::CAST)
                             },
                             datatype: Datatype::F32,
-                            mutable: false
+                            mutable: false,
+                            line: 6,
+                            column: 9,
+                            id: None,
                         }),
                         ASTNodes::ImportCall(ImportCall {
                             path: vec!["io".to_string(), "printflt".to_string()],
@@ -629,10 +749,13 @@ fn test_parse_full_8() {
                                 args: vec![Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "a".to_string(),
+                                        id: None,
                                     })),
                                     right: None,
                                     operator: None
                                 }],
+                                line: 8,
+                                column: 13,
                             })),
                         }),
                         ASTNodes::ImportCall(ImportCall {
@@ -642,10 +765,13 @@ fn test_parse_full_8() {
                                 args: vec![Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "b".to_string(),
+                                        id: None,
                                     })),
                                     right: None,
                                     operator: None
                                 }],
+                                line: 9,
+                                column: 13,
                             })),
                         }),
                         ASTNodes::ImportCall(ImportCall {
@@ -655,10 +781,13 @@ fn test_parse_full_8() {
                                 args: vec![Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "c".to_string(),
+                                        id: None,
                                     })),
                                     right: None,
                                     operator: None
                                 }],
+                                line: 10,
+                                column: 13,
                             })),
                         }),
                         ASTNodes::Return(Return {
@@ -669,10 +798,15 @@ fn test_parse_full_8() {
                                 })),
                                 right: None,
                                 operator: None
-                            })
+                            }),
+                            line: 12,
+                            column: 9,
                         })
                     ]
                 },
+                line: 3,
+                column: 10,
+                attrs: vec![],
             })
         ]
     )
@@ -746,7 +880,10 @@ fn test_parse_full_9() {
                                 right: None,
                                 operator: None
                             }
-                        ])
+                        ]),
+                        line: 2,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::LetStmt(LetStmt {
                         name: "a".to_string(),
@@ -759,18 +896,24 @@ fn test_parse_full_9() {
                             operator: None
                         },
                         datatype: Datatype::U32,
-                        mutable: true
+                        mutable: true,
+                        line: 3,
+                        column: 9,
+                        id: None,
                     }),
                     ASTNodes::ForLoop(ForLoop {
                         value: Variable {
                             name: "v".to_string(),
+                            id: None,
                         },
                         increment: Variable {
                             name: "i".to_string(),
+                            id: None,
                         },
                         iterator: Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "array".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
@@ -780,10 +923,12 @@ fn test_parse_full_9() {
                             body: vec![ASTNodes::AssignStmt(AssignStmt {
                                 name: Box::new(ASTNodes::Variable(Variable {
                                     name: "a".to_string(),
+                                    id: None,
                                 })),
                                 value: Expression::Simple {
                                     left: Box::new(ASTNodes::Variable(Variable {
                                         name: "i".to_string(),
+                                        id: None,
                                     })),
                                     right: Some(Box::new(ASTNodes::Literal(Literal {
                                         value: "1".to_string(),
@@ -791,20 +936,107 @@ fn test_parse_full_9() {
                                     }))),
                                     operator: Some(Operator::PLUS)
                                 },
+                                operator: None,
+                                line: 5,
+                                column: 13,
                             })]
-                        }
+                        },
+                        line: 4,
+                        column: 14,
                     }),
                     ASTNodes::Return(Return {
                         value: Some(Expression::Simple {
                             left: Box::new(ASTNodes::Variable(Variable {
                                 name: "a".to_string(),
+                                id: None,
                             })),
                             right: None,
                             operator: None
-                        })
+                        }),
+                        line: 7,
+                        column: 9,
                     })
                 ]
             },
+            line: 1,
+            column: 10,
+            attrs: vec![],
         })]
     );
 }
+
+#[test]
+fn test_parse_full_10() {
+    let mut lexer = Lexer::new(
+        r#"
+    import std::fs
+
+    func save() Result<u64, i32> {
+        fs::write("a.txt", "a")?
+        return ok(0)
+    }"#,
+    );
+
+    let mut parser = Parser::new(lexer.tokenize());
+    let ast = parser.parse().unwrap();
+    assert_eq!(
+        ast,
+        vec![
+            ASTNodes::ImportDef(ImportDef {
+                path: vec!["std".to_string(), "fs".to_string()],
+                line: 1,
+                column: 5,
+            }),
+            ASTNodes::Function(Function {
+                name: "save".to_string(),
+                args: vec![],
+                return_type: Some(Datatype::RESULT(
+                    Box::new(Datatype::U64),
+                    Box::new(Datatype::I32)
+                )),
+                body: Block {
+                    body: vec![
+                        ASTNodes::Try(Try {
+                            value: Box::new(ASTNodes::ImportCall(ImportCall {
+                                path: vec!["fs".to_string(), "write".to_string()],
+                                ident: Box::new(ASTNodes::FunctionCall(FunctionCall {
+                                    name: "write".to_string(),
+                                    args: vec![
+                                        Expression::String("a.txt".to_string()),
+                                        Expression::String("a".to_string())
+                                    ],
+                                    line: 4,
+                                    column: 13,
+                                }))
+                            }))
+                        }),
+                        ASTNodes::Return(Return {
+                            value: Some(Expression::Simple {
+                                left: Box::new(ASTNodes::FunctionCall(FunctionCall {
+                                    name: "ok".to_string(),
+                                    args: vec![Expression::Simple {
+                                        left: Box::new(ASTNodes::Literal(Literal {
+                                            value: "0".to_string(),
+                                            r#type: lexer::types::Types::NUMBER
+                                        })),
+                                        right: None,
+                                        operator: None
+                                    }],
+                                    line: 5,
+                                    column: 16,
+                                })),
+                                right: None,
+                                operator: None
+                            }),
+                            line: 5,
+                            column: 9,
+                        })
+                    ]
+                },
+                line: 3,
+                column: 10,
+                attrs: vec![],
+            })
+        ]
+    );
+}
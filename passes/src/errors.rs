@@ -0,0 +1,236 @@
+use std::{error::Error, fmt::Display};
+
+use diagnostics::{Diagnostic, ErrorCode, Span};
+
+use crate::lints::Lint;
+
+#[derive(Debug, PartialEq)]
+pub struct PassError {
+    /// Only errors of custom passes have no code
+    pub(crate) code: Option<ErrorCode>,
+    pub(crate) msg: String,
+    pub(crate) line: usize,
+    /// Counted from one like the lexer does, if the node the error is about records it
+    pub(crate) column: Option<usize>,
+    /// Lines related to the error, with what they have to do with it
+    pub(crate) labels: Vec<(usize, String)>,
+    pub(crate) help: Option<String>,
+}
+
+impl Error for PassError {}
+
+impl PassError {
+    pub fn new(msg: &str, line: usize) -> Self {
+        Self {
+            code: None,
+            msg: msg.to_string(),
+            line,
+            column: None,
+            labels: vec![],
+            help: None,
+        }
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_label(mut self, line: usize, msg: &str) -> Self {
+        self.labels.push((line, msg.to_string()));
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub(crate) fn mismatched_types(
+        expected: &impl Display,
+        found: &impl Display,
+        line: usize,
+    ) -> Self {
+        Self::new(
+            &format!(
+                "Mismatched types; expected `{}`, found `{}`",
+                expected, found
+            ),
+            line,
+        )
+        .with_code(ErrorCode::MismatchedTypes)
+    }
+}
+
+impl Display for PassError {
+    // Lines are counted from zero by the lexer
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line + 1, self.msg)
+    }
+}
+
+impl From<PassWarning> for PassError {
+    fn from(warning: PassWarning) -> Self {
+        let err = Self::new(&format!("{} [{}]", warning.msg, warning.lint), warning.line)
+            .with_code(ErrorCode::DeniedLint);
+        match warning.column {
+            Some(column) => err.with_column(column),
+            None => err,
+        }
+    }
+}
+
+impl From<&PassError> for Diagnostic {
+    fn from(err: &PassError) -> Self {
+        let mut diag = Diagnostic::error(&err.msg).with_primary(span(err.line, err.column), "");
+        if let Some(code) = err.code {
+            diag = diag.with_code(code);
+        }
+        for (line, msg) in err.labels.iter() {
+            diag = diag.with_label(Span::line(*line), msg);
+        }
+        match &err.help {
+            Some(help) => diag.with_help(help),
+            None => diag,
+        }
+    }
+}
+
+/// Found by a lint, these only stop compilation when the lint is denied
+#[derive(Debug, PartialEq)]
+pub struct PassWarning {
+    pub(crate) lint: Lint,
+    pub(crate) msg: String,
+    pub(crate) line: usize,
+    pub(crate) column: Option<usize>,
+}
+
+impl PassWarning {
+    pub fn new(lint: Lint, msg: &str, line: usize) -> Self {
+        Self {
+            lint,
+            msg: msg.to_string(),
+            line,
+            column: None,
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+}
+
+impl Display for PassWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: warning: {} [{}]",
+            self.line + 1,
+            self.msg,
+            self.lint
+        )
+    }
+}
+
+impl From<&PassWarning> for Diagnostic {
+    fn from(warning: &PassWarning) -> Self {
+        Diagnostic::warning(&warning.msg)
+            .with_primary(span(warning.line, warning.column), "")
+            .with_note(&format!(
+                "from the `{0}` lint, `-A {0}` allows it",
+                warning.lint
+            ))
+    }
+}
+
+/// The column of a node is where its first token starts, so the caret points there
+fn span(line: usize, column: Option<usize>) -> Span {
+    match column {
+        Some(column) => Span::new(line, column.saturating_sub(1), 1),
+        None => Span::line(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diagnostics::{Diagnostic, ErrorCode, Span};
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use crate::r#impl::PassManager;
+
+    /// The codes of the errors found in the code, or `None` if it compiles
+    fn compile(code: &str) -> Option<Vec<ErrorCode>> {
+        let mut lexer = Lexer::new(code);
+        let mut ast = match Parser::new(lexer.tokenize()).parse() {
+            Ok(ast) => ast,
+            Err(err) => return Some(vec![err.code()]),
+        };
+        match PassManager::new(&mut ast).invoke() {
+            Ok(_) => None,
+            Err(errors) => Some(errors.iter().filter_map(|err| err.code()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_error_code_examples() {
+        // Found when generating code
+        let backend = [ErrorCode::UnusedResult, ErrorCode::Unsupported];
+        for code in ErrorCode::ALL {
+            let Some((failing, fixed)) = code.explain().example else {
+                continue;
+            };
+            match compile(failing) {
+                Some(codes) => assert!(codes.contains(&code), "{}: found {:?}", code, codes),
+                None => assert!(backend.contains(&code), "{}: no error", code),
+            }
+            assert_eq!(compile(fixed), None, "{}", code);
+        }
+    }
+
+    /// The codes of the errors found in the code, with where their carets point
+    fn spans(code: &str) -> Vec<(Option<ErrorCode>, Option<Span>)> {
+        let mut ast = Parser::new(Lexer::new(code).tokenize()).parse().unwrap();
+        let errors = PassManager::new(&mut ast).invoke().unwrap_err();
+        errors
+            .iter()
+            .map(|err| (err.code(), Diagnostic::from(err).primary_span()))
+            .collect()
+    }
+
+    #[test]
+    fn test_error_columns() {
+        let assign = "func main() u32 {
+    let u32 a = 1
+    if a == 1 { a = 2 }
+    return a
+}";
+        assert_eq!(
+            spans(assign),
+            vec![(
+                Some(ErrorCode::ImmutableAssignment),
+                Some(Span::new(2, 16, 1))
+            )]
+        );
+
+        let denied = "#[deny(unused_variables)]
+func main() u32 {
+    let u32 a = 1
+    return 0
+}";
+        assert_eq!(
+            spans(denied),
+            vec![(Some(ErrorCode::DeniedLint), Some(Span::new(2, 4, 1)))]
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use diagnostics::ErrorCode;
+use parser::nodes::{
+    ASTNodes, ArrayIndex, AssignStmt, Block, Conditional, Datatype, DefId, Expression, ForLoop,
+    Function, LetStmt, Literal, Loop, Operator, Types, Variable,
+};
+
+use crate::{
+    errors::PassError,
+    r#impl::returns::stmt_diverges,
+    visit_mut::{self, VisitorMut},
+};
+
+/// Lowers constructs that can be written with simpler ones, so that the backend
+/// only has to handle a small core of the AST:
+/// - `loop range val, i = arr { .. }` becomes a `loop i < len { .. }` over a `u64` index,
+///   with `val` replaced by `arr[i]` in the body
+/// - `a += b` becomes `a = a + b`
+/// - `else if` becomes an `else` whose body is the `if`
+///
+/// Runs after the checks, so that errors point at the code as it was written.
+/// Relies on the ids set by the resolution pass.
+pub(crate) struct Desugarer {
+    types: HashMap<DefId, Datatype>,
+    errors: Vec<PassError>,
+}
+
+impl Desugarer {
+    pub(crate) fn lower(nodes: &mut [ASTNodes]) -> Vec<PassError> {
+        let mut desugarer = Self {
+            types: HashMap::new(),
+            errors: vec![],
+        };
+        nodes.iter_mut().for_each(|node| desugarer.visit_node(node));
+        desugarer.errors
+    }
+
+    fn lower_stmt(&mut self, node: ASTNodes) -> Vec<ASTNodes> {
+        match node {
+            ASTNodes::ForLoop(stmt) => self.lower_for_loop(stmt),
+            ASTNodes::AssignStmt(AssignStmt {
+                name,
+                value,
+                operator: Some(op),
+                line,
+                column,
+            }) => {
+                let value = Expression::Simple {
+                    left: name.clone(),
+                    right: Some(Box::new(into_node(value))),
+                    operator: Some(op),
+                };
+                vec![ASTNodes::AssignStmt(AssignStmt {
+                    name,
+                    value,
+                    operator: None,
+                    line,
+                    column,
+                })]
+            }
+            node => vec![node],
+        }
+    }
+
+    fn lower_for_loop(&mut self, stmt: ForLoop) -> Vec<ASTNodes> {
+        let array = match &stmt.iterator {
+            Expression::Simple {
+                left, right: None, ..
+            } => match left.as_ref() {
+                ASTNodes::Variable(var) => Some(var),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((array, len)) = array.and_then(|var| match self.types.get(&var.id?) {
+            Some(Datatype::NARRAY(_, len)) => Some((var, *len)),
+            _ => None,
+        }) else {
+            self.errors.push(
+                PassError::new(
+                    "Only arrays stored in variables can be iterated over",
+                    stmt.line,
+                )
+                .with_code(ErrorCode::NotIterable)
+                .with_column(stmt.column),
+            );
+            return vec![];
+        };
+
+        let index = || {
+            ASTNodes::Variable(Variable {
+                name: stmt.increment.name.clone(),
+                id: stmt.increment.id,
+            })
+        };
+        let mut body = stmt.body;
+        if let Some(id) = stmt.value.id {
+            let element = ASTNodes::ArrayIndex(ArrayIndex {
+                array_var: Box::new(ASTNodes::Variable(array.clone())),
+                index: Expression::Simple {
+                    left: Box::new(index()),
+                    right: None,
+                    operator: None,
+                },
+                line: stmt.line,
+                column: stmt.column,
+            });
+            Substitute { id, element }.visit_block(&mut body);
+        }
+
+        // Nothing runs after a statement that leaves the loop
+        let diverges = body
+            .body
+            .iter()
+            .any(|node| matches!(node, ASTNodes::Break) || stmt_diverges(node));
+        if !diverges {
+            let step = stmt.step.map_or(number("1"), into_node);
+            body.body.push(ASTNodes::AssignStmt(AssignStmt {
+                name: Box::new(index()),
+                value: Expression::Simple {
+                    left: Box::new(index()),
+                    right: Some(Box::new(step)),
+                    operator: Some(Operator::PLUS),
+                },
+                operator: None,
+                line: stmt.line,
+                column: stmt.column,
+            }));
+        }
+
+        vec![
+            ASTNodes::LetStmt(LetStmt {
+                name: stmt.increment.name.clone(),
+                value: Expression::Simple {
+                    left: Box::new(number("0")),
+                    right: None,
+                    operator: None,
+                },
+                datatype: Datatype::U64,
+                mutable: true,
+                line: stmt.line,
+                column: stmt.column,
+                id: stmt.increment.id,
+            }),
+            ASTNodes::Loop(Loop {
+                condition: Some(Expression::Simple {
+                    left: Box::new(index()),
+                    right: Some(Box::new(number(&len.to_string()))),
+                    operator: Some(Operator::LESSER),
+                }),
+                body,
+                line: stmt.line,
+                column: stmt.column,
+            }),
+        ]
+    }
+}
+
+impl VisitorMut for Desugarer {
+    fn visit_function(&mut self, func: &mut Function) {
+        for param in func.args.iter() {
+            if let Some(id) = param.id {
+                self.types.insert(id, param.datatype.clone());
+            }
+        }
+        visit_mut::walk_function(self, func);
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &mut LetStmt) {
+        if let Some(id) = stmt.id {
+            self.types.insert(id, stmt.datatype.clone());
+        }
+        visit_mut::walk_let_stmt(self, stmt);
+    }
+
+    fn visit_for_loop(&mut self, stmt: &mut ForLoop) {
+        // The type of the loop variable, for loops over it in the body
+        let array = match &stmt.iterator {
+            Expression::Simple { left, .. } => match left.as_ref() {
+                ASTNodes::Variable(var) => var.id.and_then(|id| self.types.get(&id)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let (Some(Datatype::NARRAY(dt, _)), Some(id)) = (array, stmt.value.id) {
+            self.types.insert(id, *dt.clone());
+        }
+        visit_mut::walk_for_loop(self, stmt);
+    }
+
+    /// Nested blocks are lowered first, so the statements here only contain lowered code
+    fn visit_block(&mut self, block: &mut Block) {
+        visit_mut::walk_block(self, block);
+        block.body = std::mem::take(&mut block.body)
+            .into_iter()
+            .flat_map(|node| self.lower_stmt(node))
+            .collect();
+    }
+
+    fn visit_conditional(&mut self, cond: &mut Conditional) {
+        visit_mut::walk_conditional(self, cond);
+        if let Conditional::If {
+            else_body: Some(else_body),
+            ..
+        } = cond
+            && matches!(**else_body, Conditional::If { .. })
+        {
+            let empty = Conditional::Else {
+                body: Block { body: vec![] },
+            };
+            let inner = std::mem::replace(&mut **else_body, empty);
+            **else_body = Conditional::Else {
+                body: Block {
+                    body: vec![ASTNodes::Conditional(inner)],
+                },
+            };
+        }
+    }
+}
+
+/// Replaces the loop variable `id` with the element it stands for
+struct Substitute {
+    id: DefId,
+    element: ASTNodes,
+}
+
+impl VisitorMut for Substitute {
+    fn visit_node(&mut self, node: &mut ASTNodes) {
+        match node {
+            ASTNodes::Variable(var) if var.id == Some(self.id) => *node = self.element.clone(),
+            node => visit_mut::walk_node(self, node),
+        }
+    }
+}
+
+fn number(value: &str) -> ASTNodes {
+    ASTNodes::Literal(Literal {
+        value: value.to_string(),
+        r#type: Types::NUMBER,
+    })
+}
+
+/// The node of an expression, without wrapping single values
+fn into_node(expr: Expression) -> ASTNodes {
+    match expr {
+        Expression::Simple {
+            left,
+            right: None,
+            operator: None,
+        } => *left,
+        expr => ASTNodes::Expression(expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{
+            ASTNodes, ArrayIndex, AssignStmt, Conditional, Function, FunctionCall, LetStmt, Loop,
+            Param, Return, Variable,
+        },
+    };
+
+    use super::Desugarer;
+    use crate::{
+        r#impl::resolve::Resolver,
+        visit_mut::{self, VisitorMut},
+    };
+
+    /// Clears ids and positions, which differ between the lowered code and code written that way
+    struct Normalize;
+
+    impl VisitorMut for Normalize {
+        fn visit_function(&mut self, func: &mut Function) {
+            (func.line, func.column) = (0, 0);
+            visit_mut::walk_function(self, func);
+        }
+
+        fn visit_param(&mut self, param: &mut Param) {
+            param.id = None;
+        }
+
+        fn visit_let_stmt(&mut self, stmt: &mut LetStmt) {
+            (stmt.id, stmt.line, stmt.column) = (None, 0, 0);
+            visit_mut::walk_let_stmt(self, stmt);
+        }
+
+        fn visit_assign_stmt(&mut self, stmt: &mut AssignStmt) {
+            (stmt.line, stmt.column) = (0, 0);
+            visit_mut::walk_assign_stmt(self, stmt);
+        }
+
+        fn visit_return(&mut self, ret: &mut Return) {
+            (ret.line, ret.column) = (0, 0);
+            visit_mut::walk_return(self, ret);
+        }
+
+        fn visit_conditional(&mut self, cond: &mut Conditional) {
+            if let Conditional::If { line, column, .. } = cond {
+                (*line, *column) = (0, 0);
+            }
+            visit_mut::walk_conditional(self, cond);
+        }
+
+        fn visit_loop(&mut self, stmt: &mut Loop) {
+            (stmt.line, stmt.column) = (0, 0);
+            visit_mut::walk_loop(self, stmt);
+        }
+
+        fn visit_function_call(&mut self, call: &mut FunctionCall) {
+            (call.line, call.column) = (0, 0);
+            visit_mut::walk_function_call(self, call);
+        }
+
+        fn visit_array_index(&mut self, index: &mut ArrayIndex) {
+            (index.line, index.column) = (0, 0);
+            visit_mut::walk_array_index(self, index);
+        }
+
+        fn visit_variable(&mut self, var: &mut Variable) {
+            var.id = None;
+        }
+    }
+
+    fn parse(code: &str) -> Vec<ASTNodes> {
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        assert!(Resolver::resolve(&mut ast).is_empty());
+        ast
+    }
+
+    fn lower(code: &str) -> Result<Vec<ASTNodes>, Vec<String>> {
+        let mut ast = parse(code);
+        let errors = Desugarer::lower(&mut ast);
+        if !errors.is_empty() {
+            return Err(errors.iter().map(|err| err.to_string()).collect());
+        }
+        ast.iter_mut().for_each(|node| Normalize.visit_node(node));
+        Ok(ast)
+    }
+
+    fn expected(code: &str) -> Result<Vec<ASTNodes>, Vec<String>> {
+        let mut ast = parse(code);
+        ast.iter_mut().for_each(|node| Normalize.visit_node(node));
+        Ok(ast)
+    }
+
+    #[test]
+    fn test_lower_for_loops() {
+        let code = "
+func main() u64 {
+    let u64[] arr = [1, 2, 3]
+    let u64[] other = [4, 5]
+    let u64! total = 0
+    loop range[::2] val, i = arr {
+        total += val * i
+        loop range x, _j = other {
+            total -= x
+            break
+        }
+    }
+    return total
+}";
+        let lowered = "
+func main() u64 {
+    let u64[] arr = [1, 2, 3]
+    let u64[] other = [4, 5]
+    let u64! total = 0
+    let u64! i = 0
+    loop i < 3 {
+        total = total + (arr[i] * i)
+        let u64! _j = 0
+        loop _j < 2 {
+            total = total - other[_j]
+            break
+        }
+        i = i + 2
+    }
+    return total
+}";
+        assert_eq!(lower(code), expected(lowered));
+    }
+
+    #[test]
+    fn test_lower_else_if() {
+        let code = "
+func main(a u32) u32 {
+    if a > 2 {
+        return 1
+    } else if a > 1 {
+        return 2
+    } else if a > 0 {
+        return 3
+    }
+    return 4
+}";
+        let lowered = "
+func main(a u32) u32 {
+    if a > 2 {
+        return 1
+    } else {
+        if a > 1 {
+            return 2
+        } else {
+            if a > 0 {
+                return 3
+            }
+        }
+    }
+    return 4
+}";
+        assert_eq!(lower(code), expected(lowered));
+    }
+
+    #[test]
+    fn test_unsupported_iterator() {
+        let code = "
+func items() u32[] {
+    return [1, 2]
+}
+
+func main() {
+    loop range val, i = items() {
+    }
+}";
+        assert_eq!(
+            lower(code),
+            Err(vec![
+                "line 7: Only arrays stored in variables can be iterated over".to_string()
+            ])
+        );
+    }
+}
@@ -0,0 +1,772 @@
+use std::collections::HashMap;
+
+use diagnostics::ErrorCode;
+use parser::nodes::{
+    ASTNodes, Block, Conditional, Datatype, DefId, Expression, Function, FunctionCall, Literal,
+    Operator, Types,
+};
+
+use crate::{errors::PassError, r#impl::typeck::operator_symbol};
+
+/// A value known at compile time, as the backend would compute it. Booleans
+/// are 1 bit integers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Const {
+    /// The lowest `width` bits of `bits` hold the value
+    Int {
+        bits: u128,
+        width: u32,
+        signed: bool,
+    },
+    Float {
+        value: f64,
+        width: u32,
+    },
+}
+
+impl Const {
+    fn int(value: i128, width: u32, signed: bool) -> Self {
+        Const::Int {
+            bits: value as u128 & mask(width),
+            width,
+            signed,
+        }
+    }
+
+    /// The value of an integer, as a signed integer if `signed`
+    fn value(bits: u128, width: u32, signed: bool) -> i128 {
+        if signed && width < 128 && bits >> (width - 1) & 1 == 1 {
+            bits as i128 - (1i128 << width)
+        } else {
+            bits as i128
+        }
+    }
+
+    /// Variables are loaded with the signedness of their type
+    fn with_type(self, dt: &Datatype) -> Self {
+        match (self, int_type(dt)) {
+            (Const::Int { bits, width, .. }, Some((_, signed))) => Const::Int {
+                bits,
+                width,
+                signed,
+            },
+            _ => self,
+        }
+    }
+
+    /// The type the backend gives a literal, from the type of the expression it is in
+    fn literal(lit: &Literal, context: &Datatype) -> Option<Self> {
+        match lit.r#type {
+            Types::BOOL => Some(Const::int(lit.value.parse().ok()?, 1, false)),
+            Types::NUMBER if lit.value.contains('.') => Some(Const::Float {
+                value: lit.value.parse().ok()?,
+                width: float_width(context)?,
+            }),
+            // Integer literals are i64 unless the expression is an integer
+            Types::NUMBER => {
+                let (width, signed) = match int_type(context) {
+                    Some((width, signed)) if width > 1 => (width, signed),
+                    _ => (64, false),
+                };
+                Some(Const::int(
+                    lit.value.parse::<u64>().ok()? as i128,
+                    width,
+                    signed,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Turns the value into a literal, if the backend builds the literal as the same value of `dt`
+    fn to_literal(self, dt: &Datatype) -> Option<Literal> {
+        match (self, int_type(dt), float_width(dt)) {
+            (Const::Int { bits, width: 1, .. }, Some((1, _)), _) => Some(Literal {
+                value: bits.to_string(),
+                r#type: Types::BOOL,
+            }),
+            (Const::Int { bits, width, .. }, Some((dt_width, _)), _) if width == dt_width => {
+                Some(Literal {
+                    value: bits.to_string(),
+                    r#type: Types::NUMBER,
+                })
+            }
+            (Const::Float { value, width }, _, Some(dt_width)) if width == dt_width => {
+                let value = format!("{:?}", value);
+                (value.contains('.') && !value.contains(['e', 'E']) && !value.contains("inf"))
+                    .then_some(Literal {
+                        value,
+                        r#type: Types::NUMBER,
+                    })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn mask(width: u32) -> u128 {
+    if width >= 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+fn int_type(dt: &Datatype) -> Option<(u32, bool)> {
+    match dt {
+        Datatype::BOOL => Some((1, false)),
+        dt if dt.is_integer() => Some((dt.bit_width()?, dt.is_signed())),
+        _ => None,
+    }
+}
+
+fn float_width(dt: &Datatype) -> Option<u32> {
+    dt.is_float().then(|| dt.bit_width()).flatten()
+}
+
+fn type_name(width: u32, signed: bool) -> String {
+    format!("{}{}", if signed { 'i' } else { 'u' }, width)
+}
+
+/// Folds expressions whose values are known at compile time, replaces immutable
+/// variables holding them with their values, and removes branches that never run.
+/// Expressions are only replaced where the backend knows their type, like the
+/// values of `let` statements, so that literals keep the type they had.
+/// Relies on the ids set by the resolution pass.
+pub(crate) struct ConstFolder {
+    consts: HashMap<DefId, Const>,
+    /// The types of variables, for the values assigned to them
+    types: HashMap<DefId, Datatype>,
+    params: HashMap<String, Vec<Datatype>>,
+    return_type: Option<Datatype>,
+    /// The line and column of the statement being folded, used for errors
+    line: usize,
+    column: usize,
+    errors: Vec<PassError>,
+}
+
+impl ConstFolder {
+    pub(crate) fn fold(nodes: &mut [ASTNodes]) -> Vec<PassError> {
+        let mut folder = Self {
+            consts: HashMap::new(),
+            types: HashMap::new(),
+            params: HashMap::new(),
+            return_type: None,
+            line: 0,
+            column: 0,
+            errors: vec![],
+        };
+        for node in nodes.iter() {
+            match node {
+                ASTNodes::Function(func) => {
+                    let params = func.args.iter().map(|p| p.datatype.clone()).collect();
+                    folder.params.insert(func.name.clone(), params);
+                }
+                ASTNodes::Extern(ext) => {
+                    let params = ext.args.iter().map(|p| p.datatype.clone()).collect();
+                    folder.params.insert(ext.name.clone(), params);
+                }
+                _ => {}
+            }
+        }
+        for node in nodes.iter_mut() {
+            if let ASTNodes::Function(func) = node {
+                folder.fold_function(func);
+            }
+        }
+        folder.errors
+    }
+
+    fn error(&mut self, code: ErrorCode, msg: &str) {
+        self.errors.push(
+            PassError::new(msg, self.line)
+                .with_code(code)
+                .with_column(self.column),
+        );
+    }
+
+    fn fold_function(&mut self, func: &mut Function) {
+        for param in func.args.iter() {
+            if let Some(id) = param.id {
+                self.types.insert(id, param.datatype.clone());
+            }
+        }
+        self.return_type = func.return_type.clone();
+        self.line = func.line;
+        self.column = func.column;
+        self.fold_block(&mut func.body);
+    }
+
+    /// Statements after a branch that always returns or breaks are dropped along
+    /// with it, the backend can't build code after them
+    fn fold_block(&mut self, block: &mut Block) {
+        let mut body = vec![];
+        for mut node in std::mem::take(&mut block.body) {
+            match self.fold_stmt(&mut node) {
+                None => body.push(node),
+                Some(nodes) => {
+                    let ends = nodes
+                        .iter()
+                        .any(|node| matches!(node, ASTNodes::Return(_) | ASTNodes::Break));
+                    body.extend(nodes);
+                    if ends {
+                        break;
+                    }
+                }
+            }
+        }
+        block.body = body;
+    }
+
+    /// Folds a statement, returning the statements to replace it with if it is a
+    /// branch that is known to run, or never to run
+    fn fold_stmt(&mut self, node: &mut ASTNodes) -> Option<Vec<ASTNodes>> {
+        match node {
+            ASTNodes::LetStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                let value = self.fold_root(&mut stmt.value, &stmt.datatype);
+                if let Some(id) = stmt.id {
+                    self.types.insert(id, stmt.datatype.clone());
+                    if let (Some(value), false) = (value, stmt.mutable) {
+                        self.consts.insert(id, value.with_type(&stmt.datatype));
+                    }
+                }
+            }
+            ASTNodes::AssignStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                let dt = match stmt.name.as_ref() {
+                    ASTNodes::Variable(var) => var.id.and_then(|id| self.types.get(&id)).cloned(),
+                    _ => None,
+                };
+                if let Some(dt) = dt {
+                    self.fold_root(&mut stmt.value, &dt);
+                }
+            }
+            ASTNodes::Return(ret) => {
+                self.line = ret.line;
+                self.column = ret.column;
+                if let (Some(value), Some(dt)) = (&mut ret.value, self.return_type.clone()) {
+                    self.fold_root(value, &dt);
+                }
+            }
+            ASTNodes::Conditional(cond) => return self.fold_conditional(cond),
+            ASTNodes::Loop(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                if let Some(condition) = &mut stmt.condition {
+                    match self.fold_condition(condition) {
+                        Some(false) => return Some(vec![]),
+                        Some(true) => stmt.condition = None,
+                        None => {}
+                    }
+                }
+                self.fold_block(&mut stmt.body);
+            }
+            ASTNodes::ForLoop(stmt) => self.fold_block(&mut stmt.body),
+            ASTNodes::FunctionCall(call) => {
+                self.line = call.line;
+                self.column = call.column;
+                self.fold_args(call);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn fold_conditional(&mut self, cond: &mut Conditional) -> Option<Vec<ASTNodes>> {
+        let Conditional::If {
+            condition,
+            body,
+            else_body,
+            line,
+            column,
+        } = cond
+        else {
+            return None;
+        };
+        self.line = *line;
+        self.column = *column;
+        match self.fold_condition(condition) {
+            Some(true) => {
+                self.fold_block(body);
+                Some(std::mem::take(&mut body.body))
+            }
+            Some(false) => match else_body.take().map(|else_body| *else_body) {
+                None => Some(vec![]),
+                Some(Conditional::Else { mut body }) => {
+                    self.fold_block(&mut body);
+                    Some(body.body)
+                }
+                Some(mut else_if) => {
+                    let folded = self.fold_conditional(&mut else_if);
+                    folded.or(Some(vec![ASTNodes::Conditional(else_if)]))
+                }
+            },
+            None => {
+                self.fold_block(body);
+                self.fold_else(else_body);
+                None
+            }
+        }
+    }
+
+    /// Folds the `else if` and `else` branches of an `if` whose condition isn't known
+    fn fold_else(&mut self, else_body: &mut Option<Box<Conditional>>) {
+        while let Some(Conditional::If {
+            condition,
+            line,
+            column,
+            ..
+        }) = else_body.as_deref_mut()
+        {
+            self.line = *line;
+            self.column = *column;
+            match self.fold_condition(condition) {
+                Some(true) => {
+                    let Some(Conditional::If { body, .. }) = else_body.take().map(|c| *c) else {
+                        unreachable!()
+                    };
+                    *else_body = Some(Box::new(Conditional::Else { body }));
+                }
+                Some(false) => {
+                    let Some(Conditional::If {
+                        else_body: next, ..
+                    }) = else_body.take().map(|c| *c)
+                    else {
+                        unreachable!()
+                    };
+                    *else_body = next;
+                }
+                None => break,
+            }
+        }
+        match else_body.as_deref_mut() {
+            Some(Conditional::If {
+                body, else_body, ..
+            }) => {
+                self.fold_block(body);
+                self.fold_else(else_body);
+            }
+            Some(Conditional::Else { body }) => self.fold_block(body),
+            None => {}
+        }
+    }
+
+    fn fold_condition(&mut self, condition: &mut Expression) -> Option<bool> {
+        match self.fold_root(condition, &Datatype::BOOL)? {
+            Const::Int { bits, width: 1, .. } => Some(bits == 1),
+            _ => None,
+        }
+    }
+
+    /// Folds an expression of type `dt`, replacing it with a literal if its value is
+    /// known. Returns the value in that case.
+    fn fold_root(&mut self, expr: &mut Expression, dt: &Datatype) -> Option<Const> {
+        if let (Expression::Array(items), Datatype::NARRAY(item_dt, _)) = (&mut *expr, dt) {
+            for item in items.iter_mut() {
+                self.fold_root(item, item_dt);
+            }
+            return None;
+        }
+        let value = self.eval_expr(expr, dt)?;
+        let lit = value.to_literal(dt)?;
+        if !matches!(expr, Expression::Simple { left, right: None, .. } if matches!(**left, ASTNodes::Literal(_)))
+        {
+            *expr = Expression::Simple {
+                left: Box::new(ASTNodes::Literal(lit)),
+                right: None,
+                operator: None,
+            };
+        }
+        Some(value)
+    }
+
+    fn fold_args(&mut self, call: &mut FunctionCall) {
+        let Some(params) = self.params.get(&call.name).cloned() else {
+            return;
+        };
+        for (arg, dt) in call.args.iter_mut().zip(params.iter()) {
+            self.fold_root(arg, dt);
+        }
+    }
+
+    /// `context` is the type the backend builds the expression with
+    fn eval_expr(&mut self, expr: &mut Expression, context: &Datatype) -> Option<Const> {
+        let Expression::Simple {
+            left,
+            right,
+            operator,
+        } = expr
+        else {
+            return None;
+        };
+        let left = self.eval_node(left, context);
+        let Some(right) = right else {
+            return left;
+        };
+        if let ASTNodes::Token(Types::DATATYPE(dt)) = right.as_ref() {
+            return cast(left?, dt);
+        }
+        let right = self.eval_node(right, context);
+        self.binary(left?, right?, operator.as_ref()?)
+    }
+
+    fn eval_node(&mut self, node: &mut ASTNodes, context: &Datatype) -> Option<Const> {
+        match node {
+            ASTNodes::Literal(lit) => Const::literal(lit, context),
+            ASTNodes::Variable(var) => var.id.and_then(|id| self.consts.get(&id)).copied(),
+            ASTNodes::Expression(expr) => self.eval_expr(expr, context),
+            ASTNodes::FunctionCall(call) => {
+                self.fold_args(call);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// The backend extends the narrower operand to the type of the other one, and
+    /// the operation is signed if either of them is
+    fn binary(&mut self, left: Const, right: Const, op: &Operator) -> Option<Const> {
+        match (left, right) {
+            (
+                Const::Int {
+                    bits: a,
+                    width: a_width,
+                    signed: a_signed,
+                },
+                Const::Int {
+                    bits: b,
+                    width: b_width,
+                    signed: b_signed,
+                },
+            ) => {
+                let width = a_width.max(b_width);
+                let signed = a_signed || b_signed;
+                let a = reinterpret(Const::value(a, a_width, a_signed), width, signed);
+                let b = reinterpret(Const::value(b, b_width, b_signed), width, signed);
+                self.int_binary(a, b, width, signed, op)
+            }
+            (
+                Const::Float {
+                    value: a,
+                    width: a_width,
+                },
+                Const::Float {
+                    value: b,
+                    width: b_width,
+                },
+            ) => float_binary(a, b, a_width.max(b_width), op),
+            _ => None,
+        }
+    }
+
+    fn int_binary(
+        &mut self,
+        a: i128,
+        b: i128,
+        width: u32,
+        signed: bool,
+        op: &Operator,
+    ) -> Option<Const> {
+        let symbol = operator_symbol(op);
+        let compare = |result: bool| Some(Const::int(result as i128, 1, false));
+        let result = match op {
+            Operator::PLUS => Some(a + b),
+            Operator::MINUS => Some(a - b),
+            Operator::MULTIPLY => a.checked_mul(b),
+            Operator::DIVIDE | Operator::MODULO if b == 0 => {
+                self.error(
+                    ErrorCode::DivisionByZero,
+                    &format!("Division by zero in `{} {} {}`", a, symbol, b),
+                );
+                return None;
+            }
+            Operator::DIVIDE => Some(a / b),
+            Operator::MODULO => Some(a % b),
+            Operator::EQUAL => return compare(a == b),
+            Operator::NOT_EQUAL => return compare(a != b),
+            Operator::GREATER => return compare(a > b),
+            Operator::GREATER_EQUAL => return compare(a >= b),
+            Operator::LESSER => return compare(a < b),
+            Operator::LESSER_EQUAL => return compare(a <= b),
+            Operator::BITWISE_AND => return Some(Const::int(a & b, width, signed)),
+            Operator::BITWISE_OR => return Some(Const::int(a | b, width, signed)),
+            Operator::BITWISE_XOR => return Some(Const::int(a ^ b, width, signed)),
+            Operator::LSHIFT | Operator::RSHIFT if !(0..width as i128).contains(&b) => {
+                self.error(
+                    ErrorCode::ConstantOverflow,
+                    &format!(
+                        "Shifting `{}` by {} bits overflows `{}`",
+                        a,
+                        b,
+                        type_name(width, signed)
+                    ),
+                );
+                return None;
+            }
+            Operator::LSHIFT => {
+                return Some(Const::Int {
+                    bits: (a as u128) << b & mask(width),
+                    width,
+                    signed,
+                });
+            }
+            Operator::RSHIFT => return Some(Const::int(a >> b, width, signed)),
+            _ => return None,
+        };
+        match result {
+            Some(result) if reinterpret(result, width, signed) == result => {
+                Some(Const::int(result, width, signed))
+            }
+            _ => {
+                self.error(
+                    ErrorCode::ConstantOverflow,
+                    &format!(
+                        "Integer overflow in `{} {} {}`, the result doesn't fit in `{}`",
+                        a,
+                        symbol,
+                        b,
+                        type_name(width, signed)
+                    ),
+                );
+                None
+            }
+        }
+    }
+}
+
+/// The value of the lowest `width` bits of `value`
+fn reinterpret(value: i128, width: u32, signed: bool) -> i128 {
+    Const::value(value as u128 & mask(width), width, signed)
+}
+
+fn float_binary(a: f64, b: f64, width: u32, op: &Operator) -> Option<Const> {
+    let compare = |result: bool| Some(Const::int(result as i128, 1, false));
+    let value = match op {
+        Operator::PLUS => a + b,
+        Operator::MINUS => a - b,
+        Operator::MULTIPLY => a * b,
+        Operator::DIVIDE => a / b,
+        Operator::MODULO => a % b,
+        Operator::EQUAL => return compare(a == b),
+        Operator::NOT_EQUAL => return compare(a != b),
+        Operator::GREATER => return compare(a > b),
+        Operator::GREATER_EQUAL => return compare(a >= b),
+        Operator::LESSER => return compare(a < b),
+        Operator::LESSER_EQUAL => return compare(a <= b),
+        _ => return None,
+    };
+    // f32 operations round to f32
+    let value = if width == 32 {
+        value as f32 as f64
+    } else {
+        value
+    };
+    value.is_finite().then_some(Const::Float { value, width })
+}
+
+/// Casts like the backend does, extending integers according to their own signedness
+fn cast(value: Const, dt: &Datatype) -> Option<Const> {
+    match (value, int_type(dt), float_width(dt)) {
+        (
+            Const::Int {
+                bits,
+                width,
+                signed,
+            },
+            Some((to_width, to_signed)),
+            _,
+        ) => Some(Const::int(
+            Const::value(bits, width, signed),
+            to_width,
+            to_signed,
+        )),
+        (
+            Const::Int {
+                bits,
+                width,
+                signed,
+            },
+            _,
+            Some(to_width),
+        ) => {
+            let value = Const::value(bits, width, signed) as f64;
+            let value = if to_width == 32 {
+                value as f32 as f64
+            } else {
+                value
+            };
+            Some(Const::Float {
+                value,
+                width: to_width,
+            })
+        }
+        // Values that don't fit are poison in LLVM, so they are left to the backend
+        (Const::Float { value, .. }, Some((to_width, to_signed)), _) if to_width > 1 => {
+            let value = value.trunc();
+            let int = value as i128;
+            (int as f64 == value && reinterpret(int, to_width, to_signed) == int)
+                .then(|| Const::int(int, to_width, to_signed))
+        }
+        (Const::Float { value, .. }, _, Some(to_width)) => Some(Const::Float {
+            value: if to_width == 32 {
+                value as f32 as f64
+            } else {
+                value
+            },
+            width: to_width,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{ASTNodes, Conditional, Expression},
+    };
+
+    use super::ConstFolder;
+    use crate::r#impl::resolve::Resolver;
+
+    fn fold(code: &str) -> (Vec<ASTNodes>, Vec<String>) {
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        assert!(Resolver::resolve(&mut ast).is_empty());
+        let errors = ConstFolder::fold(&mut ast)
+            .iter()
+            .map(|err| err.to_string())
+            .collect();
+        (ast, errors)
+    }
+
+    fn body(ast: &[ASTNodes]) -> &[ASTNodes] {
+        match ast.last() {
+            Some(ASTNodes::Function(func)) => &func.body.body,
+            _ => panic!("Expected a function"),
+        }
+    }
+
+    /// The value of a literal expression
+    fn literal(expr: &Expression) -> Option<&str> {
+        match expr {
+            Expression::Simple {
+                left, right: None, ..
+            } => match left.as_ref() {
+                ASTNodes::Literal(lit) => Some(&lit.value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_fold_expressions() {
+        let code = "
+func main(x u32) u32 {
+    let u32 a = 2 + 3 * 4
+    let u32 b = a * 2
+    let bool c = a > 10
+    let u64 d = (a -> u64) << 33
+    let f64 e = 1.5 * 2.0
+    let i32 f = 0 -> i32 - 5
+    let u32 g = x + a
+    let i8 h = 0 - 5
+    return b - 1
+}";
+        let (ast, errors) = fold(code);
+        assert!(errors.is_empty());
+        let values = body(&ast)
+            .iter()
+            .map(|node| match node {
+                ASTNodes::LetStmt(stmt) => literal(&stmt.value),
+                ASTNodes::Return(ret) => ret.value.as_ref().and_then(literal),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Some("14"),
+                Some("28"),
+                Some("1"),
+                Some("120259084288"),
+                Some("3.0"),
+                Some("4294967291"),
+                None,
+                Some("251"),
+                Some("27"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dead_branches() {
+        let code = "
+func main(x u32) u32 {
+    loop false {
+        other()
+    }
+    if false {
+        return 1
+    } else if x > 2 {
+        return 2
+    } else if true {
+        return 3
+    } else {
+        return 4
+    }
+}
+
+func other() u32 {
+    if 1 < 2 {
+        return 5
+    }
+    return 6
+}";
+        let (ast, errors) = fold(code);
+        assert!(errors.is_empty());
+
+        let ASTNodes::Function(main) = &ast[0] else {
+            panic!("Expected a function")
+        };
+        let [ASTNodes::Conditional(Conditional::If { else_body, .. })] = main.body.body.as_slice()
+        else {
+            panic!("Expected only the `else if`, got {:?}", main.body.body)
+        };
+        let Some(Conditional::Else { body: last }) = else_body.as_deref() else {
+            panic!("Expected the `else if true` to become an `else`")
+        };
+        assert!(matches!(last.body.as_slice(), [ASTNodes::Return(_)]));
+
+        let [ASTNodes::Return(ret)] = body(&ast) else {
+            panic!("Expected a single return, got {:?}", body(&ast))
+        };
+        assert_eq!(ret.value.as_ref().and_then(literal), Some("5"));
+    }
+
+    #[test]
+    fn test_constant_errors() {
+        let code = "
+func main() u8 {
+    let u8 a = 200
+    let u8 b = a + 100
+    let u32 c = 5 / 0
+    let i8 d = 0 -> i8 - 100 - 100
+    return 1 << 9
+}";
+        assert_eq!(
+            fold(code).1,
+            vec![
+                "line 4: Integer overflow in `200 + 100`, the result doesn't fit in `u8`",
+                "line 5: Division by zero in `5 / 0`",
+                "line 6: Integer overflow in `-100 - 100`, the result doesn't fit in `i8`",
+                "line 7: Shifting `1` by 9 bits overflows `u8`",
+            ]
+        );
+    }
+}
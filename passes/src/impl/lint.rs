@@ -0,0 +1,429 @@
+use std::collections::HashSet;
+
+use parser::nodes::{
+    ASTNodes, Block, Conditional, Datatype, DefId, Expression, Function, StructDef,
+};
+
+use crate::{errors::PassWarning, r#impl::returns::stmt_diverges, lints::Lint};
+
+/// A variable, parameter or loop variable
+struct Def<'a> {
+    id: DefId,
+    name: &'a str,
+    mutable: bool,
+    line: usize,
+    column: usize,
+}
+
+/// Looks for code that is never used. Relies on the ids set by the resolution pass.
+pub(crate) struct Linter<'a> {
+    defs: Vec<Def<'a>>,
+    /// Variables whose value is read
+    read: HashSet<DefId>,
+    /// Variables that are assigned to, or have a field or element assigned to
+    assigned: HashSet<DefId>,
+    called: HashSet<&'a str>,
+    modules: HashSet<&'a str>,
+    fields: HashSet<&'a str>,
+    /// The line and column of the last statement, for statements without them
+    line: usize,
+    column: usize,
+    warnings: Vec<PassWarning>,
+}
+
+impl<'a> Linter<'a> {
+    pub(crate) fn check(nodes: &'a [ASTNodes]) -> Vec<PassWarning> {
+        let mut linter = Self {
+            defs: vec![],
+            read: HashSet::new(),
+            assigned: HashSet::new(),
+            called: HashSet::new(),
+            modules: HashSet::new(),
+            fields: HashSet::new(),
+            line: 0,
+            column: 0,
+            warnings: vec![],
+        };
+        for node in nodes {
+            if let ASTNodes::Function(func) = node {
+                linter.visit_function(func);
+            }
+        }
+        linter.check_defs();
+        linter.check_items(nodes);
+        linter.warnings
+    }
+
+    fn warn(&mut self, lint: Lint, msg: &str, (line, column): (usize, usize)) {
+        self.warnings
+            .push(PassWarning::new(lint, msg, line).with_column(column));
+    }
+
+    fn check_defs(&mut self) {
+        for def in std::mem::take(&mut self.defs) {
+            if !self.read.contains(&def.id) && !def.name.starts_with('_') {
+                self.warn(
+                    Lint::UnusedVariables,
+                    &format!("Unused variable `{}`", def.name),
+                    (def.line, def.column),
+                );
+            }
+            if def.mutable && !self.assigned.contains(&def.id) {
+                self.warn(
+                    Lint::UnusedMut,
+                    &format!("Variable `{}` does not need to be mutable", def.name),
+                    (def.line, def.column),
+                );
+            }
+        }
+    }
+
+    fn check_items(&mut self, nodes: &'a [ASTNodes]) {
+        // C code may read the fields of structs passed to it
+        let extern_structs = nodes
+            .iter()
+            .filter_map(|node| match node {
+                ASTNodes::Extern(ext) => Some(ext),
+                _ => None,
+            })
+            .flat_map(|ext| {
+                ext.args
+                    .iter()
+                    .map(|param| &param.datatype)
+                    .chain(ext.return_type.iter())
+            })
+            .filter_map(|dt| match dt {
+                Datatype::CUSTOM(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        for node in nodes {
+            match node {
+                ASTNodes::Function(func)
+                    if func.name != "main"
+                        && !func.name.starts_with('_')
+                        && !self.called.contains(func.name.as_str()) =>
+                {
+                    self.warn(
+                        Lint::UnusedFunctions,
+                        &format!("Function `{}` is never called", func.name),
+                        (func.line, func.column),
+                    );
+                }
+                ASTNodes::ImportDef(imp) => {
+                    let module = imp.path.last().map_or("", String::as_str);
+                    if !self.modules.contains(module) {
+                        self.warn(
+                            Lint::UnusedImports,
+                            &format!("Unused import `{}`", imp.path.join("::")),
+                            (imp.line, imp.column),
+                        );
+                    }
+                }
+                ASTNodes::StructDef(def) if !extern_structs.contains(def.name.as_str()) => {
+                    self.check_fields(def);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_fields(&mut self, def: &StructDef) {
+        for (field, _) in def.fields.iter() {
+            if !self.fields.contains(field.as_str()) && !field.starts_with('_') {
+                self.warn(
+                    Lint::UnusedFields,
+                    &format!("Field `{}` of struct `{}` is never read", field, def.name),
+                    (def.line, def.column),
+                );
+            }
+        }
+    }
+
+    fn define(
+        &mut self,
+        id: Option<DefId>,
+        name: &'a str,
+        mutable: bool,
+        (line, column): (usize, usize),
+    ) {
+        if let Some(id) = id {
+            self.defs.push(Def {
+                id,
+                name,
+                mutable,
+                line,
+                column,
+            });
+        }
+    }
+
+    fn visit_function(&mut self, func: &'a Function) {
+        for param in func.args.iter() {
+            self.define(
+                param.id,
+                &param.name,
+                param.mutable,
+                (func.line, func.column),
+            );
+        }
+        (self.line, self.column) = (func.line, func.column);
+        self.visit_block(&func.body);
+    }
+
+    fn visit_block(&mut self, block: &'a Block) {
+        let mut diverged = false;
+        let mut reported = false;
+        for node in block.body.iter() {
+            if let Some(position) = stmt_position(node) {
+                (self.line, self.column) = position;
+            }
+            if diverged && !reported {
+                let position = (self.line, self.column);
+                self.warn(Lint::UnreachableCode, "Unreachable statement", position);
+                reported = true;
+            }
+            self.visit_stmt(node);
+            diverged |= matches!(node, ASTNodes::Break) || stmt_diverges(node);
+        }
+    }
+
+    fn visit_stmt(&mut self, node: &'a ASTNodes) {
+        match node {
+            ASTNodes::LetStmt(stmt) => {
+                self.visit_expr(&stmt.value);
+                self.define(stmt.id, &stmt.name, stmt.mutable, (stmt.line, stmt.column));
+            }
+            ASTNodes::AssignStmt(stmt) => {
+                self.visit_target(&stmt.name);
+                self.visit_expr(&stmt.value);
+            }
+            ASTNodes::Return(ret) => {
+                if let Some(value) = &ret.value {
+                    self.visit_expr(value);
+                }
+            }
+            ASTNodes::Conditional(cond) => self.visit_conditional(cond),
+            ASTNodes::Loop(stmt) => {
+                if let Some(condition) = &stmt.condition {
+                    self.visit_expr(condition);
+                }
+                self.visit_block(&stmt.body);
+            }
+            ASTNodes::ForLoop(stmt) => {
+                self.visit_expr(&stmt.iterator);
+                if let Some(step) = &stmt.step {
+                    self.visit_expr(step);
+                }
+                for var in [&stmt.value, &stmt.increment] {
+                    self.define(var.id, &var.name, false, (stmt.line, stmt.column));
+                }
+                self.visit_block(&stmt.body);
+            }
+            node => self.visit_node(node),
+        }
+    }
+
+    fn visit_conditional(&mut self, cond: &'a Conditional) {
+        match cond {
+            Conditional::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                self.visit_expr(condition);
+                self.visit_block(body);
+                if let Some(else_body) = else_body {
+                    self.visit_conditional(else_body);
+                }
+            }
+            Conditional::Else { body } => self.visit_block(body),
+        }
+    }
+
+    /// Assigning to a variable, or to a field or element of it, doesn't read it
+    fn visit_target(&mut self, target: &'a ASTNodes) {
+        match target {
+            ASTNodes::Variable(var) => {
+                if let Some(id) = var.id {
+                    self.assigned.insert(id);
+                }
+            }
+            ASTNodes::Attr(attr) => self.visit_target(&attr.parent),
+            ASTNodes::ArrayIndex(index) => {
+                self.visit_expr(&index.index);
+                self.visit_target(&index.array_var);
+            }
+            node => self.visit_node(node),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expression) {
+        match expr {
+            Expression::Simple { left, right, .. } => {
+                self.visit_node(left);
+                if let Some(right) = right {
+                    self.visit_node(right);
+                }
+            }
+            Expression::Array(items) => items.iter().for_each(|item| self.visit_expr(item)),
+            Expression::Struct(fields) => {
+                fields.iter().for_each(|(_, value)| self.visit_expr(value));
+            }
+            Expression::String(_) | Expression::None => {}
+        }
+    }
+
+    fn visit_node(&mut self, node: &'a ASTNodes) {
+        match node {
+            ASTNodes::Variable(var) => {
+                if let Some(id) = var.id {
+                    self.read.insert(id);
+                }
+            }
+            ASTNodes::Expression(expr) => self.visit_expr(expr),
+            ASTNodes::FunctionCall(call) => {
+                self.called.insert(&call.name);
+                call.args.iter().for_each(|arg| self.visit_expr(arg));
+            }
+            ASTNodes::ImportCall(call) => {
+                if let Some(module) = call.path.first() {
+                    self.modules.insert(module);
+                }
+                if let ASTNodes::FunctionCall(call) = call.ident.as_ref() {
+                    call.args.iter().for_each(|arg| self.visit_expr(arg));
+                }
+            }
+            ASTNodes::ArrayIndex(index) => {
+                self.visit_node(&index.array_var);
+                self.visit_expr(&index.index);
+            }
+            ASTNodes::Attr(attr) => {
+                self.fields.insert(&attr.name.name);
+                self.visit_node(&attr.parent);
+            }
+            ASTNodes::Method(method) => {
+                self.visit_node(&method.parent);
+                method.func.args.iter().for_each(|arg| self.visit_expr(arg));
+            }
+            ASTNodes::Try(r#try) => self.visit_node(&r#try.value),
+            _ => {}
+        }
+    }
+}
+
+fn stmt_position(node: &ASTNodes) -> Option<(usize, usize)> {
+    match node {
+        ASTNodes::LetStmt(stmt) => Some((stmt.line, stmt.column)),
+        ASTNodes::AssignStmt(stmt) => Some((stmt.line, stmt.column)),
+        ASTNodes::Return(ret) => Some((ret.line, ret.column)),
+        ASTNodes::Conditional(Conditional::If { line, column, .. }) => Some((*line, *column)),
+        ASTNodes::Loop(stmt) => Some((stmt.line, stmt.column)),
+        ASTNodes::ForLoop(stmt) => Some((stmt.line, stmt.column)),
+        ASTNodes::FunctionCall(call) => Some((call.line, call.column)),
+        ASTNodes::ImportCall(call) => stmt_position(&call.ident),
+        ASTNodes::Try(r#try) => stmt_position(&r#try.value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use super::Linter;
+    use crate::r#impl::resolve::Resolver;
+
+    fn lint(code: &str) -> Vec<String> {
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        assert!(Resolver::resolve(&mut ast).is_empty());
+        let mut warnings = Linter::check(&ast);
+        warnings.sort_by_key(|warning| warning.line);
+        warnings.iter().map(|warning| warning.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_warnings() {
+        let code = r#"
+import std::io
+
+struct Point { x i32, y i32 }
+
+func add(a i32!, b i32) i32 {
+    a = a + b
+    return a
+}
+
+func main() i32 {
+    let Point p = { x 1, y 2 }
+    let i32[] arr = [1, 2]
+    let i32! total = 0
+    loop range val, _i = arr {
+        total = add(total, val)
+    }
+    io::println("{}", total)
+    return p.x + p.y
+}"#;
+        assert_eq!(lint(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_unused_code() {
+        let code = "
+import std::io
+
+struct Point { x i32, y i32 }
+
+func unused(a u32) {
+}
+
+func main() i32 {
+    let Point p = { x 1, y 2 }
+    let i32! x = 1
+    let i32! y = 1
+    y = 2
+    return p.x + x
+}";
+        assert_eq!(
+            lint(code),
+            vec![
+                "line 2: warning: Unused import `std::io` [unused_imports]",
+                "line 4: warning: Field `y` of struct `Point` is never read [unused_fields]",
+                "line 6: warning: Unused variable `a` [unused_variables]",
+                "line 6: warning: Function `unused` is never called [unused_functions]",
+                "line 11: warning: Variable `x` does not need to be mutable [unused_mut]",
+                "line 12: warning: Unused variable `y` [unused_variables]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        let code = "
+func main() u32 {
+    loop {
+        break
+        let u32 a = 1
+    }
+    if true {
+        return 1
+    } else {
+        return 2
+    }
+    let u32 b = 3
+    return b
+}";
+        assert_eq!(
+            lint(code),
+            vec![
+                "line 5: warning: Unreachable statement [unreachable_code]",
+                "line 5: warning: Unused variable `a` [unused_variables]",
+                "line 12: warning: Unreachable statement [unreachable_code]",
+            ]
+        );
+    }
+}
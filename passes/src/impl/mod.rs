@@ -1,61 +1,250 @@
-use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use parser::nodes::{Datatype, Expression};
+use parser::nodes::ASTNodes;
 
-use crate::r#impl::nodes::PassTraversal;
+use crate::{
+    errors::{PassError, PassWarning},
+    r#impl::{
+        desugar::Desugarer, fold::ConstFolder, lint::Linter, mutability::MutabilityChecker,
+        resolve::Resolver, returns::check_returns, typeck::TypeChecker,
+    },
+    lints::{ItemLevels, LintLevels},
+    pass::{Pass, PassContext},
+};
 
-mod nodes;
+mod desugar;
+mod fold;
+mod lint;
+mod mutability;
+mod resolve;
+mod returns;
+mod typeck;
 
-struct PassData<'a> {
-    vars: std::cell::RefCell<Vec<HashMap<&'a str, Variables<'a>>>>,
+/// Turns a list of errors into the result of a pass
+fn to_result(errors: Vec<PassError>) -> Result<(), Vec<PassError>> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
 
-impl<'a> PassData<'a> {
-    pub fn get_vars(&self, name: &str) -> Option<Variables<'a>> {
-        let borrow = self.vars.borrow();
+/// Validates the lint attributes, and shares the levels of each item
+struct Attributes(LintLevels);
 
-        for map in borrow.iter() {
-            if let Some(var) = map.get(name) {
-                return Some(var.clone());
-            }
-        }
-        return None;
+impl Pass for Attributes {
+    fn name(&self) -> &str {
+        "attributes"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        ctx.insert(ItemLevels::new(&self.0, nodes)?);
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone)]
-struct Variables<'a> {
-    pub value: &'a Expression,
-    pub datatype: Datatype,
-    pub mutable: bool,
+struct Resolve;
+
+impl Pass for Resolve {
+    fn name(&self) -> &str {
+        "resolve"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        _ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        to_result(Resolver::resolve(nodes))
+    }
 }
 
-impl<'a> Variables<'a> {
-    pub fn new(let_stmt: &'a parser::nodes::LetStmt) -> Self {
-        Self {
-            value: &let_stmt.value,
-            datatype: let_stmt.datatype.clone(),
-            mutable: let_stmt.mutable,
-        }
+struct TypeCheck;
+
+impl Pass for TypeCheck {
+    fn name(&self) -> &str {
+        "typeck"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        let (errors, warnings) = TypeChecker::check(nodes);
+        warnings.into_iter().for_each(|warning| ctx.warn(warning));
+        to_result(errors)
+    }
+}
+
+/// Checks assignments to immutable variables, and functions that don't always return
+struct Mutability;
+
+impl Pass for Mutability {
+    fn name(&self) -> &str {
+        "mutability"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        _ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        let mut errors = MutabilityChecker::check(nodes);
+        errors.extend(check_returns(nodes));
+        to_result(errors)
+    }
+}
+
+struct Lints;
+
+impl Pass for Lints {
+    fn name(&self) -> &str {
+        "lints"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        Linter::check(nodes)
+            .into_iter()
+            .for_each(|warning| ctx.warn(warning));
+        Ok(())
+    }
+}
+
+struct Desugar;
+
+impl Pass for Desugar {
+    fn name(&self) -> &str {
+        "desugar"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        _ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        to_result(Desugarer::lower(nodes))
+    }
+}
+
+struct ConstFold;
+
+impl Pass for ConstFold {
+    fn name(&self) -> &str {
+        "const_fold"
+    }
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        _ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>> {
+        to_result(ConstFolder::fold(nodes))
     }
 }
 
 pub struct PassManager<'a> {
-    parser: &'a mut Vec<parser::nodes::ASTNodes>,
-    data: PassData<'a>,
+    parser: &'a mut Vec<ASTNodes>,
+    lints: LintLevels,
+    const_folding: bool,
+    /// Custom passes that run before the built-in lowerings
+    early_passes: Vec<Box<dyn Pass>>,
+    passes: Vec<Box<dyn Pass>>,
+    timings: Vec<(String, Duration)>,
 }
 
 impl<'a> PassManager<'a> {
-    pub fn new(parser: &'a mut Vec<parser::nodes::ASTNodes>) -> Self {
+    pub fn new(parser: &'a mut Vec<ASTNodes>) -> Self {
         Self {
             parser,
-            data: PassData {
-                vars: std::cell::RefCell::new(vec![]),
-            },
+            lints: LintLevels::default(),
+            const_folding: true,
+            early_passes: vec![],
+            passes: vec![],
+            timings: vec![],
+        }
+    }
+
+    pub fn with_lints(mut self, lints: LintLevels) -> Self {
+        self.lints = lints;
+        self
+    }
+
+    pub fn with_const_folding(mut self, enabled: bool) -> Self {
+        self.const_folding = enabled;
+        self
+    }
+
+    /// Adds a pass that runs after the built-in ones, in the order passes are added
+    pub fn with_pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Adds a pass that runs after the built-in checks but before desugaring, so it
+    /// sees the program as it was written. These run in the order they are added.
+    pub fn with_pass_before_desugar(mut self, pass: impl Pass + 'static) -> Self {
+        self.early_passes.push(Box::new(pass));
+        self
+    }
+
+    /// How long each pass of the last `invoke` took, in the order they ran
+    pub fn timings(&self) -> &[(String, Duration)] {
+        &self.timings
+    }
+
+    fn builtin_checks(&self) -> Vec<Box<dyn Pass>> {
+        vec![
+            Box::new(Attributes(self.lints.clone())),
+            Box::new(Resolve),
+            Box::new(TypeCheck),
+            Box::new(Mutability),
+            Box::new(Lints),
+        ]
+    }
+
+    fn builtin_lowerings(&self) -> Vec<Box<dyn Pass>> {
+        let mut passes: Vec<Box<dyn Pass>> = vec![Box::new(Desugar)];
+        if self.const_folding {
+            passes.push(Box::new(ConstFold));
         }
+        passes
     }
 
-    pub fn invoke(&'a mut self) {
-        self.parser.iter_mut().for_each(|x| x.visit(&self.data));
+    /// Checks the program, returning the warnings of the lints that aren't allowed.
+    /// Warnings of denied lints are returned as errors.
+    pub fn invoke(&mut self) -> Result<Vec<PassWarning>, Vec<PassError>> {
+        let mut ctx = PassContext::default();
+        let mut checks = self.builtin_checks();
+        let mut lowerings = self.builtin_lowerings();
+        self.timings.clear();
+
+        // Custom passes are kept, so invoking again runs them again
+        let passes = checks
+            .iter_mut()
+            .chain(self.early_passes.iter_mut())
+            .chain(lowerings.iter_mut())
+            .chain(self.passes.iter_mut());
+        for pass in passes {
+            let start = Instant::now();
+            let result = pass.run(self.parser, &mut ctx);
+            self.timings
+                .push((pass.name().to_string(), start.elapsed()));
+            result?;
+        }
+
+        let warnings = ctx.take_warnings();
+        match ctx.get::<ItemLevels>() {
+            Some(levels) => levels.apply(warnings),
+            None => Ok(warnings),
+        }
     }
 }
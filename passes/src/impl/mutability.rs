@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use diagnostics::ErrorCode;
+use parser::nodes::{ASTNodes, AssignStmt, Block, Conditional, DefId, Function};
+
+use crate::errors::PassError;
+
+/// Checks that assignments only change mutable variables, and fields and elements
+/// of them. Relies on the ids set by the resolution pass.
+pub(crate) struct MutabilityChecker {
+    bindings: HashMap<DefId, Binding>,
+    errors: Vec<PassError>,
+}
+
+/// A variable defined so far
+struct Binding {
+    mutable: bool,
+    line: usize,
+    /// Loop variables cannot be made mutable
+    in_loop: bool,
+}
+
+impl Binding {
+    fn new(mutable: bool, line: usize) -> Self {
+        Self {
+            mutable,
+            line,
+            in_loop: false,
+        }
+    }
+}
+
+impl MutabilityChecker {
+    pub(crate) fn check(nodes: &[ASTNodes]) -> Vec<PassError> {
+        let mut checker = Self {
+            bindings: HashMap::new(),
+            errors: vec![],
+        };
+        for node in nodes {
+            if let ASTNodes::Function(func) = node {
+                checker.check_function(func);
+            }
+        }
+        checker.errors
+    }
+
+    fn check_function(&mut self, func: &Function) {
+        for param in func.args.iter() {
+            if let Some(id) = param.id {
+                self.bindings
+                    .insert(id, Binding::new(param.mutable, func.line));
+            }
+        }
+        self.check_block(&func.body);
+    }
+
+    fn check_block(&mut self, block: &Block) {
+        for node in block.body.iter() {
+            match node {
+                ASTNodes::LetStmt(stmt) => {
+                    if let Some(id) = stmt.id {
+                        self.bindings
+                            .insert(id, Binding::new(stmt.mutable, stmt.line));
+                    }
+                }
+                ASTNodes::AssignStmt(stmt) => self.check_assign(stmt),
+                ASTNodes::Conditional(cond) => self.check_conditional(cond),
+                ASTNodes::Loop(stmt) => self.check_block(&stmt.body),
+                ASTNodes::ForLoop(stmt) => {
+                    for var in [&stmt.value, &stmt.increment] {
+                        if let Some(id) = var.id {
+                            let binding = Binding {
+                                in_loop: true,
+                                ..Binding::new(false, stmt.line)
+                            };
+                            self.bindings.insert(id, binding);
+                        }
+                    }
+                    self.check_block(&stmt.body);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_conditional(&mut self, cond: &Conditional) {
+        match cond {
+            Conditional::If {
+                body, else_body, ..
+            } => {
+                self.check_block(body);
+                if let Some(else_body) = else_body {
+                    self.check_conditional(else_body);
+                }
+            }
+            Conditional::Else { body } => self.check_block(body),
+        }
+    }
+
+    /// Follows fields and elements back to the variable they belong to
+    fn check_assign(&mut self, stmt: &AssignStmt) {
+        let target = stmt.name.as_ref();
+        let part = match target {
+            ASTNodes::Attr(_) => "a field of ",
+            ASTNodes::ArrayIndex(_) => "an element of ",
+            _ => "",
+        };
+        let mut node = target;
+        loop {
+            match node {
+                ASTNodes::Attr(attr) => node = &attr.parent,
+                ASTNodes::ArrayIndex(index) => node = &index.array_var,
+                _ => break,
+            }
+        }
+
+        let ASTNodes::Variable(var) = node else {
+            self.errors.push(
+                PassError::new("Invalid left-hand side of assignment", stmt.line)
+                    .with_code(ErrorCode::InvalidAssignment)
+                    .with_column(stmt.column),
+            );
+            return;
+        };
+        let Some(binding) = var.id.and_then(|id| self.bindings.get(&id)) else {
+            return;
+        };
+        if !binding.mutable {
+            let err = PassError::new(
+                &format!("Cannot assign to {}immutable variable `{}`", part, var.name),
+                stmt.line,
+            )
+            .with_code(ErrorCode::ImmutableAssignment)
+            .with_column(stmt.column)
+            .with_label(binding.line, &format!("`{}` is defined here", var.name));
+            self.errors.push(match binding.in_loop {
+                true => err,
+                false => err.with_help(&format!(
+                    "make `{}` mutable by adding `!` after its type",
+                    var.name
+                )),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use super::MutabilityChecker;
+    use crate::r#impl::resolve::Resolver;
+
+    fn check(code: &str) -> Vec<String> {
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        assert!(Resolver::resolve(&mut ast).is_empty());
+        MutabilityChecker::check(&ast)
+            .iter()
+            .map(|err| err.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_mutable_assignments() {
+        let code = "
+struct Point { x i32, y i32 }
+
+func add(a u32!, b u32) u32 {
+    a = a + b
+    return a
+}
+
+func main() {
+    let u32! x = 1
+    x = 2
+    let Point! p = { x 1, y 2 }
+    p.x = 3
+    let u32[]! arr = [1, 2]
+    arr[0] = 3
+}";
+        assert_eq!(check(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_immutable_assignments() {
+        let code = "
+struct Point { x i32, y i32 }
+
+func add(a u32, b u32) u32 {
+    a = a + b
+    return a
+}
+
+func main() {
+    let u32 x = 1
+    x = 2
+    let Point p = { x 1, y 2 }
+    p.x = 3
+    let u32[] arr = [1, 2]
+    arr[0] = 3
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 5: Cannot assign to immutable variable `a`",
+                "line 11: Cannot assign to immutable variable `x`",
+                "line 13: Cannot assign to a field of immutable variable `p`",
+                "line 15: Cannot assign to an element of immutable variable `arr`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_immutable_definition() {
+        let code = "
+func main() {
+    let u32 x = 1
+    loop range val, i = [1, 2] {
+        val = 2
+        x = val
+    }
+}";
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        assert!(Resolver::resolve(&mut ast).is_empty());
+        let errors = MutabilityChecker::check(&ast);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| (err.line, err.labels.clone(), err.help.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (4, vec![(3, "`val` is defined here".to_string())], None),
+                (
+                    5,
+                    vec![(2, "`x` is defined here".to_string())],
+                    Some("make `x` mutable by adding `!` after its type".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadowed_mutability() {
+        let code = "
+func main() {
+    let u32! x = 1
+    if true {
+        let u32 x = 2
+    }
+    x = 3
+}";
+        assert_eq!(check(code), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,747 @@
+use std::collections::{HashMap, HashSet};
+
+use diagnostics::ErrorCode;
+use parser::{
+    format::{FormatPiece, parse_format_string},
+    nodes::{
+        ASTNodes, Block, Conditional, Datatype, DefId, Expression, ForLoop, Function, FunctionCall,
+        ImportCall, Param, Types, Variable,
+    },
+};
+
+use crate::{
+    errors::PassError,
+    stdlib::{full_path, is_std_module, std_function},
+};
+
+/// Calls that are handled by the compiler rather than defined in the source
+const BUILTIN_FUNCTIONS: [&str; 4] = ["assert", "unreachable", "ok", "err"];
+
+/// Links every variable to the `let`, parameter or loop variable that defines it,
+/// and checks that every function, struct and module that is used is defined, and
+/// that no struct contains itself.
+///
+/// Each variable definition gets a unique `DefId`, which is also set on the variables
+/// that refer to it, so the backend never has to track scopes. Functions and structs
+/// can only be defined at the top level, so their names already identify them.
+pub(crate) struct Resolver {
+    functions: HashSet<String>,
+    /// The fields of each struct
+    structs: HashMap<String, Vec<(String, Datatype)>>,
+    /// The last segment of each import, mapped to its full path
+    imports: HashMap<String, Vec<String>>,
+    scopes: Vec<HashMap<String, DefId>>,
+    next_id: DefId,
+    /// The line and column of the node being resolved, used for errors
+    line: usize,
+    column: usize,
+    errors: Vec<PassError>,
+}
+
+impl Resolver {
+    pub(crate) fn resolve(nodes: &mut [ASTNodes]) -> Vec<PassError> {
+        let mut resolver = Self {
+            functions: HashSet::new(),
+            structs: HashMap::new(),
+            imports: HashMap::new(),
+            scopes: vec![],
+            next_id: 0,
+            line: 0,
+            column: 0,
+            errors: vec![],
+        };
+
+        for node in nodes.iter() {
+            match node {
+                ASTNodes::Function(func) => {
+                    resolver.line = func.line;
+                    resolver.column = func.column;
+                    if !resolver.functions.insert(func.name.clone()) {
+                        resolver.error(
+                            ErrorCode::DuplicateDefinition,
+                            &format!("Function `{}` is defined more than once", func.name),
+                        );
+                    }
+                }
+                ASTNodes::Extern(ext) => {
+                    resolver.line = ext.line;
+                    resolver.column = ext.column;
+                    if !resolver.functions.insert(ext.name.clone()) {
+                        resolver.error(
+                            ErrorCode::DuplicateDefinition,
+                            &format!("Function `{}` is defined more than once", ext.name),
+                        );
+                    }
+                }
+                ASTNodes::StructDef(st) => {
+                    resolver.line = st.line;
+                    resolver.column = st.column;
+                    if resolver
+                        .structs
+                        .insert(st.name.clone(), st.fields.clone())
+                        .is_some()
+                    {
+                        resolver.error(
+                            ErrorCode::DuplicateDefinition,
+                            &format!("Struct `{}` is defined more than once", st.name),
+                        );
+                    }
+                }
+                ASTNodes::ImportDef(imp) => {
+                    resolver.line = imp.line;
+                    resolver.column = imp.column;
+                    if !is_std_module(&imp.path) {
+                        resolver.error(
+                            ErrorCode::UnknownModule,
+                            &format!("Cannot find module `{}`", imp.path.join("::")),
+                        );
+                    }
+                    if let Some(last) = imp.path.last() {
+                        resolver.imports.insert(last.clone(), imp.path.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for node in nodes.iter_mut() {
+            match node {
+                ASTNodes::Function(func) => resolver.resolve_function(func),
+                ASTNodes::Extern(ext) => {
+                    resolver.line = ext.line;
+                    resolver.column = ext.column;
+                    resolver.resolve_params(&ext.name, &ext.args);
+                    if let Some(dt) = &ext.return_type {
+                        resolver.resolve_type(dt);
+                    }
+                }
+                ASTNodes::StructDef(st) => {
+                    resolver.line = st.line;
+                    resolver.column = st.column;
+                    let mut fields = HashSet::new();
+                    for (name, dt) in st.fields.iter() {
+                        if !fields.insert(name) {
+                            resolver.error(
+                                ErrorCode::DuplicateDefinition,
+                                &format!(
+                                    "Field `{}` is defined more than once in struct `{}`",
+                                    name, st.name
+                                ),
+                            );
+                        }
+                        resolver.resolve_type(dt);
+                    }
+                    let recursive = st
+                        .fields
+                        .iter()
+                        .any(|(_, dt)| resolver.contains_struct(&st.name, dt, &mut HashSet::new()));
+                    if recursive {
+                        resolver.error(
+                            ErrorCode::RecursiveType,
+                            &format!(
+                                "Struct `{}` contains itself, so it would have an infinite size",
+                                st.name
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        resolver.errors
+    }
+
+    fn error(&mut self, code: ErrorCode, msg: &str) {
+        self.errors.push(
+            PassError::new(msg, self.line)
+                .with_code(code)
+                .with_column(self.column),
+        );
+    }
+
+    fn new_id(&mut self) -> DefId {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Adds a variable to the innermost scope. Variables of outer scopes can be
+    /// shadowed, but a scope can't define the same name twice.
+    fn define(&mut self, name: &str) -> DefId {
+        let id = self.new_id();
+        if self
+            .scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), id)
+            .is_some()
+        {
+            self.error(
+                ErrorCode::DuplicateDefinition,
+                &format!("Variable `{}` is already defined in this scope", name),
+            );
+        }
+        id
+    }
+
+    fn resolve_variable(&mut self, var: &mut Variable) {
+        var.id = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&var.name))
+            .copied();
+        if var.id.is_none() {
+            self.error(
+                ErrorCode::UnknownVariable,
+                &format!("Cannot find variable `{}` in this scope", var.name),
+            );
+        }
+    }
+
+    fn resolve_type(&mut self, dt: &Datatype) {
+        match dt {
+            Datatype::CUSTOM(name) if !self.structs.contains_key(name) => {
+                self.error(
+                    ErrorCode::UnknownType,
+                    &format!("Cannot find type `{}`", name),
+                );
+            }
+            Datatype::NARRAY(dt, _) | Datatype::SIMD(dt, _) | Datatype::SLICE(dt) => {
+                self.resolve_type(dt)
+            }
+            Datatype::RESULT(ok, err) => {
+                self.resolve_type(ok);
+                self.resolve_type(err);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether values of the type hold a value of the struct `name`, directly or
+    /// through the fields of other structs. `visited` stops at structs that contain
+    /// themselves without containing `name`.
+    fn contains_struct(&self, name: &str, dt: &Datatype, visited: &mut HashSet<String>) -> bool {
+        match dt {
+            Datatype::CUSTOM(st) if st == name => true,
+            Datatype::CUSTOM(st) => {
+                visited.insert(st.clone())
+                    && self.structs.get(st).is_some_and(|fields| {
+                        fields
+                            .iter()
+                            .any(|(_, dt)| self.contains_struct(name, dt, visited))
+                    })
+            }
+            Datatype::NARRAY(dt, _) | Datatype::SIMD(dt, _) => {
+                self.contains_struct(name, dt, visited)
+            }
+            Datatype::RESULT(ok, err) => {
+                self.contains_struct(name, ok, visited) || self.contains_struct(name, err, visited)
+            }
+            // The elements of a slice are stored outside of it
+            _ => false,
+        }
+    }
+
+    /// Checks the types of parameters, and that none of them share a name
+    fn resolve_params(&mut self, func_name: &str, params: &[Param]) {
+        let mut names = HashSet::new();
+        for param in params.iter() {
+            if !names.insert(&param.name) {
+                self.error(
+                    ErrorCode::DuplicateDefinition,
+                    &format!(
+                        "Parameter `{}` is defined more than once in function `{}`",
+                        param.name, func_name
+                    ),
+                );
+            }
+            self.resolve_type(&param.datatype);
+        }
+    }
+
+    fn resolve_function(&mut self, func: &mut Function) {
+        self.line = func.line;
+        self.column = func.column;
+        self.resolve_params(&func.name, &func.args);
+        if let Some(dt) = &func.return_type {
+            self.resolve_type(dt);
+        }
+
+        // Parameters live in a scope of their own, so the body can shadow them
+        let mut params = HashMap::new();
+        for param in func.args.iter_mut() {
+            let id = self.new_id();
+            param.id = Some(id);
+            params.insert(param.name.clone(), id);
+        }
+        self.scopes.push(params);
+        self.resolve_block(&mut func.body);
+        self.scopes.pop();
+    }
+
+    fn resolve_block(&mut self, block: &mut Block) {
+        self.scopes.push(HashMap::new());
+        for node in block.body.iter_mut() {
+            self.resolve_stmt(node);
+        }
+        self.scopes.pop();
+    }
+
+    fn resolve_stmt(&mut self, node: &mut ASTNodes) {
+        match node {
+            ASTNodes::LetStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                self.resolve_type(&stmt.datatype);
+                // The value is resolved first, as it can't refer to the new variable
+                self.resolve_expr(&mut stmt.value);
+                stmt.id = Some(self.define(&stmt.name));
+            }
+            ASTNodes::AssignStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                self.resolve_node(&mut stmt.name);
+                self.resolve_expr(&mut stmt.value);
+            }
+            ASTNodes::Return(ret) => {
+                self.line = ret.line;
+                self.column = ret.column;
+                if let Some(value) = &mut ret.value {
+                    self.resolve_expr(value);
+                }
+            }
+            ASTNodes::Conditional(cond) => self.resolve_conditional(cond),
+            ASTNodes::Loop(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                if let Some(condition) = &mut stmt.condition {
+                    self.resolve_expr(condition);
+                }
+                self.resolve_block(&mut stmt.body);
+            }
+            ASTNodes::ForLoop(stmt) => self.resolve_for_loop(stmt),
+            node => self.resolve_node(node),
+        }
+    }
+
+    fn resolve_conditional(&mut self, cond: &mut Conditional) {
+        match cond {
+            Conditional::If {
+                condition,
+                body,
+                else_body,
+                line,
+                column,
+            } => {
+                self.line = *line;
+                self.column = *column;
+                self.resolve_expr(condition);
+                self.resolve_block(body);
+                if let Some(else_body) = else_body {
+                    self.resolve_conditional(else_body);
+                }
+            }
+            Conditional::Else { body } => self.resolve_block(body),
+        }
+    }
+
+    fn resolve_for_loop(&mut self, stmt: &mut ForLoop) {
+        self.line = stmt.line;
+        self.column = stmt.column;
+        self.resolve_expr(&mut stmt.iterator);
+        if let Some(step) = &mut stmt.step {
+            self.resolve_expr(step);
+        }
+
+        self.scopes.push(HashMap::new());
+        stmt.value.id = Some(self.define(&stmt.value.name));
+        stmt.increment.id = Some(self.define(&stmt.increment.name));
+        self.resolve_block(&mut stmt.body);
+        self.scopes.pop();
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Simple { left, right, .. } => {
+                self.resolve_node(left);
+                if let Some(right) = right {
+                    self.resolve_node(right);
+                }
+            }
+            Expression::Array(items) => {
+                for item in items.iter_mut() {
+                    self.resolve_expr(item);
+                }
+            }
+            Expression::Struct(fields) => {
+                for (_, value) in fields.iter_mut() {
+                    self.resolve_expr(value);
+                }
+            }
+            Expression::String(_) | Expression::None => {}
+        }
+    }
+
+    fn resolve_node(&mut self, node: &mut ASTNodes) {
+        match node {
+            ASTNodes::Variable(var) => self.resolve_variable(var),
+            ASTNodes::Expression(expr) => self.resolve_expr(expr),
+            ASTNodes::FunctionCall(call) => {
+                self.line = call.line;
+                self.column = call.column;
+                if !self.functions.contains(&call.name)
+                    && !BUILTIN_FUNCTIONS.contains(&call.name.as_str())
+                {
+                    self.error(
+                        ErrorCode::UnknownFunction,
+                        &format!("Cannot find function `{}`", call.name),
+                    );
+                }
+                self.resolve_args(call);
+            }
+            ASTNodes::ImportCall(call) => self.resolve_import_call(call),
+            ASTNodes::ArrayIndex(index) => {
+                self.resolve_node(&mut index.array_var);
+                self.line = index.line;
+                self.column = index.column;
+                self.resolve_expr(&mut index.index);
+            }
+            // Only the parent is a variable, the name is one of its fields
+            ASTNodes::Attr(attr) => self.resolve_node(&mut attr.parent),
+            ASTNodes::Method(method) => {
+                self.resolve_node(&mut method.parent);
+                self.resolve_args(&mut method.func);
+            }
+            ASTNodes::Try(r#try) => self.resolve_node(&mut r#try.value),
+            // The target of a cast
+            ASTNodes::Token(Types::DATATYPE(dt)) => self.resolve_type(dt),
+            _ => {}
+        }
+    }
+
+    fn resolve_args(&mut self, call: &mut FunctionCall) {
+        for arg in call.args.iter_mut() {
+            self.resolve_expr(arg);
+        }
+    }
+
+    fn resolve_import_call(&mut self, call: &mut ImportCall) {
+        if let ASTNodes::FunctionCall(func_call) = call.ident.as_ref() {
+            self.line = func_call.line;
+            self.column = func_call.column;
+        }
+        let Some(path) = full_path(&self.imports, &call.path) else {
+            self.error(
+                ErrorCode::UnknownModule,
+                &format!("Cannot find module `{}`", call.path[0]),
+            );
+            return self.resolve_external(&mut call.ident);
+        };
+
+        let (name, module) = path.split_last().unwrap();
+        // Imports of unknown modules are reported where they are defined
+        let unknown_import = self
+            .imports
+            .get(&call.path[0])
+            .is_some_and(|import| !is_std_module(import));
+        if !is_std_module(module) {
+            if !unknown_import {
+                self.error(
+                    ErrorCode::UnknownModule,
+                    &format!("Cannot find module `{}`", module.join("::")),
+                );
+            }
+        } else if std_function(&path).is_none() {
+            self.error(
+                ErrorCode::UnknownFunction,
+                &format!(
+                    "Cannot find function `{}` in module `{}`",
+                    name,
+                    module.join("::")
+                ),
+            );
+        }
+
+        if let ASTNodes::FunctionCall(func_call) = call.ident.as_mut()
+            && (path == ["std", "io", "print"] || path == ["std", "io", "println"])
+        {
+            self.resolve_format_args(func_call);
+        }
+        self.resolve_external(&mut call.ident);
+    }
+
+    /// Resolves the arguments of a function from another module, and of the
+    /// attributes and methods accessed on its result
+    fn resolve_external(&mut self, node: &mut ASTNodes) {
+        match node {
+            ASTNodes::FunctionCall(call) => {
+                self.line = call.line;
+                self.column = call.column;
+                self.resolve_args(call);
+            }
+            ASTNodes::Attr(attr) => self.resolve_external(&mut attr.parent),
+            ASTNodes::Method(method) => {
+                self.resolve_external(&mut method.parent);
+                self.resolve_args(&mut method.func);
+            }
+            _ => {}
+        }
+    }
+
+    /// Turns the named args of a format string, like `{x}`, into positional ones
+    /// followed by a variable, so that they are resolved like any other argument.
+    /// Invalid format strings and wrong argument counts are left for the type checker to report.
+    fn resolve_format_args(&mut self, call: &mut FunctionCall) {
+        let Some(Expression::String(fmt)) = call.args.first() else {
+            return;
+        };
+        let Ok(pieces) = parse_format_string(fmt) else {
+            return;
+        };
+        let positional = pieces
+            .iter()
+            .filter(|p| matches!(p, FormatPiece::Arg { name: None, .. }))
+            .count();
+        if positional
+            == pieces
+                .iter()
+                .filter(|p| matches!(p, FormatPiece::Arg { .. }))
+                .count()
+            || positional != call.args.len() - 1
+        {
+            return;
+        }
+
+        let mut given = call.args.drain(1..).collect::<Vec<_>>().into_iter();
+        let mut fmt = String::new();
+        let mut args = vec![];
+        for piece in pieces {
+            match piece {
+                FormatPiece::Arg {
+                    name: Some(name),
+                    precision,
+                } => {
+                    fmt += &FormatPiece::Arg {
+                        name: None,
+                        precision,
+                    }
+                    .to_string();
+                    args.push(Expression::Simple {
+                        left: Box::new(ASTNodes::Variable(Variable { name, id: None })),
+                        right: None,
+                        operator: None,
+                    });
+                }
+                FormatPiece::Arg { name: None, .. } => {
+                    fmt += &piece.to_string();
+                    args.extend(given.next());
+                }
+                FormatPiece::Str(_) => fmt += &piece.to_string(),
+            }
+        }
+        call.args = vec![Expression::String(fmt)];
+        call.args.extend(args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{ASTNodes, Expression, LetStmt},
+    };
+
+    use super::Resolver;
+
+    fn resolve(code: &str) -> (Vec<ASTNodes>, Vec<String>) {
+        let mut lexer = Lexer::new(code);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let errors = Resolver::resolve(&mut ast)
+            .iter()
+            .map(|err| err.to_string())
+            .collect();
+        (ast, errors)
+    }
+
+    fn let_stmts(block: &[ASTNodes]) -> Vec<&LetStmt> {
+        block
+            .iter()
+            .filter_map(|node| match node {
+                ASTNodes::LetStmt(stmt) => Some(stmt),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn var_id(expr: &Expression) -> Option<usize> {
+        match expr {
+            Expression::Simple { left, .. } => match left.as_ref() {
+                ASTNodes::Variable(var) => var.id,
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_shadowing() {
+        let code = "
+func main() u32 {
+    let u32 a = 1
+    if a == 1 {
+        let u32 a = 2
+        let u32 b = a
+    }
+    let u32 c = a
+    return c
+}";
+        let (ast, errors) = resolve(code);
+        assert_eq!(errors, Vec::<String>::new());
+
+        let ASTNodes::Function(func) = &ast[0] else {
+            panic!()
+        };
+        let outer = let_stmts(&func.body.body);
+        let ASTNodes::Conditional(parser::nodes::Conditional::If { body, .. }) = &func.body.body[1]
+        else {
+            panic!()
+        };
+        let inner = let_stmts(&body.body);
+
+        assert_ne!(outer[0].id, inner[0].id);
+        assert_eq!(var_id(&inner[1].value), inner[0].id);
+        assert_eq!(var_id(&outer[1].value), outer[0].id);
+    }
+
+    #[test]
+    fn test_undefined_names() {
+        let code = "
+func main() u32 {
+    if true {
+        let u32 a = 1
+    }
+    let Point p = { x 1 }
+    let u32 b = add(a, 2)
+    io::println(\"{b}\")
+    std::io::println(\"{b} {c}\")
+    return b
+}";
+        let (_, errors) = resolve(code);
+        assert_eq!(
+            errors,
+            vec![
+                "line 6: Cannot find type `Point`",
+                "line 7: Cannot find function `add`",
+                "line 7: Cannot find variable `a` in this scope",
+                "line 8: Cannot find module `io`",
+                "line 9: Cannot find variable `c` in this scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_stdlib_names() {
+        let code = "
+import std::io
+import std::foo
+
+func main() u32 {
+    io::println(\"a\")
+    io::printline(\"a\")
+    foo::bar()
+    std::fs::size(\"a\")
+    std::net::connect()
+    std::exit(1)
+    return 0
+}";
+        let (_, errors) = resolve(code);
+        assert_eq!(
+            errors,
+            vec![
+                "line 3: Cannot find module `std::foo`",
+                "line 7: Cannot find function `printline` in module `std::io`",
+                "line 10: Cannot find module `std::net`",
+                "line 11: Cannot find function `exit` in module `std`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let code = "
+struct Point { x i32, x i32 }
+
+func add(a u32, a u32) u32 {
+    return a
+}
+
+func add() u32 {
+    let u32 b = 1
+    let u32 b = 2
+    return b
+}";
+        let (_, errors) = resolve(code);
+        assert_eq!(
+            errors,
+            vec![
+                "line 8: Function `add` is defined more than once",
+                "line 2: Field `x` is defined more than once in struct `Point`",
+                "line 4: Parameter `a` is defined more than once in function `add`",
+                "line 10: Variable `b` is already defined in this scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_structs() {
+        let code = "
+struct Node { value u32, next Node }
+
+struct Tree { left Branch[] }
+
+struct Branch { tree Result<Tree, u32> }
+
+struct Leaf { value u32 }
+
+struct Pair { a Leaf, b Leaf }
+";
+        let (_, errors) = resolve(code);
+        assert_eq!(
+            errors,
+            vec![
+                "line 2: Struct `Node` contains itself, so it would have an infinite size",
+                "line 4: Struct `Tree` contains itself, so it would have an infinite size",
+                "line 6: Struct `Branch` contains itself, so it would have an infinite size",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_format_args() {
+        let code = "
+import std::io
+
+func main() {
+    let f32 x = 1.5
+    io::println(\"{}, {x:.1} {{x}}\", 2)
+}";
+        let (ast, errors) = resolve(code);
+        assert_eq!(errors, Vec::<String>::new());
+
+        let ASTNodes::Function(func) = &ast[1] else {
+            panic!()
+        };
+        let ASTNodes::ImportCall(call) = &func.body.body[1] else {
+            panic!()
+        };
+        let ASTNodes::FunctionCall(call) = call.ident.as_ref() else {
+            panic!()
+        };
+        assert_eq!(
+            call.args[0],
+            Expression::String("{}, {:.1} {{x}}".to_string())
+        );
+        assert_eq!(var_id(&call.args[2]), let_stmts(&func.body.body)[0].id);
+    }
+}
@@ -0,0 +1,158 @@
+use diagnostics::ErrorCode;
+use parser::nodes::{ASTNodes, Block, Conditional, Function};
+
+use crate::errors::PassError;
+
+/// Checks that functions with a return type return a value on every path
+pub(crate) fn check_returns(nodes: &[ASTNodes]) -> Vec<PassError> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            ASTNodes::Function(func)
+                if func.return_type.is_some() && !block_diverges(&func.body) =>
+            {
+                Some(missing_return(func))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn missing_return(func: &Function) -> PassError {
+    PassError::new(
+        &format!(
+            "Function `{}` does not return a value on every path",
+            func.name
+        ),
+        func.line,
+    )
+    .with_code(ErrorCode::MissingReturn)
+    .with_column(func.column)
+}
+
+/// Whether the block never reaches its end, because every path through it
+/// returns, panics or loops forever
+fn block_diverges(block: &Block) -> bool {
+    block.body.iter().any(stmt_diverges)
+}
+
+/// Whether the statements after `node` can never run
+pub(crate) fn stmt_diverges(node: &ASTNodes) -> bool {
+    match node {
+        ASTNodes::Return(_) => true,
+        ASTNodes::Conditional(cond) => conditional_diverges(cond),
+        // Loops with a condition may not run at all
+        ASTNodes::Loop(stmt) => stmt.condition.is_none() && !block_breaks(&stmt.body),
+        ASTNodes::FunctionCall(call) => call.name == "unreachable",
+        ASTNodes::ImportCall(call) => call.path == ["std", "panic"],
+        _ => false,
+    }
+}
+
+/// An `if` diverges when it has an `else`, and all of its branches diverge
+fn conditional_diverges(cond: &Conditional) -> bool {
+    match cond {
+        Conditional::If {
+            body,
+            else_body: Some(else_body),
+            ..
+        } => block_diverges(body) && conditional_diverges(else_body),
+        Conditional::If {
+            else_body: None, ..
+        } => false,
+        Conditional::Else { body } => block_diverges(body),
+    }
+}
+
+/// Whether the block can break out of the loop it is in. Breaks in nested loops
+/// belong to those loops.
+fn block_breaks(block: &Block) -> bool {
+    block.body.iter().any(|node| match node {
+        ASTNodes::Break => true,
+        ASTNodes::Conditional(cond) => conditional_breaks(cond),
+        _ => false,
+    })
+}
+
+fn conditional_breaks(cond: &Conditional) -> bool {
+    match cond {
+        Conditional::If {
+            body, else_body, ..
+        } => block_breaks(body) || else_body.as_deref().is_some_and(conditional_breaks),
+        Conditional::Else { body } => block_breaks(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use super::check_returns;
+
+    fn check(code: &str) -> Vec<String> {
+        let mut lexer = Lexer::new(code);
+        let ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        check_returns(&ast)
+            .iter()
+            .map(|err| err.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_returning_functions() {
+        let code = "
+func sign(a i32) i32 {
+    if a > 0 {
+        return 1
+    } else if a < 0 {
+        return 2
+    } else {
+        return 0
+    }
+}
+
+func forever() u32 {
+    loop {
+        if true {
+            return 1
+        }
+    }
+}
+
+func nothing() {
+}
+
+func fail() u32 {
+    unreachable()
+}";
+        assert_eq!(check(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_missing_returns() {
+        let code = "
+func no_else(a i32) i32 {
+    if a > 0 {
+        return 1
+    }
+}
+
+func breaks() u32 {
+    loop {
+        break
+    }
+}
+
+func empty() u32 {
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 2: Function `no_else` does not return a value on every path",
+                "line 8: Function `breaks` does not return a value on every path",
+                "line 14: Function `empty` does not return a value on every path",
+            ]
+        );
+    }
+}
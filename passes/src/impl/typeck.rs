@@ -0,0 +1,1525 @@
+use std::{collections::HashMap, fmt::Display};
+
+use diagnostics::ErrorCode;
+use parser::{
+    format::{FormatPiece, parse_format_string},
+    nodes::{
+        ASTNodes, ArrayIndex, Attr, Block, Conditional, Datatype, Expression, ForLoop, Function,
+        FunctionCall, ImportCall, Literal, Method, Operator, Param, Types,
+    },
+};
+
+use crate::{
+    errors::{PassError, PassWarning},
+    lints::Lint,
+    stdlib::{StdFunction, full_path, std_function},
+};
+
+/// The type of an expression, as far as it can be worked out before codegen
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    Known(Datatype),
+    /// Integer literals take the type of the integer they are used with
+    Int,
+    Float,
+    /// String literals can be used as both strings and C strings
+    Str,
+    /// Values whose type isn't tracked, like the return values of the stdlib.
+    /// These are never reported as errors.
+    Unknown,
+}
+
+impl Type {
+    fn is_integer(&self) -> bool {
+        match self {
+            Type::Known(dt) => dt.is_integer(),
+            Type::Int | Type::Unknown => true,
+            _ => false,
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        match self {
+            Type::Known(dt) => dt.is_float(),
+            Type::Float | Type::Unknown => true,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Known(dt) => write!(f, "{}", dt),
+            Type::Int => write!(f, "integer"),
+            Type::Float => write!(f, "float"),
+            Type::Str => write!(f, "string"),
+            Type::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+struct FuncSig<'a> {
+    args: &'a [Param],
+    return_type: Option<&'a Datatype>,
+}
+
+/// Works out the type of every expression and checks that they are used where
+/// values of that type are expected
+pub(crate) struct TypeChecker<'a> {
+    functions: HashMap<&'a str, FuncSig<'a>>,
+    structs: HashMap<&'a str, &'a [(String, Datatype)]>,
+    /// The last segment of each import, mapped to its full path
+    imports: HashMap<String, Vec<String>>,
+    scopes: Vec<HashMap<&'a str, Type>>,
+    return_type: Option<&'a Datatype>,
+    /// The line and column of the node being checked, used for errors
+    line: usize,
+    column: usize,
+    errors: Vec<PassError>,
+    warnings: Vec<PassWarning>,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub(crate) fn check(nodes: &'a [ASTNodes]) -> (Vec<PassError>, Vec<PassWarning>) {
+        let mut checker = Self {
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            imports: HashMap::new(),
+            scopes: vec![],
+            return_type: None,
+            line: 0,
+            column: 0,
+            errors: vec![],
+            warnings: vec![],
+        };
+
+        for node in nodes {
+            match node {
+                ASTNodes::Function(func) => {
+                    checker.functions.insert(
+                        &func.name,
+                        FuncSig {
+                            args: &func.args,
+                            return_type: returned_type(func.return_type.as_ref()),
+                        },
+                    );
+                }
+                ASTNodes::Extern(ext) => {
+                    checker.functions.insert(
+                        &ext.name,
+                        FuncSig {
+                            args: &ext.args,
+                            return_type: returned_type(ext.return_type.as_ref()),
+                        },
+                    );
+                }
+                ASTNodes::StructDef(st) => {
+                    checker.structs.insert(&st.name, &st.fields);
+                }
+                ASTNodes::ImportDef(imp) => {
+                    if let Some(last) = imp.path.last() {
+                        checker.imports.insert(last.clone(), imp.path.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for node in nodes {
+            match node {
+                ASTNodes::Function(func) => {
+                    checker.line = func.line;
+                    checker.column = func.column;
+                    checker.check_signature(&func.args, func.return_type.as_ref());
+                    checker.check_function(func);
+                }
+                ASTNodes::Extern(ext) => {
+                    checker.line = ext.line;
+                    checker.column = ext.column;
+                    checker.check_signature(&ext.args, ext.return_type.as_ref());
+                }
+                ASTNodes::StructDef(st) => {
+                    checker.line = st.line;
+                    checker.column = st.column;
+                    for (_, dt) in st.fields.iter() {
+                        checker.check_sized(dt);
+                    }
+                }
+                _ => {}
+            }
+        }
+        (checker.errors, checker.warnings)
+    }
+
+    fn error(&mut self, code: ErrorCode, msg: &str) {
+        self.errors.push(
+            PassError::new(msg, self.line)
+                .with_code(code)
+                .with_column(self.column),
+        );
+    }
+
+    fn expect(&mut self, expected: &Datatype, found: &Type) {
+        if !accepts(expected, found) {
+            let err =
+                PassError::mismatched_types(expected, found, self.line).with_column(self.column);
+            // The value of a call to a function without a return type
+            self.errors.push(match found {
+                Type::Known(Datatype::NONE) => err.with_code(ErrorCode::NoValue),
+                _ => err,
+            });
+        } else if let Type::Known(dt) = found
+            && dt.is_integer()
+            && expected.is_integer()
+            && dt.is_signed() != expected.is_signed()
+        {
+            self.warnings.push(
+                PassWarning::new(
+                    Lint::LossyConversions,
+                    &format!(
+                        "Implicit conversion from `{}` to `{}` can change the value",
+                        dt, expected
+                    ),
+                    self.line,
+                )
+                .with_column(self.column),
+            );
+        }
+    }
+
+    /// Warns about integer literals that are too big for the type they are used as
+    fn check_literal(&mut self, lit: &Literal, expected: Option<&Datatype>) {
+        let is_int = lit.r#type == Types::NUMBER && !lit.value.contains('.');
+        if is_int && lit.value.parse::<u64>().is_err() {
+            self.error(
+                ErrorCode::ConstantOverflow,
+                &format!("Integer literal `{}` does not fit in 64 bits", lit.value),
+            );
+            return;
+        }
+        let (Some(dt), Some(bits)) = (expected, expected.and_then(|dt| dt.bit_width())) else {
+            return;
+        };
+        if !dt.is_integer() || lit.r#type != Types::NUMBER {
+            return;
+        }
+        let max = if dt.is_signed() {
+            (1u128 << (bits - 1)) - 1
+        } else {
+            (1u128 << bits) - 1
+        };
+        if lit.value.parse::<u128>().is_ok_and(|value| value > max) {
+            self.warnings.push(
+                PassWarning::new(
+                    Lint::LossyConversions,
+                    &format!("Literal `{}` does not fit in `{}`", lit.value, dt),
+                    self.line,
+                )
+                .with_column(self.column),
+            );
+        }
+    }
+
+    fn declare(&mut self, name: &'a str, ty: Type) {
+        self.scopes.last_mut().unwrap().insert(name, ty);
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    fn check_signature(&mut self, params: &[Param], return_type: Option<&Datatype>) {
+        for param in params {
+            self.check_sized(&param.datatype);
+        }
+        if let Some(dt) = return_type {
+            self.check_sized(dt);
+        }
+    }
+
+    /// Arrays are passed and stored by value, so their size has to be known. Only
+    /// `let` statements can leave it out, to take the size of an array literal.
+    fn check_sized(&mut self, dt: &Datatype) {
+        match dt {
+            Datatype::NARRAY(inner, 0) => self.error(
+                ErrorCode::UnsizedArray,
+                &format!(
+                    "Array type `{}` needs a size, such as `{}`",
+                    dt,
+                    Datatype::NARRAY(inner.clone(), 4)
+                ),
+            ),
+            Datatype::NARRAY(inner, _) | Datatype::SIMD(inner, _) | Datatype::SLICE(inner) => {
+                self.check_sized(inner)
+            }
+            Datatype::RESULT(ok, err) => {
+                self.check_sized(ok);
+                self.check_sized(err);
+            }
+            _ => {}
+        }
+    }
+
+    fn check_function(&mut self, func: &'a Function) {
+        self.return_type = returned_type(func.return_type.as_ref());
+        self.scopes.push(
+            func.args
+                .iter()
+                .map(|param| (param.name.as_str(), Type::Known(param.datatype.clone())))
+                .collect(),
+        );
+        self.check_block(&func.body);
+        self.scopes.pop();
+    }
+
+    fn check_block(&mut self, block: &'a Block) {
+        self.scopes.push(HashMap::new());
+        for node in block.body.iter() {
+            self.check_stmt(node);
+        }
+        self.scopes.pop();
+    }
+
+    fn check_stmt(&mut self, node: &'a ASTNodes) {
+        match node {
+            ASTNodes::LetStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                self.check_sized(&stmt.datatype);
+                let found = self.check_expr(&stmt.value, Some(&stmt.datatype));
+                self.expect(&stmt.datatype, &found);
+                self.declare(&stmt.name, Type::Known(stmt.datatype.clone()));
+            }
+            ASTNodes::AssignStmt(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                if let Type::Known(dt) = self.check_node(&stmt.name, None) {
+                    let mut found = self.check_expr(&stmt.value, Some(&dt));
+                    // `a += b` is checked like `a = a + b`
+                    if let Some(op) = &stmt.operator {
+                        found = self.binary_type(&Type::Known(dt.clone()), &found, op);
+                    }
+                    self.expect(&dt, &found);
+                } else {
+                    self.check_expr(&stmt.value, None);
+                }
+            }
+            ASTNodes::Return(ret) => {
+                self.line = ret.line;
+                self.column = ret.column;
+                match (&ret.value, self.return_type) {
+                    (Some(value), Some(dt)) => {
+                        let found = self.check_expr(value, Some(dt));
+                        self.expect(dt, &found);
+                    }
+                    (Some(value), None) => {
+                        self.check_expr(value, None);
+                        self.error(
+                            ErrorCode::InvalidReturn,
+                            "Cannot return a value from a function without a return type",
+                        );
+                    }
+                    (None, Some(dt)) => {
+                        self.error(
+                            ErrorCode::InvalidReturn,
+                            &format!("Expected a return value of type `{}`", dt),
+                        );
+                    }
+                    (None, None) => {}
+                }
+            }
+            ASTNodes::Conditional(cond) => self.check_conditional(cond),
+            ASTNodes::Loop(stmt) => {
+                self.line = stmt.line;
+                self.column = stmt.column;
+                if let Some(condition) = &stmt.condition {
+                    self.check_condition(condition);
+                }
+                self.check_block(&stmt.body);
+            }
+            ASTNodes::ForLoop(stmt) => self.check_for_loop(stmt),
+            ASTNodes::Break => {}
+            node => {
+                self.check_node(node, None);
+            }
+        }
+    }
+
+    fn check_conditional(&mut self, cond: &'a Conditional) {
+        match cond {
+            Conditional::If {
+                condition,
+                body,
+                else_body,
+                line,
+                column,
+            } => {
+                self.line = *line;
+                self.column = *column;
+                self.check_condition(condition);
+                self.check_block(body);
+                if let Some(else_body) = else_body {
+                    self.check_conditional(else_body);
+                }
+            }
+            Conditional::Else { body } => self.check_block(body),
+        }
+    }
+
+    fn check_condition(&mut self, condition: &'a Expression) {
+        let found = self.check_expr(condition, Some(&Datatype::BOOL));
+        if !accepts(&Datatype::BOOL, &found) {
+            self.error(
+                ErrorCode::MismatchedTypes,
+                &format!("Expected a `bool` condition, found `{}`", found),
+            );
+        }
+    }
+
+    fn check_for_loop(&mut self, stmt: &'a ForLoop) {
+        self.line = stmt.line;
+        self.column = stmt.column;
+        let value = match self.check_expr(&stmt.iterator, None) {
+            Type::Known(Datatype::NARRAY(dt, _)) => Type::Known(*dt),
+            Type::Unknown => Type::Unknown,
+            found => {
+                self.error(
+                    ErrorCode::NotIterable,
+                    &format!("Cannot iterate over `{}`", found),
+                );
+                Type::Unknown
+            }
+        };
+        if let Some(step) = &stmt.step {
+            let found = self.check_expr(step, None);
+            if !found.is_integer() {
+                self.error(
+                    ErrorCode::MismatchedTypes,
+                    &format!("Expected an integer step, found `{}`", found),
+                );
+            }
+        }
+
+        self.scopes.push(HashMap::from([
+            (stmt.value.name.as_str(), value),
+            (stmt.increment.name.as_str(), Type::Known(Datatype::U64)),
+        ]));
+        self.check_block(&stmt.body);
+        self.scopes.pop();
+    }
+
+    /// `expected` is the type the context wants, which literals without a type
+    /// of their own, like arrays and structs, are checked against
+    fn check_expr(&mut self, expr: &'a Expression, expected: Option<&Datatype>) -> Type {
+        match expr {
+            Expression::Simple {
+                left, right: None, ..
+            } => self.check_node(left, expected),
+            Expression::Simple {
+                left,
+                right: Some(right),
+                operator,
+            } => {
+                if let ASTNodes::Token(Types::DATATYPE(dt)) = right.as_ref() {
+                    return self.check_cast(left, dt);
+                }
+                match operator {
+                    Some(op) => self.check_binary(left, right, op, expected),
+                    None => Type::Unknown,
+                }
+            }
+            Expression::Array(items) => self.check_array(items, expected),
+            Expression::String(_) => Type::Str,
+            Expression::Struct(fields) => self.check_struct(fields, expected),
+            Expression::None => {
+                self.error(ErrorCode::NoValue, "Expected an expression");
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_node(&mut self, node: &'a ASTNodes, expected: Option<&Datatype>) -> Type {
+        match node {
+            ASTNodes::Literal(lit) => {
+                self.check_literal(lit, expected);
+                literal_type(lit)
+            }
+            ASTNodes::Variable(var) => self.lookup(&var.name),
+            ASTNodes::Expression(expr) => self.check_expr(expr, expected),
+            ASTNodes::FunctionCall(call) => self.check_call(call, expected),
+            ASTNodes::ImportCall(call) => self.check_import_call(call),
+            ASTNodes::ArrayIndex(index) => self.check_array_index(index),
+            ASTNodes::Attr(attr) => self.check_attr(attr),
+            ASTNodes::Method(method) => self.check_method(method),
+            ASTNodes::Try(r#try) => {
+                match self.check_node(&r#try.value, None) {
+                    Type::Known(Datatype::RESULT(ok, _)) => {
+                        if !matches!(self.return_type, Some(Datatype::RESULT(..))) {
+                            self.error(ErrorCode::InvalidTry, "The `?` operator can only be used in functions that return a Result");
+                        }
+                        Type::Known(*ok)
+                    }
+                    Type::Unknown => Type::Unknown,
+                    found => {
+                        self.error(
+                            ErrorCode::InvalidTry,
+                            &format!(
+                                "The `?` operator can only be applied to a Result, found `{}`",
+                                found
+                            ),
+                        );
+                        Type::Unknown
+                    }
+                }
+            }
+            _ => Type::Unknown,
+        }
+    }
+
+    fn check_cast(&mut self, value: &'a ASTNodes, to: &Datatype) -> Type {
+        let found = self.check_node(value, None);
+        let is_number = |dt: &Datatype| dt.is_integer() || dt.is_float() || *dt == Datatype::BOOL;
+        let castable = match (&found, to) {
+            (Type::Unknown, _) => true,
+            (Type::Int | Type::Float, to) => is_number(to),
+            (Type::Str, Datatype::STRING(_) | Datatype::CSTRING(_)) => true,
+            (
+                Type::Known(Datatype::STRING(_) | Datatype::CSTRING(_)),
+                Datatype::STRING(_) | Datatype::CSTRING(_),
+            ) => true,
+            (
+                Type::Known(Datatype::NARRAY(from, n) | Datatype::SIMD(from, n)),
+                Datatype::NARRAY(to, m) | Datatype::SIMD(to, m),
+            ) => same_type(from, to) && n == m,
+            (Type::Known(from), to) => is_number(from) && is_number(to),
+            _ => false,
+        };
+        if !castable {
+            self.error(
+                ErrorCode::InvalidCast,
+                &format!("Cannot cast `{}` to `{}`", found, to),
+            );
+        }
+        Type::Known(to.clone())
+    }
+
+    fn check_binary(
+        &mut self,
+        left: &'a ASTNodes,
+        right: &'a ASTNodes,
+        op: &Operator,
+        expected: Option<&Datatype>,
+    ) -> Type {
+        use Operator::*;
+        let comparison = matches!(
+            op,
+            EQUAL | NOT_EQUAL | LESSER | GREATER | LESSER_EQUAL | GREATER_EQUAL
+        );
+
+        // The operands of a comparison don't have to be bools
+        let expected = if comparison { None } else { expected };
+        let left = self.check_node(left, expected);
+        let right = self.check_node(right, expected);
+        self.binary_type(&left, &right, op)
+    }
+
+    /// The type of applying `op` to operands of the given types
+    fn binary_type(&mut self, left: &Type, right: &Type, op: &Operator) -> Type {
+        use Operator::*;
+        let comparison = matches!(
+            op,
+            EQUAL | NOT_EQUAL | LESSER | GREATER | LESSER_EQUAL | GREATER_EQUAL
+        );
+        let bitwise = matches!(op, BITWISE_AND | BITWISE_OR | BITWISE_XOR);
+        let shift = matches!(op, LSHIFT | RSHIFT);
+
+        let operand = unify(left, right);
+        let supported = match &operand {
+            None => false,
+            Some(Type::Unknown) => true,
+            Some(Type::Int) => true,
+            Some(Type::Float) => !bitwise && !shift,
+            Some(Type::Str) => comparison || *op == PLUS,
+            Some(Type::Known(dt)) if dt.is_integer() => true,
+            Some(Type::Known(dt)) if dt.is_float() => !bitwise && !shift,
+            Some(Type::Known(Datatype::BOOL)) => comparison || bitwise,
+            Some(Type::Known(Datatype::STRING(_))) => comparison || *op == PLUS,
+            Some(Type::Known(Datatype::SIMD(..))) => true,
+            Some(Type::Known(_)) => false,
+        };
+        if !supported {
+            self.error(
+                ErrorCode::InvalidOperands,
+                &format!(
+                    "Cannot apply `{}` to `{}` and `{}`",
+                    operator_symbol(op),
+                    left,
+                    right
+                ),
+            );
+            return Type::Unknown;
+        }
+
+        match operand {
+            Some(Type::Known(Datatype::SIMD(..))) if comparison => Type::Unknown,
+            _ if comparison => Type::Known(Datatype::BOOL),
+            operand => operand.unwrap(),
+        }
+    }
+
+    fn check_array(&mut self, items: &'a [Expression], expected: Option<&Datatype>) -> Type {
+        match expected {
+            Some(Datatype::NARRAY(inner, _) | Datatype::SLICE(inner)) => {
+                for item in items {
+                    let found = self.check_expr(item, Some(inner));
+                    self.expect(inner, &found);
+                }
+                Type::Known(Datatype::NARRAY(inner.clone(), items.len()))
+            }
+            Some(dt @ Datatype::SIMD(inner, _)) => {
+                for item in items {
+                    let found = self.check_expr(item, Some(inner));
+                    self.expect(inner, &found);
+                }
+                Type::Known(dt.clone())
+            }
+            Some(dt) => {
+                for item in items {
+                    self.check_expr(item, None);
+                }
+                self.error(
+                    ErrorCode::MismatchedTypes,
+                    &format!("Mismatched types; expected `{}`, found an array", dt),
+                );
+                Type::Unknown
+            }
+            None => {
+                let types = items
+                    .iter()
+                    .map(|item| self.check_expr(item, None))
+                    .collect::<Vec<_>>();
+                match types.first() {
+                    Some(Type::Known(dt)) => {
+                        Type::Known(Datatype::NARRAY(Box::new(dt.clone()), items.len()))
+                    }
+                    _ => Type::Unknown,
+                }
+            }
+        }
+    }
+
+    fn check_struct(
+        &mut self,
+        fields: &'a [(String, Expression)],
+        expected: Option<&Datatype>,
+    ) -> Type {
+        let Some(Datatype::CUSTOM(name)) = expected else {
+            for (_, value) in fields {
+                self.check_expr(value, None);
+            }
+            if let Some(dt) = expected {
+                self.error(
+                    ErrorCode::MismatchedTypes,
+                    &format!("Mismatched types; expected `{}`, found a struct", dt),
+                );
+            }
+            return Type::Unknown;
+        };
+        let Some(def) = self.structs.get(name.as_str()).copied() else {
+            self.error(
+                ErrorCode::UnknownType,
+                &format!("Unknown struct `{}`", name),
+            );
+            return Type::Unknown;
+        };
+
+        for (field, value) in fields {
+            if let Some((_, dt)) = def.iter().find(|(def_field, _)| def_field == field) {
+                let found = self.check_expr(value, Some(dt));
+                self.expect(dt, &found);
+            } else {
+                self.check_expr(value, None);
+                self.error(
+                    ErrorCode::UnknownField,
+                    &format!("Struct `{}` has no field `{}`", name, field),
+                );
+            }
+        }
+        for (def_field, _) in def {
+            if !fields.iter().any(|(field, _)| field == def_field) {
+                self.error(
+                    ErrorCode::MissingField,
+                    &format!("Missing field `{}` in struct `{}`", def_field, name),
+                );
+            }
+        }
+        Type::Known(Datatype::CUSTOM(name.clone()))
+    }
+
+    fn check_call(&mut self, call: &'a FunctionCall, expected: Option<&Datatype>) -> Type {
+        self.line = call.line;
+        self.column = call.column;
+        match call.name.as_str() {
+            "ok" | "err" => {
+                let arg_type = match expected {
+                    Some(Datatype::RESULT(ok, _)) if call.name == "ok" => Some(ok.as_ref()),
+                    Some(Datatype::RESULT(_, err)) => Some(err.as_ref()),
+                    _ => None,
+                };
+                self.check_arg_count(call, 1);
+                self.check_args(call, arg_type.map(std::slice::from_ref));
+                match expected {
+                    Some(dt @ Datatype::RESULT(..)) => Type::Known(dt.clone()),
+                    Some(dt) => {
+                        self.error(
+                            ErrorCode::MismatchedTypes,
+                            &format!("Mismatched types; expected `{}`, found a Result", dt),
+                        );
+                        Type::Unknown
+                    }
+                    None => Type::Unknown,
+                }
+            }
+            "assert" => {
+                match call.args.as_slice() {
+                    [cond] => self.check_condition(cond),
+                    [cond, msg] => {
+                        self.check_condition(cond);
+                        let found = self.check_expr(msg, None);
+                        self.expect(&Datatype::STRING(0), &found);
+                    }
+                    _ => {
+                        self.check_args(call, None);
+                        self.error(
+                            ErrorCode::WrongArgumentCount,
+                            "`assert` takes a condition and an optional message",
+                        );
+                    }
+                }
+                Type::Known(Datatype::NONE)
+            }
+            "unreachable" => {
+                self.check_arg_count(call, 0);
+                self.check_args(call, None);
+                Type::Known(Datatype::NONE)
+            }
+            name => {
+                let Some(sig) = self.functions.get(name) else {
+                    for arg in call.args.iter() {
+                        self.check_expr(arg, None);
+                    }
+                    return Type::Unknown;
+                };
+                let (args, return_type) = (sig.args, sig.return_type);
+                self.check_arg_count(call, args.len());
+                let params = args
+                    .iter()
+                    .map(|param| param.datatype.clone())
+                    .collect::<Vec<_>>();
+                self.check_args(call, Some(&params));
+                Type::Known(return_type.cloned().unwrap_or(Datatype::NONE))
+            }
+        }
+    }
+
+    fn check_arg_count(&mut self, call: &FunctionCall, count: usize) {
+        if call.args.len() != count {
+            self.error(
+                ErrorCode::WrongArgumentCount,
+                &format!(
+                    "Function `{}` takes {} arguments, but {} were given",
+                    call.name,
+                    count,
+                    call.args.len()
+                ),
+            );
+        }
+    }
+
+    /// Calls to the standard library are checked against its signatures, where
+    /// integer and float parameters take numbers of any size
+    fn check_import_call(&mut self, call: &'a ImportCall) -> Type {
+        let name = call.path.join("::");
+        let func = full_path(&self.imports, &call.path).and_then(|path| std_function(&path));
+        self.check_external(&call.ident, &name, func.as_ref())
+    }
+
+    /// The type of the call to a function of the standard library, and of the
+    /// attributes and methods accessed on its result
+    fn check_external(
+        &mut self,
+        node: &'a ASTNodes,
+        name: &str,
+        func: Option<&StdFunction>,
+    ) -> Type {
+        match node {
+            ASTNodes::FunctionCall(call) => {
+                self.line = call.line;
+                self.column = call.column;
+                let Some(func) = func else {
+                    // Unknown modules and functions are reported by the resolver
+                    self.check_args(call, None);
+                    return Type::Unknown;
+                };
+                let count = func.params.len();
+                if call.args.len() < count || (!func.variadic && call.args.len() > count) {
+                    self.error(
+                        ErrorCode::WrongArgumentCount,
+                        &format!(
+                            "Function `{}` takes {}{} arguments, but {} were given",
+                            name,
+                            if func.variadic { "at least " } else { "" },
+                            count,
+                            call.args.len()
+                        ),
+                    );
+                }
+                let mut rest = vec![];
+                for (i, arg) in call.args.iter().enumerate() {
+                    let Some(param) = func.params.get(i) else {
+                        rest.push(self.check_expr(arg, None));
+                        continue;
+                    };
+                    let found = self.check_expr(arg, Some(param));
+                    let number = (param.is_integer() && found.is_integer())
+                        || (param.is_float() && found.is_float());
+                    if !number {
+                        self.expect(param, &found);
+                    }
+                }
+                if func.variadic
+                    && let Some(Expression::String(fmt)) = call.args.first()
+                {
+                    self.line = call.line;
+                    self.column = call.column;
+                    self.check_format(fmt, &rest);
+                }
+                Type::Known(func.return_type.clone())
+            }
+            ASTNodes::Attr(attr) => {
+                let parent = self.check_external(&attr.parent, name, func);
+                self.attr_type(&parent, attr)
+            }
+            ASTNodes::Method(method) => {
+                let parent = self.check_external(&method.parent, name, func);
+                self.method_type(&parent, &method.func)
+            }
+            node => {
+                self.error(
+                    ErrorCode::Unsupported,
+                    &format!("Expected a call to `{}`, found {}", name, node.kind()),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    /// Checks the arguments of a format string against its placeholders. The resolver
+    /// has already turned the named ones into positional ones.
+    fn check_format(&mut self, fmt: &str, args: &[Type]) {
+        let pieces = match parse_format_string(fmt) {
+            Ok(pieces) => pieces,
+            Err(err) => {
+                self.error(ErrorCode::InvalidFormat, &err);
+                return;
+            }
+        };
+        let precisions = pieces
+            .iter()
+            .filter_map(|piece| match piece {
+                FormatPiece::Arg {
+                    name: None,
+                    precision,
+                } => Some(*precision),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if precisions.len() != args.len() {
+            self.error(
+                ErrorCode::InvalidFormat,
+                &format!(
+                    "Format string expects {} arguments, but {} were given",
+                    precisions.len(),
+                    args.len()
+                ),
+            );
+            return;
+        }
+        for (precision, found) in precisions.iter().zip(args) {
+            if let Type::Known(Datatype::RESULT(..)) = found {
+                self.error(ErrorCode::InvalidFormat, "Results cannot be formatted");
+            } else if precision.is_some() && !found.is_float() {
+                self.error(
+                    ErrorCode::InvalidFormat,
+                    &format!(
+                        "Precision can only be used to format floats, found `{}`",
+                        found
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Checks the arguments of a call against `params`, if they are known
+    fn check_args(&mut self, call: &'a FunctionCall, params: Option<&[Datatype]>) {
+        for (i, arg) in call.args.iter().enumerate() {
+            match params.and_then(|params| params.get(i)) {
+                Some(param) => {
+                    let found = match (param, self.check_expr(arg, Some(param))) {
+                        // Arrays are passed to slice parameters as a slice of their elements
+                        (Datatype::SLICE(_), Type::Known(Datatype::NARRAY(inner, _))) => {
+                            Type::Known(Datatype::SLICE(inner))
+                        }
+                        (_, found) => found,
+                    };
+                    self.expect(param, &found);
+                }
+                None => {
+                    self.check_expr(arg, None);
+                }
+            }
+        }
+    }
+
+    fn check_array_index(&mut self, index: &'a ArrayIndex) -> Type {
+        let array = self.check_node(&index.array_var, None);
+        self.line = index.line;
+        self.column = index.column;
+        let found = self.check_expr(&index.index, None);
+        if !found.is_integer() {
+            self.error(
+                ErrorCode::NotIndexable,
+                &format!("Array index must be an integer, found `{}`", found),
+            );
+        }
+        match array {
+            Type::Known(Datatype::NARRAY(dt, _) | Datatype::SIMD(dt, _) | Datatype::SLICE(dt)) => {
+                Type::Known(*dt)
+            }
+            Type::Unknown => Type::Unknown,
+            array => {
+                self.error(
+                    ErrorCode::NotIndexable,
+                    &format!("Cannot index into a value of type `{}`", array),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_attr(&mut self, attr: &'a Attr) -> Type {
+        let parent = self.check_node(&attr.parent, None);
+        self.attr_type(&parent, attr)
+    }
+
+    fn attr_type(&mut self, parent: &Type, attr: &Attr) -> Type {
+        let field = &attr.name.name;
+        match parent {
+            Type::Known(Datatype::CUSTOM(name)) => {
+                let field_type = self.structs.get(name.as_str()).and_then(|fields| {
+                    fields
+                        .iter()
+                        .find(|(def_field, _)| def_field == field)
+                        .map(|(_, dt)| dt.clone())
+                });
+                field_type.map(Type::Known).unwrap_or_else(|| {
+                    self.error(
+                        ErrorCode::UnknownField,
+                        &format!("No field `{}` on type `{}`", field, name),
+                    );
+                    Type::Unknown
+                })
+            }
+            Type::Unknown => Type::Unknown,
+            parent => {
+                self.error(
+                    ErrorCode::UnknownField,
+                    &format!("No field `{}` on type `{}`", field, parent),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_method(&mut self, method: &'a Method) -> Type {
+        let parent = self.check_node(&method.parent, None);
+        self.method_type(&parent, &method.func)
+    }
+
+    fn method_type(&mut self, parent: &Type, call: &'a FunctionCall) -> Type {
+        self.line = call.line;
+        self.column = call.column;
+        let name = call.name.as_str();
+
+        let Type::Known(dt) = parent else {
+            self.check_args(call, None);
+            return Type::Unknown;
+        };
+        let (params, return_type) = match dt {
+            Datatype::RESULT(ok, _) => match name {
+                "is_ok" | "is_err" => (vec![], Datatype::BOOL),
+                "unwrap_or" => (vec![*ok.clone()], *ok.clone()),
+                _ => return self.unknown_method(call, parent),
+            },
+            Datatype::STRING(_) => match name {
+                "len" => (vec![], Datatype::U64),
+                "slice" => (vec![Datatype::U64, Datatype::U64], Datatype::STRING(0)),
+                "find" => (vec![Datatype::STRING(0)], Datatype::I64),
+                "starts_with" => (vec![Datatype::STRING(0)], Datatype::BOOL),
+                _ => return self.unknown_method(call, parent),
+            },
+            // The length of an array is a constant, so like a literal it can be used as
+            // any integer
+            Datatype::NARRAY(..) if name == "len" && call.args.is_empty() => return Type::Int,
+            Datatype::SLICE(_) => match name {
+                "len" => (vec![], Datatype::U64),
+                _ => return self.unknown_method(call, parent),
+            },
+            dt if dt.is_integer() => match name {
+                "wrapping_add" | "wrapping_sub" | "wrapping_mul" | "saturating_add"
+                | "saturating_sub" => (vec![dt.clone()], dt.clone()),
+                "checked_add" | "checked_sub" | "checked_mul" => (
+                    vec![dt.clone()],
+                    Datatype::RESULT(Box::new(dt.clone()), Box::new(Datatype::I32)),
+                ),
+                _ => return self.unknown_method(call, parent),
+            },
+            _ => return self.unknown_method(call, parent),
+        };
+
+        if params.len() != call.args.len() {
+            return self.unknown_method(call, parent);
+        }
+        for (arg, param) in call.args.iter().zip(params.iter()) {
+            let found = self.check_expr(arg, Some(param));
+            // Methods taking integers accept any integer
+            if !(param.is_integer() && found.is_integer()) {
+                self.expect(param, &found);
+            }
+        }
+        Type::Known(return_type)
+    }
+
+    fn unknown_method(&mut self, call: &'a FunctionCall, parent: &Type) -> Type {
+        self.check_args(call, None);
+        self.error(
+            ErrorCode::UnknownMethod,
+            &format!(
+                "No method `{}` taking {} arguments on type `{}`",
+                call.name,
+                call.args.len(),
+                parent
+            ),
+        );
+        Type::Unknown
+    }
+}
+
+/// `none` is the same as not returning anything
+fn returned_type(dt: Option<&Datatype>) -> Option<&Datatype> {
+    dt.filter(|dt| **dt != Datatype::NONE)
+}
+
+fn literal_type(lit: &Literal) -> Type {
+    match lit.r#type {
+        Types::NUMBER if lit.value.contains('.') => Type::Float,
+        Types::NUMBER => Type::Int,
+        Types::BOOL => Type::Known(Datatype::BOOL),
+        Types::DATATYPE(Datatype::STRING(_)) => Type::Str,
+        _ => Type::Unknown,
+    }
+}
+
+/// Whether two types are the same, ignoring sizes that aren't known yet.
+/// Integers of the same size only differ in how they are used, so they are interchangeable.
+fn same_type(a: &Datatype, b: &Datatype) -> bool {
+    match (a, b) {
+        (a, b) if a.is_integer() && b.is_integer() => a.bit_width() == b.bit_width(),
+        (Datatype::NARRAY(a, n), Datatype::NARRAY(b, m)) => {
+            same_type(a, b) && (*n == 0 || *m == 0 || n == m)
+        }
+        (Datatype::SIMD(a, n), Datatype::SIMD(b, m)) => same_type(a, b) && n == m,
+        (Datatype::SLICE(a), Datatype::SLICE(b)) => same_type(a, b),
+        (Datatype::STRING(_), Datatype::STRING(_)) => true,
+        (Datatype::CSTRING(_), Datatype::CSTRING(_)) => true,
+        (Datatype::RESULT(a_ok, a_err), Datatype::RESULT(b_ok, b_err)) => {
+            same_type(a_ok, b_ok) && same_type(a_err, b_err)
+        }
+        _ => a == b,
+    }
+}
+
+/// Whether a value of type `found` can be used where `expected` is expected
+fn accepts(expected: &Datatype, found: &Type) -> bool {
+    match found {
+        Type::Unknown => true,
+        Type::Int => expected.is_integer(),
+        Type::Float => expected.is_float(),
+        Type::Str => matches!(expected, Datatype::STRING(_) | Datatype::CSTRING(_)),
+        Type::Known(dt) => same_type(expected, dt),
+    }
+}
+
+/// The type both operands of a binary operation are converted to. The backend
+/// widens the narrower of two integers or floats.
+fn unify(left: &Type, right: &Type) -> Option<Type> {
+    match (left, right) {
+        (Type::Unknown, _) | (_, Type::Unknown) => Some(Type::Unknown),
+        (Type::Int, Type::Int) => Some(Type::Int),
+        (Type::Float, Type::Float) => Some(Type::Float),
+        (Type::Str, Type::Str) => Some(Type::Str),
+        (Type::Int, Type::Known(dt)) | (Type::Known(dt), Type::Int) if dt.is_integer() => {
+            Some(Type::Known(dt.clone()))
+        }
+        (Type::Float, Type::Known(dt)) | (Type::Known(dt), Type::Float) if dt.is_float() => {
+            Some(Type::Known(dt.clone()))
+        }
+        (Type::Str, Type::Known(dt @ Datatype::STRING(_)))
+        | (Type::Known(dt @ Datatype::STRING(_)), Type::Str) => Some(Type::Known(dt.clone())),
+        (Type::Known(a), Type::Known(b))
+            if (a.is_integer() && b.is_integer()) || (a.is_float() && b.is_float()) =>
+        {
+            let wider = match a.bit_width().cmp(&b.bit_width()) {
+                std::cmp::Ordering::Greater => a,
+                std::cmp::Ordering::Less => b,
+                std::cmp::Ordering::Equal if b.is_signed() => b,
+                std::cmp::Ordering::Equal => a,
+            };
+            Some(Type::Known(wider.clone()))
+        }
+        (Type::Known(a), Type::Known(b)) if same_type(a, b) => Some(Type::Known(a.clone())),
+        _ => None,
+    }
+}
+
+pub(crate) fn operator_symbol(op: &Operator) -> &'static str {
+    match op {
+        Operator::PLUS => "+",
+        Operator::MINUS => "-",
+        Operator::MULTIPLY => "*",
+        Operator::DIVIDE => "/",
+        Operator::MODULO => "%",
+        Operator::EQUAL => "==",
+        Operator::NOT_EQUAL => "!=",
+        Operator::LESSER => "<",
+        Operator::GREATER => ">",
+        Operator::LESSER_EQUAL => "<=",
+        Operator::GREATER_EQUAL => ">=",
+        Operator::BITWISE_AND => "&",
+        Operator::BITWISE_OR => "|",
+        Operator::BITWISE_XOR => "^",
+        Operator::LSHIFT => "<<",
+        Operator::RSHIFT => ">>",
+        _ => "?",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use super::TypeChecker;
+
+    fn check(code: &str) -> Vec<String> {
+        let mut lexer = Lexer::new(code);
+        let ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        TypeChecker::check(&ast)
+            .0
+            .iter()
+            .map(|err| err.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_program() {
+        let code = "
+struct Point { x i32, y i32 }
+
+func add(a u64, b u64) u64 {
+    return a + b * 2
+}
+
+func main() i32 {
+    let Point p = { x 1, y 2 }
+    let u64[] arr = [1, 2, 3]
+    let u64! total = 0
+    loop range val, index = arr {
+        total = total + add(val, index)
+    }
+    if total > 10 {
+        return p.y
+    }
+    return total -> i32
+}";
+        assert_eq!(check(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_mismatched_let() {
+        let code = "
+func main() u32 {
+    let u64 a = 1
+    let u32 b = a
+    let bool c = 2
+    return b
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 4: Mismatched types; expected `u32`, found `u64`",
+                "line 5: Mismatched types; expected `bool`, found `integer`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compound_assignments() {
+        let code = "
+func main() u32 {
+    let u32! a = 1
+    let u64 b = 2
+    let bool! c = true
+    a += 2
+    a <<= b -> u32
+    a *= b
+    c += true
+    c |= false
+    return a
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 8: Mismatched types; expected `u32`, found `u64`",
+                "line 9: Cannot apply `+` to `bool` and `bool`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let code = "
+func add(a u32, b u32) u32 {
+    return a + b
+}
+
+func main() u32 {
+    let u32 a = add(1)
+    let u32 b = add(1, true)
+    return add(a, b, 3)
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 7: Function `add` takes 2 arguments, but 1 were given",
+                "line 8: Mismatched types; expected `u32`, found `bool`",
+                "line 9: Function `add` takes 2 arguments, but 3 were given",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stdlib_calls() {
+        let code = "
+import std::io
+import std::str
+
+func main() u32 {
+    io::println(\"{} {}\", 1, 2)
+    io::printint()
+    io::printint(1, 2)
+    io::printflt(true)
+    let Result<i64, i32> n = str::parse_int(\"4\")
+    let u32 len = str::from_int(4).len()
+    let u32 x = io::printint
+    return x
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 7: Function `io::printint` takes 1 arguments, but 0 were given",
+                "line 8: Function `io::printint` takes 1 arguments, but 2 were given",
+                "line 9: Mismatched types; expected `f64`, found `bool`",
+                "line 11: Mismatched types; expected `u32`, found `u64`",
+                "line 12: Expected a call to `io::printint`, found variable",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_strings() {
+        let code = "
+import std::io
+
+func half(a u32) Result<u32, i32> {
+    return ok(a / 2)
+}
+
+func main() u32 {
+    let f32 a = 1.5
+    let u32 b = 2
+    io::println(\"{:.2} {}\", a, b)
+    io::println(\"{} {}\", a)
+    io::println(\"{:.2}\", b)
+    io::println(\"{}\", half(b))
+    io::print(\"{\")
+    return b
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 12: Format string expects 2 arguments, but 1 were given",
+                "line 13: Precision can only be used to format floats, found `u32`",
+                "line 14: Results cannot be formatted",
+                "line 15: Unclosed '{' in format string",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_of_the_wrong_kind() {
+        let code = "
+struct Point { x i32, y i32 }
+
+func get() u32 {
+    return ok(1)
+}
+
+func main() u32 {
+    let u32 a = [1]
+    let u32 b = { x 1, y 2 }
+    let u32[2] c = [1, ]
+    let u8 len = c.len()
+    let u64 d = 99999999999999999999999
+    assert(true, \"a\", \"b\")
+    return c.first()
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 5: Mismatched types; expected `u32`, found a Result",
+                "line 9: Mismatched types; expected `u32`, found an array",
+                "line 10: Mismatched types; expected `u32`, found a struct",
+                "line 11: Expected an expression",
+                "line 13: Integer literal `99999999999999999999999` does not fit in 64 bits",
+                "line 14: `assert` takes a condition and an optional message",
+                "line 15: No method `first` taking 0 arguments on type `u32[2]`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_and_conditions() {
+        let code = "
+func nothing() {
+    return 1
+}
+
+func main() f32 {
+    let u32 a = 1
+    if a {
+        return 1.5
+    }
+    loop a + 1 {
+        break
+    }
+    return a
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 3: Cannot return a value from a function without a return type",
+                "line 8: Expected a `bool` condition, found `u32`",
+                "line 11: Expected a `bool` condition, found `u32`",
+                "line 14: Mismatched types; expected `f32`, found `u32`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let code = "
+struct Point { x i32, y i32 }
+
+func main() i32 {
+    let Point p = { x 1, z 2 }
+    return p.z
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 5: Struct `Point` has no field `z`",
+                "line 5: Missing field `y` in struct `Point`",
+                "line 6: No field `z` on type `Point`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsized_arrays() {
+        let code = "
+struct Grid { cells u8[][3] }
+
+extern func fill(values u32[]) u32[4]
+
+func first(values u32[3]) u32 {
+    let u32[] copy = values
+    return copy[0]
+}
+
+func main() u32 {
+    let u32[] values = [1, 2, 3]
+    return first(values)
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 2: Array type `u8[]` needs a size, such as `u8[4]`",
+                "line 4: Array type `u32[]` needs a size, such as `u32[4]`",
+                "line 7: Array type `u32[]` needs a size, such as `u32[4]`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsized_array_params() {
+        let code = "
+func first(values u32[]) u32 {
+    return values[0] + values.len() -> u32
+}
+
+func main() u32 {
+    let u32[] values = [1, 2, 3]
+    let bool[] flags = [true]
+    return first(values) + first([4, 5]) + first(flags)
+}";
+        assert_eq!(
+            check(code),
+            vec!["line 9: Mismatched types; expected `u32[..]`, found `bool[..]`"]
+        );
+    }
+
+    #[test]
+    fn test_slices() {
+        let code = "
+func count(args string[..]) u64 {
+    return args.len()
+}
+
+func main(args string[..]) i32 {
+    let string first = args[0]
+    let bool second = args[1]
+    let u32 n = args.len()
+    return count(args) -> i32
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 8: Mismatched types; expected `bool`, found `string`",
+                "line 9: Mismatched types; expected `u32`, found `u64`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_binary_operations() {
+        let code = "
+func main() u32 {
+    let f32 a = 1.5
+    let u32 b = 2
+    let string s = \"a\"
+    let f32 c = a + b
+    let string d = s * s
+    return b
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 6: Cannot apply `+` to `f32` and `u32`",
+                "line 7: Cannot apply `*` to `string` and `string`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_operator() {
+        let code = "
+func half(a u32) Result<u32, i32> {
+    return ok(a / 2)
+}
+
+func quarter(a u32) Result<u32, i32> {
+    return half(half(a)?)
+}
+
+func log(a u32) {
+    let u32 b = half(a)?
+}
+
+func main() u32 {
+    let u32 n = 4
+    let u32 c = n?
+    return half(n)?
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 11: The `?` operator can only be used in functions that return a Result",
+                "line 16: The `?` operator can only be applied to a Result, found `u32`",
+                "line 17: The `?` operator can only be used in functions that return a Result",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_int_methods() {
+        let code = "
+func main() u8 {
+    let u8 a = 200
+    let u8 b = a.wrapping_mul(2)
+    let u8 c = a.checked_sub(1).unwrap_or(0)
+    let u8 d = a.saturating_mul(2)
+    let u8 e = a.checked_div(2).unwrap_or(0)
+    return b + c + d + e
+}";
+        assert_eq!(
+            check(code),
+            vec![
+                "line 6: No method `saturating_mul` taking 1 arguments on type `u8`",
+                "line 7: No method `checked_div` taking 1 arguments on type `u8`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lossy_conversions() {
+        let code = "
+func main() i32 {
+    let u8 a = 300
+    let i8 b = 127
+    let u32 c = 4294967295
+    let u32 d = b -> u32
+    return d
+}";
+        let mut lexer = Lexer::new(code);
+        let ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let (errors, warnings) = TypeChecker::check(&ast);
+        assert!(errors.is_empty());
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "line 3: warning: Literal `300` does not fit in `u8` [lossy_conversions]",
+                "line 7: warning: Implicit conversion from `u32` to `i32` can change the value [lossy_conversions]",
+            ]
+        );
+    }
+}
@@ -1,2 +1,8 @@
+pub mod errors;
 pub mod r#impl;
+pub mod lints;
+pub mod pass;
+pub mod stdlib;
+pub mod visit;
+pub mod visit_mut;
 
@@ -0,0 +1,273 @@
+use std::{collections::HashMap, fmt::Display};
+
+use diagnostics::ErrorCode;
+use parser::nodes::{ASTNodes, Attribute};
+
+use crate::errors::{PassError, PassWarning};
+
+/// Warnings that can be allowed, or turned into errors, one by one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    UnusedVariables,
+    UnusedFunctions,
+    UnusedImports,
+    UnusedFields,
+    UnusedMut,
+    UnreachableCode,
+    LossyConversions,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedVariables,
+        Lint::UnusedFunctions,
+        Lint::UnusedImports,
+        Lint::UnusedFields,
+        Lint::UnusedMut,
+        Lint::UnreachableCode,
+        Lint::LossyConversions,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedFunctions => "unused_functions",
+            Lint::UnusedImports => "unused_imports",
+            Lint::UnusedFields => "unused_fields",
+            Lint::UnusedMut => "unused_mut",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::LossyConversions => "lossy_conversions",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Self::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    /// The level set by an attribute, like `#[allow(...)]`
+    fn from_attr(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// The level of every lint. All lints warn by default.
+#[derive(Debug, Clone)]
+pub struct LintLevels {
+    levels: HashMap<Lint, LintLevel>,
+}
+
+impl Default for LintLevels {
+    fn default() -> Self {
+        Self {
+            levels: Lint::ALL
+                .into_iter()
+                .map(|lint| (lint, LintLevel::Warn))
+                .collect(),
+        }
+    }
+}
+
+impl LintLevels {
+    /// Sets the level of the lint called `name`, or of every lint for `warnings`
+    pub fn set(&mut self, name: &str, level: LintLevel) -> Result<(), String> {
+        if name == "warnings" {
+            self.levels.values_mut().for_each(|l| *l = level);
+            return Ok(());
+        }
+        let lint = Lint::from_name(name).ok_or(format!("Unknown lint `{}`", name))?;
+        self.levels.insert(lint, level);
+        Ok(())
+    }
+
+    pub fn get(&self, lint: Lint) -> LintLevel {
+        self.levels[&lint]
+    }
+
+    fn with_attrs(&self, attrs: &[Attribute]) -> Result<LintLevels, PassError> {
+        let mut levels = self.clone();
+        for attr in attrs {
+            let level = LintLevel::from_attr(&attr.name).ok_or_else(|| {
+                PassError::new(&format!("Unknown attribute `{}`", attr.name), attr.line)
+                    .with_code(ErrorCode::UnknownAttribute)
+                    .with_column(attr.column)
+            })?;
+            for name in attr.args.iter() {
+                levels.set(name, level).map_err(|msg| {
+                    PassError::new(&msg, attr.line)
+                        .with_code(ErrorCode::UnknownAttribute)
+                        .with_column(attr.column)
+                })?;
+            }
+        }
+        Ok(levels)
+    }
+}
+
+/// The lint levels of each function and struct, with their attributes applied.
+/// Items cover the lines from where they start up to the next item.
+pub(crate) struct ItemLevels {
+    global: LintLevels,
+    items: Vec<(usize, LintLevels)>,
+}
+
+impl ItemLevels {
+    pub(crate) fn new(global: &LintLevels, nodes: &[ASTNodes]) -> Result<Self, Vec<PassError>> {
+        let mut items = vec![];
+        let mut errors = vec![];
+        for node in nodes {
+            let (line, attrs) = match node {
+                ASTNodes::Function(func) => (func.line, func.attrs.as_slice()),
+                ASTNodes::StructDef(def) => (def.line, def.attrs.as_slice()),
+                ASTNodes::Extern(ext) => (ext.line, [].as_slice()),
+                ASTNodes::ImportDef(imp) => (imp.line, [].as_slice()),
+                _ => continue,
+            };
+            match global.with_attrs(attrs) {
+                Ok(levels) => items.push((line, levels)),
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        items.sort_by_key(|(line, _)| *line);
+        Ok(Self {
+            global: global.clone(),
+            items,
+        })
+    }
+
+    fn get(&self, lint: Lint, line: usize) -> LintLevel {
+        self.items
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= line)
+            .map_or(&self.global, |(_, levels)| levels)
+            .get(lint)
+    }
+
+    /// Drops allowed warnings, and turns denied ones into errors
+    pub(crate) fn apply(
+        &self,
+        warnings: Vec<PassWarning>,
+    ) -> Result<Vec<PassWarning>, Vec<PassError>> {
+        let mut kept = vec![];
+        let mut errors = vec![];
+        for warning in warnings {
+            match self.get(warning.lint, warning.line) {
+                LintLevel::Allow => {}
+                LintLevel::Warn => kept.push(warning),
+                LintLevel::Deny => errors.push(PassError::from(warning)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        kept.sort_by_key(|warning| warning.line);
+        Ok(kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::Parser;
+
+    use super::{ItemLevels, Lint, LintLevel, LintLevels};
+    use crate::errors::PassWarning;
+
+    fn apply(code: &str, levels: &LintLevels, lines: &[usize]) -> Result<Vec<usize>, Vec<String>> {
+        let mut lexer = Lexer::new(code);
+        let ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let levels = ItemLevels::new(levels, &ast)
+            .map_err(|errors| errors.iter().map(|err| err.to_string()).collect::<Vec<_>>())?;
+        let warnings = lines
+            .iter()
+            .map(|line| PassWarning::new(Lint::UnusedVariables, "Unused", *line))
+            .collect();
+        levels
+            .apply(warnings)
+            .map(|warnings| warnings.iter().map(|warning| warning.line).collect())
+            .map_err(|errors| errors.iter().map(|err| err.to_string()).collect())
+    }
+
+    const CODE: &str = "
+func first() {
+    let u32 a = 1
+}
+
+#[allow(unused_variables)]
+func second() {
+    let u32 b = 2
+}
+
+#[deny(unused_variables)]
+func third() {
+    let u32 c = 3
+}";
+
+    #[test]
+    fn test_attribute_levels() {
+        assert_eq!(apply(CODE, &LintLevels::default(), &[2, 7]), Ok(vec![2]));
+        assert_eq!(
+            apply(CODE, &LintLevels::default(), &[2, 7, 12]),
+            Err(vec!["line 13: Unused [unused_variables]".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_global_levels() {
+        let mut levels = LintLevels::default();
+        levels.set("warnings", LintLevel::Deny).unwrap();
+        levels.set("unused_mut", LintLevel::Allow).unwrap();
+        assert_eq!(levels.get(Lint::UnusedMut), LintLevel::Allow);
+        assert_eq!(levels.get(Lint::UnusedImports), LintLevel::Deny);
+        // Attributes take precedence over the command line
+        assert_eq!(apply(CODE, &levels, &[7]), Ok(vec![]));
+        assert!(apply(CODE, &levels, &[2]).is_err());
+
+        assert_eq!(
+            levels.set("unused_things", LintLevel::Allow),
+            Err("Unknown lint `unused_things`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_attributes() {
+        let code = "
+#[allow(unused_things)]
+func first() {
+}
+
+#[ignore(unused_variables)]
+func second() {
+}";
+        assert_eq!(
+            apply(code, &LintLevels::default(), &[]),
+            Err(vec![
+                "line 2: Unknown lint `unused_things`".to_string(),
+                "line 6: Unknown attribute `ignore`".to_string(),
+            ])
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use parser::nodes::ASTNodes;
+
+use crate::errors::{PassError, PassWarning};
+
+/// A step of the pipeline run by `PassManager`. Passes run in the order they are
+/// registered, and the first pass that returns errors stops the pipeline.
+pub trait Pass {
+    /// Shown by `--time-passes`
+    fn name(&self) -> &str;
+
+    fn run(
+        &mut self,
+        nodes: &mut Vec<ASTNodes>,
+        ctx: &mut PassContext,
+    ) -> Result<(), Vec<PassError>>;
+}
+
+/// State shared by the passes of a pipeline: the warnings found so far, and the
+/// results of analyses that later passes can reuse, stored by type.
+#[derive(Default)]
+pub struct PassContext {
+    warnings: Vec<PassWarning>,
+    results: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl PassContext {
+    pub fn warn(&mut self, warning: PassWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// Stores the result of an analysis, replacing the previous result of the same type
+    pub fn insert<T: Any>(&mut self, result: T) {
+        self.results.insert(TypeId::of::<T>(), Box::new(result));
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.results.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub(crate) fn take_warnings(&mut self) -> Vec<PassWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{ASTNodes, FunctionCall},
+    };
+
+    use super::{Pass, PassContext};
+    use crate::{
+        errors::{PassError, PassWarning},
+        r#impl::PassManager,
+        lints::Lint,
+        visit::{Visitor, walk_function_call},
+    };
+
+    /// Shared by `CountCalls` with the passes after it
+    struct CallCount(usize);
+
+    struct CountCalls;
+
+    impl Pass for CountCalls {
+        fn name(&self) -> &str {
+            "count_calls"
+        }
+
+        fn run(
+            &mut self,
+            nodes: &mut Vec<ASTNodes>,
+            ctx: &mut PassContext,
+        ) -> Result<(), Vec<PassError>> {
+            struct Counter(usize);
+            impl<'a> Visitor<'a> for Counter {
+                fn visit_function_call(&mut self, call: &'a FunctionCall) {
+                    self.0 += 1;
+                    walk_function_call(self, call);
+                }
+            }
+            let mut counter = Counter(0);
+            nodes.iter().for_each(|node| counter.visit_node(node));
+            ctx.insert(CallCount(counter.0));
+            Ok(())
+        }
+    }
+
+    /// Rejects programs with more calls than the limit
+    struct LimitCalls(usize);
+
+    impl Pass for LimitCalls {
+        fn name(&self) -> &str {
+            "limit_calls"
+        }
+
+        fn run(
+            &mut self,
+            _nodes: &mut Vec<ASTNodes>,
+            ctx: &mut PassContext,
+        ) -> Result<(), Vec<PassError>> {
+            let count = ctx.get::<CallCount>().map_or(0, |count| count.0);
+            if count > self.0 {
+                return Err(vec![PassError::new(
+                    &format!("{} calls, expected at most {}", count, self.0),
+                    0,
+                )]);
+            }
+            ctx.warn(PassWarning::new(Lint::UnusedFunctions, "Few calls", 0));
+            Ok(())
+        }
+    }
+
+    const CODE: &str = "
+func double(a u32) u32 {
+    return a * 2
+}
+
+func main() u32 {
+    return double(double(1))
+}";
+
+    fn invoke(limit: usize) -> (Result<Vec<String>, Vec<String>>, Vec<String>) {
+        let mut lexer = Lexer::new(CODE);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let mut manager = PassManager::new(&mut ast)
+            .with_pass(CountCalls)
+            .with_pass(LimitCalls(limit));
+        let result = manager
+            .invoke()
+            .map(|warnings| warnings.iter().map(|w| w.to_string()).collect())
+            .map_err(|errors| errors.iter().map(|err| err.to_string()).collect());
+        let names = manager
+            .timings()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        (result, names)
+    }
+
+    #[test]
+    fn test_pass_order_and_reuse() {
+        let mut lexer = Lexer::new(CODE);
+        let mut ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let mut manager = PassManager::new(&mut ast)
+            .with_const_folding(false)
+            .with_pass(LimitCalls(2))
+            .with_pass_before_desugar(CountCalls);
+        for _ in 0..2 {
+            assert!(manager.invoke().is_ok());
+            let names = manager
+                .timings()
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                names,
+                vec![
+                    "attributes",
+                    "resolve",
+                    "typeck",
+                    "mutability",
+                    "lints",
+                    "count_calls",
+                    "desugar",
+                    "limit_calls",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_passes() {
+        let (result, names) = invoke(2);
+        assert_eq!(
+            result,
+            Ok(vec![
+                "line 1: warning: Few calls [unused_functions]".to_string()
+            ])
+        );
+        assert_eq!(
+            names,
+            vec![
+                "attributes",
+                "resolve",
+                "typeck",
+                "mutability",
+                "lints",
+                "desugar",
+                "const_fold",
+                "count_calls",
+                "limit_calls",
+            ]
+        );
+
+        let (result, names) = invoke(1);
+        assert_eq!(
+            result,
+            Err(vec!["line 1: 2 calls, expected at most 1".to_string()])
+        );
+        assert_eq!(names.last().map(String::as_str), Some("limit_calls"));
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use parser::nodes::Datatype;
+
+/// The modules of the standard library, imported as `std::<module>`
+const MODULES: [&str; 3] = ["io", "fs", "str"];
+
+/// The signature of a function of the standard library, which the backend maps to
+/// the signature of its implementation
+pub struct StdFunction {
+    pub params: Vec<Datatype>,
+    /// `print` and `println` take the arguments of their format string after it
+    pub variadic: bool,
+    pub return_type: Datatype,
+}
+
+/// Whether the full path is `std` or one of its modules
+pub(crate) fn is_std_module(path: &[String]) -> bool {
+    match path {
+        [std] => std == "std",
+        [std, module] => std == "std" && MODULES.contains(&module.as_str()),
+        _ => false,
+    }
+}
+
+/// The function of the standard library at the full path, such as `std::io::println`
+pub fn std_function(path: &[String]) -> Option<StdFunction> {
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    // Fallible functions return an error code
+    let result = |ok| Datatype::RESULT(Box::new(ok), Box::new(Datatype::I32));
+    let (params, return_type) = match path.as_slice() {
+        ["std", "panic"] => (vec![Datatype::STRING(0)], Datatype::NONE),
+        ["std", "io", "print" | "println"] => {
+            return Some(StdFunction {
+                params: vec![Datatype::STRING(0)],
+                variadic: true,
+                return_type: Datatype::NONE,
+            });
+        }
+        ["std", "io", "printint"] => (vec![Datatype::I64], Datatype::NONE),
+        ["std", "io", "printflt"] => (vec![Datatype::F64], Datatype::NONE),
+        ["std", "fs", "write"] => (
+            vec![Datatype::STRING(0), Datatype::STRING(0)],
+            result(Datatype::U64),
+        ),
+        ["std", "fs", "size"] => (vec![Datatype::STRING(0)], result(Datatype::U64)),
+        ["std", "str", "parse_int"] => (vec![Datatype::STRING(0)], result(Datatype::I64)),
+        ["std", "str", "parse_float"] => (vec![Datatype::STRING(0)], result(Datatype::F64)),
+        ["std", "str", "from_int"] => (vec![Datatype::I64], Datatype::STRING(0)),
+        ["std", "str", "from_float"] => (vec![Datatype::F64], Datatype::STRING(0)),
+        _ => return None,
+    };
+    Some(StdFunction {
+        params,
+        variadic: false,
+        return_type,
+    })
+}
+
+/// The full path of a call through an imported module, such as `io::println` with
+/// `import std::io`. Calls through `std` itself don't need an import.
+pub(crate) fn full_path(
+    imports: &HashMap<String, Vec<String>>,
+    path: &[String],
+) -> Option<Vec<String>> {
+    let module = path.first()?;
+    match imports.get(module) {
+        Some(import) => {
+            let mut full = import[..import.len() - 1].to_vec();
+            full.extend(path.iter().cloned());
+            Some(full)
+        }
+        None if module == "std" => Some(path.to_vec()),
+        None => None,
+    }
+}
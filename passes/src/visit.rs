@@ -0,0 +1,296 @@
+//! Read-only traversal of the AST.
+//!
+//! Every method of [`Visitor`] calls the `walk_` function of the same name by default,
+//! which visits the children of the node. Override a method to look at a kind of node,
+//! and call its `walk_` function from the override to keep recursing into it.
+
+use parser::nodes::{
+    ASTNodes, ArrayIndex, AssignStmt, Attr, Block, Conditional, Expression, Extern, ForLoop,
+    Function, FunctionCall, ImportCall, ImportDef, LetStmt, Literal, Loop, Method, Param, Return,
+    StructDef, Try, Types, Variable,
+};
+
+pub trait Visitor<'a> {
+    fn visit_node(&mut self, node: &'a ASTNodes) {
+        walk_node(self, node);
+    }
+
+    fn visit_function(&mut self, func: &'a Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_param(&mut self, _param: &'a Param) {}
+
+    fn visit_extern(&mut self, ext: &'a Extern) {
+        walk_extern(self, ext);
+    }
+
+    fn visit_struct_def(&mut self, _def: &'a StructDef) {}
+
+    fn visit_import_def(&mut self, _imp: &'a ImportDef) {}
+
+    fn visit_block(&mut self, block: &'a Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &'a LetStmt) {
+        walk_let_stmt(self, stmt);
+    }
+
+    fn visit_assign_stmt(&mut self, stmt: &'a AssignStmt) {
+        walk_assign_stmt(self, stmt);
+    }
+
+    fn visit_return(&mut self, ret: &'a Return) {
+        walk_return(self, ret);
+    }
+
+    fn visit_conditional(&mut self, cond: &'a Conditional) {
+        walk_conditional(self, cond);
+    }
+
+    fn visit_loop(&mut self, stmt: &'a Loop) {
+        walk_loop(self, stmt);
+    }
+
+    fn visit_for_loop(&mut self, stmt: &'a ForLoop) {
+        walk_for_loop(self, stmt);
+    }
+
+    fn visit_break(&mut self) {}
+
+    fn visit_expression(&mut self, expr: &'a Expression) {
+        walk_expression(self, expr);
+    }
+
+    fn visit_literal(&mut self, _lit: &'a Literal) {}
+
+    fn visit_variable(&mut self, _var: &'a Variable) {}
+
+    fn visit_token(&mut self, _token: &'a Types) {}
+
+    fn visit_function_call(&mut self, call: &'a FunctionCall) {
+        walk_function_call(self, call);
+    }
+
+    fn visit_import_call(&mut self, call: &'a ImportCall) {
+        walk_import_call(self, call);
+    }
+
+    fn visit_method(&mut self, method: &'a Method) {
+        walk_method(self, method);
+    }
+
+    fn visit_attr(&mut self, attr: &'a Attr) {
+        walk_attr(self, attr);
+    }
+
+    fn visit_array_index(&mut self, index: &'a ArrayIndex) {
+        walk_array_index(self, index);
+    }
+
+    fn visit_try(&mut self, r#try: &'a Try) {
+        walk_try(self, r#try);
+    }
+}
+
+pub fn walk_node<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ASTNodes) {
+    match node {
+        ASTNodes::AssignStmt(stmt) => v.visit_assign_stmt(stmt),
+        ASTNodes::ArrayIndex(index) => v.visit_array_index(index),
+        ASTNodes::Attr(attr) => v.visit_attr(attr),
+        ASTNodes::Block(block) => v.visit_block(block),
+        ASTNodes::Conditional(cond) => v.visit_conditional(cond),
+        ASTNodes::Expression(expr) => v.visit_expression(expr),
+        ASTNodes::Function(func) => v.visit_function(func),
+        ASTNodes::FunctionCall(call) => v.visit_function_call(call),
+        ASTNodes::ImportDef(imp) => v.visit_import_def(imp),
+        ASTNodes::ImportCall(call) => v.visit_import_call(call),
+        ASTNodes::LetStmt(stmt) => v.visit_let_stmt(stmt),
+        ASTNodes::Literal(lit) => v.visit_literal(lit),
+        ASTNodes::Loop(stmt) => v.visit_loop(stmt),
+        ASTNodes::ForLoop(stmt) => v.visit_for_loop(stmt),
+        ASTNodes::Method(method) => v.visit_method(method),
+        ASTNodes::Return(ret) => v.visit_return(ret),
+        ASTNodes::StructDef(def) => v.visit_struct_def(def),
+        ASTNodes::Token(token) => v.visit_token(token),
+        ASTNodes::Variable(var) => v.visit_variable(var),
+        ASTNodes::Break => v.visit_break(),
+        ASTNodes::Extern(ext) => v.visit_extern(ext),
+        ASTNodes::Try(r#try) => v.visit_try(r#try),
+    }
+}
+
+pub fn walk_function<'a, V: Visitor<'a> + ?Sized>(v: &mut V, func: &'a Function) {
+    func.args.iter().for_each(|param| v.visit_param(param));
+    v.visit_block(&func.body);
+}
+
+pub fn walk_extern<'a, V: Visitor<'a> + ?Sized>(v: &mut V, ext: &'a Extern) {
+    ext.args.iter().for_each(|param| v.visit_param(param));
+}
+
+pub fn walk_block<'a, V: Visitor<'a> + ?Sized>(v: &mut V, block: &'a Block) {
+    block.body.iter().for_each(|node| v.visit_node(node));
+}
+
+pub fn walk_let_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, stmt: &'a LetStmt) {
+    v.visit_expression(&stmt.value);
+}
+
+pub fn walk_assign_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, stmt: &'a AssignStmt) {
+    v.visit_node(&stmt.name);
+    v.visit_expression(&stmt.value);
+}
+
+pub fn walk_return<'a, V: Visitor<'a> + ?Sized>(v: &mut V, ret: &'a Return) {
+    if let Some(value) = &ret.value {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_conditional<'a, V: Visitor<'a> + ?Sized>(v: &mut V, cond: &'a Conditional) {
+    match cond {
+        Conditional::If {
+            condition,
+            body,
+            else_body,
+            ..
+        } => {
+            v.visit_expression(condition);
+            v.visit_block(body);
+            if let Some(else_body) = else_body {
+                v.visit_conditional(else_body);
+            }
+        }
+        Conditional::Else { body } => v.visit_block(body),
+    }
+}
+
+pub fn walk_loop<'a, V: Visitor<'a> + ?Sized>(v: &mut V, stmt: &'a Loop) {
+    if let Some(condition) = &stmt.condition {
+        v.visit_expression(condition);
+    }
+    v.visit_block(&stmt.body);
+}
+
+pub fn walk_for_loop<'a, V: Visitor<'a> + ?Sized>(v: &mut V, stmt: &'a ForLoop) {
+    v.visit_variable(&stmt.value);
+    v.visit_variable(&stmt.increment);
+    v.visit_expression(&stmt.iterator);
+    if let Some(step) = &stmt.step {
+        v.visit_expression(step);
+    }
+    v.visit_block(&stmt.body);
+}
+
+pub fn walk_expression<'a, V: Visitor<'a> + ?Sized>(v: &mut V, expr: &'a Expression) {
+    match expr {
+        Expression::Simple { left, right, .. } => {
+            v.visit_node(left);
+            if let Some(right) = right {
+                v.visit_node(right);
+            }
+        }
+        Expression::Array(items) => items.iter().for_each(|item| v.visit_expression(item)),
+        Expression::Struct(fields) => fields
+            .iter()
+            .for_each(|(_, value)| v.visit_expression(value)),
+        Expression::String(_) | Expression::None => {}
+    }
+}
+
+pub fn walk_function_call<'a, V: Visitor<'a> + ?Sized>(v: &mut V, call: &'a FunctionCall) {
+    call.args.iter().for_each(|arg| v.visit_expression(arg));
+}
+
+pub fn walk_import_call<'a, V: Visitor<'a> + ?Sized>(v: &mut V, call: &'a ImportCall) {
+    v.visit_node(&call.ident);
+}
+
+/// The name of a method is not a function defined in the source, so only its
+/// arguments are visited
+pub fn walk_method<'a, V: Visitor<'a> + ?Sized>(v: &mut V, method: &'a Method) {
+    v.visit_node(&method.parent);
+    method
+        .func
+        .args
+        .iter()
+        .for_each(|arg| v.visit_expression(arg));
+}
+
+/// The name of a field is not a variable, so only the parent is visited
+pub fn walk_attr<'a, V: Visitor<'a> + ?Sized>(v: &mut V, attr: &'a Attr) {
+    v.visit_node(&attr.parent);
+}
+
+pub fn walk_array_index<'a, V: Visitor<'a> + ?Sized>(v: &mut V, index: &'a ArrayIndex) {
+    v.visit_node(&index.array_var);
+    v.visit_expression(&index.index);
+}
+
+pub fn walk_try<'a, V: Visitor<'a> + ?Sized>(v: &mut V, r#try: &'a Try) {
+    v.visit_node(&r#try.value);
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{FunctionCall, Variable},
+    };
+
+    use super::{Visitor, walk_function_call};
+
+    /// Collects the names of the called functions and of the variables that are used
+    #[derive(Default)]
+    struct Names<'a> {
+        calls: Vec<&'a str>,
+        vars: Vec<&'a str>,
+    }
+
+    impl<'a> Visitor<'a> for Names<'a> {
+        fn visit_function_call(&mut self, call: &'a FunctionCall) {
+            self.calls.push(&call.name);
+            walk_function_call(self, call);
+        }
+
+        fn visit_variable(&mut self, var: &'a Variable) {
+            self.vars.push(&var.name);
+        }
+    }
+
+    #[test]
+    fn test_visit_every_node() {
+        let code = "
+import std::io
+
+func double(a u32) u32 {
+    return a * 2
+}
+
+func main() u32 {
+    let u32[] arr = [double(1), 2]
+    let u32! total = 0
+    loop range val, i = arr {
+        if val > 1 {
+            total = total + double(arr[i])
+        }
+    }
+    io::println(\"{}\", total.wrapping_add(double(3)))
+    return total
+}";
+        let mut lexer = Lexer::new(code);
+        let ast = Parser::new(lexer.tokenize()).parse().unwrap();
+        let mut names = Names::default();
+        ast.iter().for_each(|node| names.visit_node(node));
+        assert_eq!(names.calls, vec!["double", "double", "println", "double"]);
+        assert_eq!(
+            names.vars,
+            vec![
+                "a", "val", "i", "arr", "val", "total", "total", "arr", "i", "total", "total"
+            ]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Traversal of the AST that can change the nodes it visits.
+//!
+//! Every method of [`VisitorMut`] calls the `walk_` function of the same name by default,
+//! which visits the children of the node. Override a method to look at a kind of node,
+//! and call its `walk_` function from the override to keep recursing into it.
+
+use parser::nodes::{
+    ASTNodes, ArrayIndex, AssignStmt, Attr, Block, Conditional, Expression, Extern, ForLoop,
+    Function, FunctionCall, ImportCall, ImportDef, LetStmt, Literal, Loop, Method, Param, Return,
+    StructDef, Try, Types, Variable,
+};
+
+pub trait VisitorMut {
+    fn visit_node(&mut self, node: &mut ASTNodes) {
+        walk_node(self, node);
+    }
+
+    fn visit_function(&mut self, func: &mut Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_param(&mut self, _param: &mut Param) {}
+
+    fn visit_extern(&mut self, ext: &mut Extern) {
+        walk_extern(self, ext);
+    }
+
+    fn visit_struct_def(&mut self, _def: &mut StructDef) {}
+
+    fn visit_import_def(&mut self, _imp: &mut ImportDef) {}
+
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &mut LetStmt) {
+        walk_let_stmt(self, stmt);
+    }
+
+    fn visit_assign_stmt(&mut self, stmt: &mut AssignStmt) {
+        walk_assign_stmt(self, stmt);
+    }
+
+    fn visit_return(&mut self, ret: &mut Return) {
+        walk_return(self, ret);
+    }
+
+    fn visit_conditional(&mut self, cond: &mut Conditional) {
+        walk_conditional(self, cond);
+    }
+
+    fn visit_loop(&mut self, stmt: &mut Loop) {
+        walk_loop(self, stmt);
+    }
+
+    fn visit_for_loop(&mut self, stmt: &mut ForLoop) {
+        walk_for_loop(self, stmt);
+    }
+
+    fn visit_break(&mut self) {}
+
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression(self, expr);
+    }
+
+    fn visit_literal(&mut self, _lit: &mut Literal) {}
+
+    fn visit_variable(&mut self, _var: &mut Variable) {}
+
+    fn visit_token(&mut self, _token: &mut Types) {}
+
+    fn visit_function_call(&mut self, call: &mut FunctionCall) {
+        walk_function_call(self, call);
+    }
+
+    fn visit_import_call(&mut self, call: &mut ImportCall) {
+        walk_import_call(self, call);
+    }
+
+    fn visit_method(&mut self, method: &mut Method) {
+        walk_method(self, method);
+    }
+
+    fn visit_attr(&mut self, attr: &mut Attr) {
+        walk_attr(self, attr);
+    }
+
+    fn visit_array_index(&mut self, index: &mut ArrayIndex) {
+        walk_array_index(self, index);
+    }
+
+    fn visit_try(&mut self, r#try: &mut Try) {
+        walk_try(self, r#try);
+    }
+}
+
+pub fn walk_node<V: VisitorMut + ?Sized>(v: &mut V, node: &mut ASTNodes) {
+    match node {
+        ASTNodes::AssignStmt(stmt) => v.visit_assign_stmt(stmt),
+        ASTNodes::ArrayIndex(index) => v.visit_array_index(index),
+        ASTNodes::Attr(attr) => v.visit_attr(attr),
+        ASTNodes::Block(block) => v.visit_block(block),
+        ASTNodes::Conditional(cond) => v.visit_conditional(cond),
+        ASTNodes::Expression(expr) => v.visit_expression(expr),
+        ASTNodes::Function(func) => v.visit_function(func),
+        ASTNodes::FunctionCall(call) => v.visit_function_call(call),
+        ASTNodes::ImportDef(imp) => v.visit_import_def(imp),
+        ASTNodes::ImportCall(call) => v.visit_import_call(call),
+        ASTNodes::LetStmt(stmt) => v.visit_let_stmt(stmt),
+        ASTNodes::Literal(lit) => v.visit_literal(lit),
+        ASTNodes::Loop(stmt) => v.visit_loop(stmt),
+        ASTNodes::ForLoop(stmt) => v.visit_for_loop(stmt),
+        ASTNodes::Method(method) => v.visit_method(method),
+        ASTNodes::Return(ret) => v.visit_return(ret),
+        ASTNodes::StructDef(def) => v.visit_struct_def(def),
+        ASTNodes::Token(token) => v.visit_token(token),
+        ASTNodes::Variable(var) => v.visit_variable(var),
+        ASTNodes::Break => v.visit_break(),
+        ASTNodes::Extern(ext) => v.visit_extern(ext),
+        ASTNodes::Try(r#try) => v.visit_try(r#try),
+    }
+}
+
+pub fn walk_function<V: VisitorMut + ?Sized>(v: &mut V, func: &mut Function) {
+    func.args.iter_mut().for_each(|param| v.visit_param(param));
+    v.visit_block(&mut func.body);
+}
+
+pub fn walk_extern<V: VisitorMut + ?Sized>(v: &mut V, ext: &mut Extern) {
+    ext.args.iter_mut().for_each(|param| v.visit_param(param));
+}
+
+pub fn walk_block<V: VisitorMut + ?Sized>(v: &mut V, block: &mut Block) {
+    block.body.iter_mut().for_each(|node| v.visit_node(node));
+}
+
+pub fn walk_let_stmt<V: VisitorMut + ?Sized>(v: &mut V, stmt: &mut LetStmt) {
+    v.visit_expression(&mut stmt.value);
+}
+
+pub fn walk_assign_stmt<V: VisitorMut + ?Sized>(v: &mut V, stmt: &mut AssignStmt) {
+    v.visit_node(&mut stmt.name);
+    v.visit_expression(&mut stmt.value);
+}
+
+pub fn walk_return<V: VisitorMut + ?Sized>(v: &mut V, ret: &mut Return) {
+    if let Some(value) = &mut ret.value {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_conditional<V: VisitorMut + ?Sized>(v: &mut V, cond: &mut Conditional) {
+    match cond {
+        Conditional::If {
+            condition,
+            body,
+            else_body,
+            ..
+        } => {
+            v.visit_expression(condition);
+            v.visit_block(body);
+            if let Some(else_body) = else_body {
+                v.visit_conditional(else_body);
+            }
+        }
+        Conditional::Else { body } => v.visit_block(body),
+    }
+}
+
+pub fn walk_loop<V: VisitorMut + ?Sized>(v: &mut V, stmt: &mut Loop) {
+    if let Some(condition) = &mut stmt.condition {
+        v.visit_expression(condition);
+    }
+    v.visit_block(&mut stmt.body);
+}
+
+pub fn walk_for_loop<V: VisitorMut + ?Sized>(v: &mut V, stmt: &mut ForLoop) {
+    v.visit_variable(&mut stmt.value);
+    v.visit_variable(&mut stmt.increment);
+    v.visit_expression(&mut stmt.iterator);
+    if let Some(step) = &mut stmt.step {
+        v.visit_expression(step);
+    }
+    v.visit_block(&mut stmt.body);
+}
+
+pub fn walk_expression<V: VisitorMut + ?Sized>(v: &mut V, expr: &mut Expression) {
+    match expr {
+        Expression::Simple { left, right, .. } => {
+            v.visit_node(left);
+            if let Some(right) = right {
+                v.visit_node(right);
+            }
+        }
+        Expression::Array(items) => items.iter_mut().for_each(|item| v.visit_expression(item)),
+        Expression::Struct(fields) => fields
+            .iter_mut()
+            .for_each(|(_, value)| v.visit_expression(value)),
+        Expression::String(_) | Expression::None => {}
+    }
+}
+
+pub fn walk_function_call<V: VisitorMut + ?Sized>(v: &mut V, call: &mut FunctionCall) {
+    call.args.iter_mut().for_each(|arg| v.visit_expression(arg));
+}
+
+pub fn walk_import_call<V: VisitorMut + ?Sized>(v: &mut V, call: &mut ImportCall) {
+    v.visit_node(&mut call.ident);
+}
+
+/// The name of a method is not a function defined in the source, so only its
+/// arguments are visited
+pub fn walk_method<V: VisitorMut + ?Sized>(v: &mut V, method: &mut Method) {
+    v.visit_node(&mut method.parent);
+    method
+        .func
+        .args
+        .iter_mut()
+        .for_each(|arg| v.visit_expression(arg));
+}
+
+/// The name of a field is not a variable, so only the parent is visited
+pub fn walk_attr<V: VisitorMut + ?Sized>(v: &mut V, attr: &mut Attr) {
+    v.visit_node(&mut attr.parent);
+}
+
+pub fn walk_array_index<V: VisitorMut + ?Sized>(v: &mut V, index: &mut ArrayIndex) {
+    v.visit_node(&mut index.array_var);
+    v.visit_expression(&mut index.index);
+}
+
+pub fn walk_try<V: VisitorMut + ?Sized>(v: &mut V, r#try: &mut Try) {
+    v.visit_node(&mut r#try.value);
+}
+
+#[cfg(test)]
+mod tests {
+    use lexer::lexer::Lexer;
+    use parser::{
+        Parser,
+        nodes::{ASTNodes, FunctionCall},
+    };
+
+    use super::{VisitorMut, walk_function_call};
+
+    /// Renames calls to `old` as calls to `new`
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_function_call(&mut self, call: &mut FunctionCall) {
+            if call.name == "old" {
+                call.name = "new".to_string();
+            }
+            walk_function_call(self, call);
+        }
+    }
+
+    fn parse(code: &str) -> Vec<ASTNodes> {
+        let mut lexer = Lexer::new(code);
+        Parser::new(lexer.tokenize()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_change_nodes() {
+        let code = "
+func main() u32 {
+    let u32[] arr = [old(1), 2]
+    if arr[old(0)] > 1 {
+        return old(old(2))
+    }
+    return 0
+}";
+        let mut ast = parse(code);
+        ast.iter_mut().for_each(|node| Rename.visit_node(node));
+        assert_eq!(ast, parse(&code.replace("old", "new")));
+    }
+}
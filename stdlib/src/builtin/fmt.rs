@@ -0,0 +1,47 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use crate::{
+    builtin::strings::str_as_bytes,
+    io::{Str, flush_stdout, write_stdout},
+};
+
+// Formatted printing is compiled to a sequence of calls to these functions,
+// one per piece of the format string, followed by `__builtin__fmt_end`.
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_str(s: *const Str) {
+    write_stdout(str_as_bytes(s));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_int(i: i64) {
+    write_stdout(i.to_string().as_bytes());
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_uint(u: u64) {
+    write_stdout(u.to_string().as_bytes());
+}
+
+/// A negative precision prints the float with as many digits as needed
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_float(f: f64, precision: i32) {
+    if precision < 0 {
+        write_stdout(f.to_string().as_bytes());
+    } else {
+        write_stdout(format!("{:.*}", precision as usize, f).as_bytes());
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_bool(b: bool) {
+    write_stdout(b.to_string().as_bytes());
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__fmt_end(newline: bool) {
+    if newline {
+        write_stdout(b"\n");
+    }
+    flush_stdout();
+}
@@ -1 +1,4 @@
 pub mod arrays;
+pub mod fmt;
+pub mod panic;
+pub mod strings;
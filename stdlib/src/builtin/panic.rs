@@ -0,0 +1,36 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::io::Write;
+
+use crate::{builtin::strings::str_as_bytes, io::Str};
+
+/// Exit code of a program that panicked
+pub const PANIC_EXIT_CODE: i32 = 101;
+
+/// Prints the message and where it happened to stderr, then exits the program.
+/// Exiting instead of unwinding behaves the same in the JIT and in compiled binaries.
+pub(crate) fn panic_with(msg: &str, location: &str) -> ! {
+    let _ = std::io::stdout().flush();
+    eprintln!("panicked at {}: {}", location, msg);
+    std::process::exit(PANIC_EXIT_CODE);
+}
+
+fn lossy(s: *const Str) -> String {
+    String::from_utf8_lossy(str_as_bytes(s)).into_owned()
+}
+
+/// Panics raised by the compiler, like failed overflow checks, `assert` and `unreachable`,
+/// and by `std::panic(msg)`. The location is passed by the compiler.
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__panic(msg: *const Str, location: *const Str) -> ! {
+    panic_with(&lossy(msg), &lossy(location))
+}
+
+/// Panics on an array index past the end of the array
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__panic_bounds(len: u64, index: u64, location: *const Str) -> ! {
+    panic_with(
+        &format!("index out of bounds: len {}, index {}", len, index),
+        &lossy(location),
+    )
+}
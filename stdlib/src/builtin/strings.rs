@@ -0,0 +1,88 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use core::slice;
+use std::ffi::{CStr, CString, c_char};
+
+use crate::io::Str;
+
+pub(crate) fn str_as_bytes<'a>(s: *const Str) -> &'a [u8] {
+    let str_struct = unsafe { &*s };
+    unsafe { slice::from_raw_parts(str_struct.str, str_struct.len as usize) }
+}
+
+/// Moves the bytes to the heap, returning a string pointing to them.
+/// Strings are never freed.
+pub(crate) fn leak_str(bytes: Vec<u8>) -> Str {
+    let bytes = bytes.leak();
+    Str {
+        len: bytes.len() as u64,
+        str: bytes.as_ptr(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_concat(a: *const Str, b: *const Str) -> Str {
+    leak_str([str_as_bytes(a), str_as_bytes(b)].concat())
+}
+
+/// Returns -1, 0 or 1 if `a` is less than, equal to or greater than `b`
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_cmp(a: *const Str, b: *const Str) -> i32 {
+    str_as_bytes(a).cmp(str_as_bytes(b)) as i32
+}
+
+/// Returns a view of the bytes from `start` to `end`, clamped to the length of the string
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_slice(s: *const Str, start: u64, end: u64) -> Str {
+    let str_struct = unsafe { &*s };
+    let end = end.min(str_struct.len);
+    let start = start.min(end);
+    Str {
+        len: end - start,
+        str: unsafe { str_struct.str.add(start as usize) },
+    }
+}
+
+/// Returns the index of the first occurrence of `sub`, or -1 if there is none
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_find(s: *const Str, sub: *const Str) -> i64 {
+    let (s, sub) = (str_as_bytes(s), str_as_bytes(sub));
+    if sub.is_empty() {
+        return 0;
+    }
+    s.windows(sub.len())
+        .position(|window| window == sub)
+        .map_or(-1, |index| index as i64)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_starts_with(s: *const Str, prefix: *const Str) -> bool {
+    str_as_bytes(s).starts_with(str_as_bytes(prefix))
+}
+
+/// Copies the string to a new null terminated string. Interior null bytes end the string early.
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__str_to_cstr(s: *const Str) -> *const c_char {
+    let bytes = str_as_bytes(s);
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    CString::new(&bytes[..len]).unwrap().into_raw()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__cstr_to_str(s: *const c_char) -> Str {
+    let cstr = unsafe { CStr::from_ptr(s) };
+    Str {
+        len: cstr.count_bytes() as u64,
+        str: s as *const u8,
+    }
+}
+
+/// Wraps the arguments of the program in strings, for a `main` taking them. The array of
+/// strings is never freed, and the strings point to the arguments themselves.
+#[unsafe(no_mangle)]
+pub extern "C" fn __builtin__args(argc: i32, argv: *const *const c_char) -> *const Str {
+    let args = (0..argc.max(0) as usize)
+        .map(|index| __builtin__cstr_to_str(unsafe { *argv.add(index) }))
+        .collect::<Vec<_>>();
+    args.leak().as_ptr()
+}
@@ -0,0 +1,45 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use crate::{builtin::strings::str_as_bytes, io::Str};
+
+// Fallible functions write their value to `out` and return 0 on success, or
+// an error code (the OS error, or -1 if there is none) on failure.
+
+fn str_to_path(s: *const Str) -> Result<&'static str, i32> {
+    std::str::from_utf8(str_as_bytes(s)).map_err(|_| -1)
+}
+
+fn io_err_code(err: std::io::Error) -> i32 {
+    err.raw_os_error().unwrap_or(-1)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__fs__write(path: *const Str, contents: *const Str, out: *mut u64) -> i32 {
+    let path = match str_to_path(path) {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+    let bytes = str_as_bytes(contents);
+    match std::fs::write(path, bytes) {
+        Ok(()) => {
+            unsafe { *out = bytes.len() as u64 };
+            0
+        }
+        Err(err) => io_err_code(err),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__fs__size(path: *const Str, out: *mut u64) -> i32 {
+    let path = match str_to_path(path) {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+    match std::fs::metadata(path) {
+        Ok(meta) => {
+            unsafe { *out = meta.len() };
+            0
+        }
+        Err(err) => io_err_code(err),
+    }
+}
@@ -1,6 +1,8 @@
 use core::slice;
 use std::io::Write;
 
+use crate::builtin::panic::panic_with;
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct Str {
@@ -8,29 +10,40 @@ pub struct Str {
     pub str: *const u8,
 }
 
+/// Writes to stdout, panicking through the runtime if that fails
+pub(crate) fn write_stdout(bytes: &[u8]) {
+    if let Err(err) = std::io::stdout().write_all(bytes) {
+        panic_with(&format!("failed printing to stdout: {}", err), "std::io");
+    }
+}
+
+pub(crate) fn flush_stdout() {
+    if let Err(err) = std::io::stdout().flush() {
+        panic_with(&format!("failed flushing stdout: {}", err), "std::io");
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn __std__io__print(s: *const Str) {
     let str_struct = unsafe { &*s };
     let string = unsafe { slice::from_raw_parts(str_struct.str, str_struct.len as usize) };
-    std::io::stdout().write_all(string).unwrap();
-    std::io::stdout().flush().unwrap();
+    write_stdout(string);
+    flush_stdout();
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn __std__io__println(s: *const Str) {
     let str_struct = unsafe { &*s };
     let string = unsafe { slice::from_raw_parts(str_struct.str, str_struct.len as usize) };
-    std::io::stdout().write_all(string).unwrap();
-    std::io::stdout().write(b"\n").unwrap();
+    write_stdout(string);
+    write_stdout(b"\n");
 }
 
-// Temporary funtion until format print is implemented
 #[unsafe(no_mangle)]
-pub extern "C" fn __std__io__printint(s: i32) {
-    println!("{}", s);
+pub extern "C" fn __std__io__printint(s: i64) {
+    write_stdout(format!("{}\n", s).as_bytes());
 }
 #[unsafe(no_mangle)]
-pub extern "C" fn __std__io__printflt(s: f32) -> i32 {
-    println!("{}", s);
-    return 0;
+pub extern "C" fn __std__io__printflt(s: f64) {
+    write_stdout(format!("{}\n", s).as_bytes());
 }
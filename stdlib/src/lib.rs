@@ -1,2 +1,4 @@
 pub mod builtin;
+pub mod fs;
 pub mod io;
+pub mod str;
@@ -0,0 +1,42 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use crate::{
+    builtin::strings::{leak_str, str_as_bytes},
+    io::Str,
+};
+
+// Parsing functions write the number to `out` and return 0 on success, or -1
+// if the string is not a valid number.
+
+fn parse<T: std::str::FromStr>(s: *const Str, out: *mut T) -> i32 {
+    let parsed = std::str::from_utf8(str_as_bytes(s))
+        .ok()
+        .and_then(|s| s.trim().parse::<T>().ok());
+    match parsed {
+        Some(value) => {
+            unsafe { *out = value };
+            0
+        }
+        None => -1,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__str__parse_int(s: *const Str, out: *mut i64) -> i32 {
+    parse(s, out)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__str__parse_float(s: *const Str, out: *mut f64) -> i32 {
+    parse(s, out)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__str__from_int(i: i64) -> Str {
+    leak_str(i.to_string().into_bytes())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __std__str__from_float(f: f64) -> Str {
+    leak_str(f.to_string().into_bytes())
+}
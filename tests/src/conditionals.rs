@@ -92,4 +92,31 @@ mod tests {
 
         assert_eq!(2, generate_result(contents).unwrap());
     }
+
+    #[test]
+    fn check_float_comparisons() {
+        let contents = r#"
+        func le(a f64, b f64) bool {
+            return a <= b
+        }
+
+        func main() u32 {
+            let u32! result = 0
+            if le(1.0, 2.0) {
+                result = result + 1
+            }
+            if le(2.0, 2.0) {
+                result = result + 10
+            }
+            if le(3.0, 2.0) {
+                result = result + 100
+            }
+            if 1.5 <= 2.5 {
+                result = result + 1000
+            }
+            return result
+        }"#;
+
+        assert_eq!(1011, generate_result(contents).unwrap());
+    }
 }
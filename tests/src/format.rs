@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use crate::generate_result;
+
+    #[test]
+    fn check_format_print() {
+        let contents = r#"
+        import std::io
+
+        struct Point { x i32, y f32 }
+
+        func main() u32 {
+            let u8 a = 200
+            let f64 b = 1.5
+            let Point p = { x 1, y 2.5 }
+            let string name = "points"
+            io::println("a = {}, b = {:.2}, {p} {{{name}}}", a, b)
+            io::print("{} ", a > 100)
+            io::println("done")
+            return 1
+        }"#;
+
+        assert_eq!(1, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_print_numbers() {
+        let contents = r#"
+        import std::io
+
+        func main() u32 {
+            let i32 a = 7
+            let f32 b = 0.5
+            io::printint(a)
+            io::printflt(b)
+            return 2
+        }"#;
+
+        assert_eq!(2, generate_result(contents).unwrap());
+    }
+}
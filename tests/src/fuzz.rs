@@ -0,0 +1,273 @@
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    use inkwell::context::Context;
+
+    use backend_llvm::CodeGen;
+    use diagnostics::ErrorCode;
+    use lexer::lexer::{Lexer, Token};
+    use parser::Parser;
+    use passes::r#impl::PassManager;
+
+    /// Valid programs whose tokens are mutated
+    const SEEDS: [&str; 4] = [
+        r#"
+        import std::io
+
+        struct Point { x i32, y f32 }
+
+        func main() u32 {
+            let Point p = { x 1, y 2.5 }
+            let string name = "points"
+            io::println("{p} {name} {}", p.x)
+            return name.len() -> u32
+        }"#,
+        r#"
+        func half(a u32) Result<u32, u32> {
+            if a > 10 {
+                return err(1)
+            }
+            return ok(a / 2)
+        }
+
+        func main() u32 {
+            let Result<u32, u32> r = half(4)
+            return r.unwrap_or(0)
+        }
+
+        func quarter(a u32) Result<u32, u32> {
+            let u32 h = half(a)?
+            return ok(h / 2)
+        }"#,
+        r#"
+        func main() u32 {
+            let u32[] a = [1, 2, 3]
+            let u32! sum = 0
+            loop range val, i = a {
+                sum += val
+            }
+            loop {
+                if sum > 10 {
+                    break
+                }
+                sum = sum + a[0]
+            }
+            return sum
+        }"#,
+        r#"
+        func main() u32 {
+            let f32 a = 1.5
+            let u8 b = a -> u8
+            return b -> u32
+        }"#,
+    ];
+
+    /// Where a program is rejected
+    #[derive(Debug, PartialEq)]
+    enum Stage {
+        Parser,
+        Passes,
+        Backend,
+    }
+
+    /// Programs with the stage that rejects them and the code of its first error
+    const CORPUS: [(&str, Stage, ErrorCode); 17] = [
+        (
+            "func main() u32 {
+                let u32 a =
+                return a
+            }",
+            Stage::Passes,
+            ErrorCode::NoValue,
+        ),
+        (
+            "func main() u32 {
+                let u32[] a = [1, , 3]
+                return a[0]
+            }",
+            Stage::Passes,
+            ErrorCode::NoValue,
+        ),
+        (
+            "func main() u32 {
+                let u64 a = 99999999999999999999999
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::ConstantOverflow,
+        ),
+        (
+            "func main() u32 {
+                break
+                return 0
+            }",
+            Stage::Parser,
+            ErrorCode::BreakOutsideLoop,
+        ),
+        (
+            "import std::io
+            func main() u32 {
+                io::printint()
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::WrongArgumentCount,
+        ),
+        (
+            "import std::io
+            func main() u32 {
+                io::printint(1, 2)
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::WrongArgumentCount,
+        ),
+        (
+            "import std::io
+            func main() u32 {
+                io::printint
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::Unsupported,
+        ),
+        (
+            "import std::foo
+            func main() u32 {
+                foo::bar()
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::UnknownModule,
+        ),
+        (
+            "func main() u32 {
+                let u32[] a = [1, 2]
+                return a.first()
+            }",
+            Stage::Passes,
+            ErrorCode::UnknownMethod,
+        ),
+        (
+            "func main() u32 {
+                let u32 a = [1]
+                return a
+            }",
+            Stage::Passes,
+            ErrorCode::MismatchedTypes,
+        ),
+        (
+            "func main() u32 {
+                return ok(1)
+            }",
+            Stage::Passes,
+            ErrorCode::MismatchedTypes,
+        ),
+        (
+            "func main() u32 {
+                let u32 a = > 2
+                return a
+            }",
+            Stage::Parser,
+            ErrorCode::UnexpectedToken,
+        ),
+        (
+            "func main() u32 {
+                let u32 a = 1
+                return a.x
+            }",
+            Stage::Passes,
+            ErrorCode::UnknownField,
+        ),
+        (
+            "func main() u32 {
+                let u32 a = {}
+                return a
+            }",
+            Stage::Parser,
+            ErrorCode::UnexpectedToken,
+        ),
+        (
+            "import std::io
+            func main() u32 {
+                io::println(\"{} {}\", 1)
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::InvalidFormat,
+        ),
+        (
+            "import std::io
+            func half(a u32) Result<u32, u32> {
+                return ok(a / 2)
+            }
+            func main() u32 {
+                io::println(\"{}\", half(2))
+                return 0
+            }",
+            Stage::Passes,
+            ErrorCode::InvalidFormat,
+        ),
+        (
+            "func half(a u32) Result<u32, u32> {
+                return ok(a / 2)
+            }
+            func main() u32 {
+                half(2)
+                return 0
+            }",
+            Stage::Backend,
+            ErrorCode::UnusedResult,
+        ),
+    ];
+
+    /// Generates code for the tokens without running it, or returns where they were
+    /// rejected
+    fn compile(tokens: Vec<Token>) -> Result<(), (Stage, ErrorCode)> {
+        let mut ast = Parser::new(tokens)
+            .parse()
+            .map_err(|err| (Stage::Parser, err.code()))?;
+        PassManager::new(&mut ast).invoke().map_err(|errors| {
+            let code = errors[0].code().unwrap_or(ErrorCode::Internal);
+            (Stage::Passes, code)
+        })?;
+        let context = Context::create();
+        let codegen = CodeGen::new(&context, ast, false).with_runtime_checks(true);
+        codegen
+            .codegen()
+            .map_err(|err| (Stage::Backend, err.code()))
+    }
+
+    #[test]
+    fn test_corpus_is_rejected() {
+        for (contents, stage, code) in CORPUS {
+            let tokens = Lexer::new(contents).tokenize();
+            let result = catch_unwind(AssertUnwindSafe(|| compile(tokens)));
+            assert_eq!(result.ok(), Some(Err((stage, code))), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn test_mutated_programs_do_not_panic() {
+        for contents in SEEDS {
+            let tokens = Lexer::new(contents).tokenize();
+            assert!(compile(tokens.clone()).is_ok(), "{}", contents);
+
+            let (eof, tokens) = tokens.split_last().unwrap();
+            for i in 0..tokens.len() {
+                let mut removed = tokens.to_vec();
+                removed.remove(i);
+                let mut repeated = tokens.to_vec();
+                repeated.insert(i, tokens[i].clone());
+                let truncated = tokens[..i].to_vec();
+
+                for mut mutated in [removed, repeated, truncated] {
+                    mutated.push(eof.clone());
+                    let result = catch_unwind(AssertUnwindSafe(|| compile(mutated)));
+                    assert!(result.is_ok(), "token {} of {}", i, contents);
+                }
+            }
+        }
+    }
+}
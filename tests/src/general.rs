@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::generate_result;
+    use backend_llvm::OptLevel;
+
+    use crate::{generate_optimized_result, generate_result, generate_result_with_args};
 
     #[test]
     fn check_main_func() {
@@ -107,4 +109,299 @@ mod tests {
         }"#;
         assert_eq!(4, generate_result(contents).unwrap());
     }
+
+    #[test]
+    fn check_unsigned_semantics() {
+        let contents = r#"
+        func main() u32 {
+            let u32 a = 3000000000
+            let u32 b = a / 1000000000
+            let u32 c = a % 7
+            if a > 1 {
+                return b + c
+            }
+            return 0
+        }"#;
+        assert_eq!(7, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_signed_widening() {
+        let contents = r#"
+        func main() i32 {
+            let i8 a = 0 - 5
+            let i32 b = a -> i32
+            return b + 10
+        }"#;
+        assert_eq!(5, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_overflow_methods() {
+        let contents = r#"
+        func main() u32 {
+            let u8 a = 250
+            let u8 b = a.saturating_add(10)
+            let u8 c = a.wrapping_add(10)
+            let Result<u8, i32> d = a.checked_add(10)
+            if d.is_err() {
+                if b == 255 {
+                    return c -> u32
+                }
+            }
+            return 0
+        }"#;
+        assert_eq!(4, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_passing_assert() {
+        let contents = r#"
+        func main() u32 {
+            let u32 a = 3
+            assert(a == 3, "a should be 3")
+            assert(a < 10)
+            if a > 10 {
+                unreachable()
+            }
+            return a
+        }"#;
+        assert_eq!(3, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_block_scoping() {
+        let contents = r#"
+        func main() u32 {
+            let u32 a = 1
+            if a == 1 {
+                let u32 a = 5
+                let u32 b = a
+            }
+            let u32[] arr = [7, 8]
+            loop range x, i = arr {
+                let u32 b = x
+            }
+            return a
+        }"#;
+        assert_eq!(1, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_mutable_params() {
+        let contents = r#"
+        func add(a u32!, b u32) u32 {
+            a = a + b
+            return a
+        }
+
+        func main() u32 {
+            return add(2, 3)
+        }"#;
+        assert_eq!(5, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_if_else_fallthrough() {
+        let contents = r#"
+        func main() u32 {
+            let u32! a = 1
+            if a == 2 {
+                a = 10
+            } else if a == 1 {
+                a = 20
+            } else {
+                return 0
+            }
+            return a + 1
+        }"#;
+        assert_eq!(21, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_compound_assignments() {
+        let contents = r#"
+        func main() u32 {
+            let u32! a = 3
+            let u32[] b = [1, 2]
+            a += 5
+            a *= b[1] + 1
+            a -= 4
+            a <<= 1
+            a |= 1
+            return a
+        }"#;
+        assert_eq!(41, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_optimization_levels() {
+        let contents = r#"
+        func fib(num u32) u32 {
+            if 2 > num {
+                return num
+            }
+            return fib(num - 1) + fib(num - 2)
+        }
+
+        func main() u32 {
+            let u32[] nums = [10, 15, 20]
+            let u32! sum = 0
+            loop range num, _i = nums {
+                sum += fib(num)
+            }
+            return sum
+        }"#;
+
+        for level in [
+            OptLevel::O0,
+            OptLevel::O1,
+            OptLevel::O2,
+            OptLevel::O3,
+            OptLevel::Os,
+        ] {
+            let result = generate_optimized_result(contents, level).unwrap();
+            assert_eq!(55 + 610 + 6765, result, "{}", level);
+        }
+    }
+    #[test]
+    fn check_declaration_order() {
+        let contents = r#"
+        func main() u32 {
+            let Line l = { start { x 1, y 2 }, end { x 3, y 4 } }
+            let u32 even = is_even(l.end.y)
+            return even * l.end.x + l.start.x
+        }
+
+        struct Line { start Point, end Point }
+
+        func is_even(n u32) u32 {
+            if n == 0 {
+                return 1
+            }
+            return is_odd(n - 1)
+        }
+
+        func is_odd(n u32) u32 {
+            if n == 0 {
+                return 0
+            }
+            return is_even(n - 1)
+        }
+
+        struct Point { x u32, y u32 }"#;
+
+        assert_eq!(4, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_aggregates_by_value() {
+        let contents = r#"
+        struct Point { x u32, y u32 }
+
+        func moved(p Point, by u32[2]) Point {
+            let Point! q = p
+            q.x = q.x + by[0]
+            return q
+        }
+
+        func squares() u32[3] {
+            let u32[] a = [1, 4, 9]
+            return a
+        }
+
+        func main() u32 {
+            let Point p = { x 1, y 2 }
+            let u32[] by = [3, 4]
+            let Point q = moved(p, by)
+            let u32[3] s = squares()
+            return q.x * 100 + p.x * 10 + s[2]
+        }"#;
+
+        assert_eq!(419, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_unsized_array_params() {
+        let contents = r#"
+        func sum(values u32[]) u32 {
+            let u32! total = 0
+            let u64! i = 0
+            loop i < values.len() {
+                total = total + values[i]
+                i = i + 1
+            }
+            return total
+        }
+
+        func main() u32 {
+            let u32[] a = [1, 2, 3]
+            return sum(a) * 10 + sum([4, 5])
+        }"#;
+
+        assert_eq!(69, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_extern_struct_return() {
+        let contents = r#"
+        struct Div { quot i32, rem i32 }
+
+        extern func div(num i32, den i32) Div
+
+        func main() i32 {
+            let Div d = div(32, 10)
+            return d.quot * 10 + d.rem
+        }"#;
+
+        assert_eq!(32, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_nested_aggregates() {
+        let contents = r#"
+        struct Cell { value u32 }
+
+        struct Row { cells Cell[2], total u32 }
+
+        func main() u32 {
+            let u32 a = 2
+            let Row first = { cells [{ value 1 }, { value a }], total a + 1 }
+            let Row[]! grid = [first, { cells [{ value 3 }, { value 4 }], total 7 }]
+            let u32 i = 1
+            grid[i].cells[a - 2].value = 5
+            grid[0].cells[1] = { value a * 10 }
+            let u32[] sums = [grid[0].total, grid[i].cells[0].value]
+            return grid[0].cells[1].value + sums[0] * 100 + sums[1] * 1000
+        }"#;
+
+        assert_eq!(5320, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_main_args() {
+        let contents = r#"
+        func count(words string[..]) u64 {
+            let u64! total = 0
+            let u64! i = 1
+            loop i < words.len() {
+                total = total + words[i].len()
+                i = i + 1
+            }
+            return total
+        }
+
+        func main(args string[..]) i32 {
+            if args.len() < 2 {
+                return 1
+            }
+            return (args.len() * 100 + count(args)) -> i32
+        }"#;
+
+        assert_eq!(1, generate_result_with_args(contents, &["prog"]).unwrap());
+        assert_eq!(
+            407,
+            generate_result_with_args(contents, &["prog", "abc", "de", "fg"]).unwrap()
+        );
+    }
 }
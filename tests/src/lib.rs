@@ -1,18 +1,39 @@
 use inkwell::context::Context;
 
-use backend_llvm::CodeGen;
+use backend_llvm::{CodeGen, OptLevel};
 use lexer::lexer::Lexer;
 use parser::Parser;
+use passes::r#impl::PassManager;
 
 mod conditionals;
+mod format;
+mod fuzz;
 mod general;
 mod loops;
+mod results;
+mod strings;
 
 pub fn generate_result(contents: &str) -> Option<i32> {
+    generate_optimized_result(contents, OptLevel::O0)
+}
+
+pub fn generate_optimized_result(contents: &str, opt_level: OptLevel) -> Option<i32> {
+    run_program(contents, opt_level, &[])
+}
+
+/// Runs the program with the arguments, which start with its name
+pub fn generate_result_with_args(contents: &str, args: &[&str]) -> Option<i32> {
+    let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    run_program(contents, OptLevel::O0, &args)
+}
+
+fn run_program(contents: &str, opt_level: OptLevel, args: &[String]) -> Option<i32> {
     let lexer = Lexer::new(&contents).tokenize();
-    let parser = Parser::new(lexer).parse().unwrap();
+    let mut parser = Parser::new(lexer).parse().unwrap();
+    PassManager::new(&mut parser).invoke().unwrap();
     let context = Context::create();
-    let codegen = CodeGen::new(&context, parser, true);
+    let codegen = CodeGen::new(&context, parser, true).with_opt_level(opt_level);
     codegen.codegen().unwrap();
-    return codegen.run_with_jit();
+    codegen.optimize().unwrap();
+    return codegen.run_with_jit(args).unwrap();
 }
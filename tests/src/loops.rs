@@ -62,4 +62,25 @@ mod tests {
 
         assert_eq!(120, generate_result(content).unwrap());
     }
+
+    #[test]
+    fn test_nested_for_loops() {
+        let content = r#"
+        func main() u32 {
+            let u32[] rows = [1, 2, 3]
+            let u32[] cols = [10, 20]
+            let u32! total = 0
+            loop range row, _i = rows {
+                loop range col, _j = cols {
+                    total += row * col
+                }
+                if row == 2 {
+                    break
+                }
+            }
+            return total
+        }"#;
+
+        assert_eq!(90, generate_result(content).unwrap());
+    }
 }
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use crate::generate_result;
+
+    const HALVES: &str = r#"
+        func half(a u32) Result<u32, u32> {
+            if a % 2 == 1 {
+                return err(a)
+            }
+            return ok(a / 2)
+        }
+
+        func quarter(a u32) Result<u32, u32> {
+            let u32 h = half(a)?
+            return ok(half(h)?)
+        }
+    "#;
+
+    #[test]
+    fn check_result_ok() {
+        let contents = HALVES.to_string()
+            + r#"
+        func main() u32 {
+            let Result<u32, u32> r = quarter(12)
+            return r.unwrap_or(0)
+        }"#;
+
+        assert_eq!(3, generate_result(&contents).unwrap());
+    }
+
+    #[test]
+    fn check_result_try_propagates_err() {
+        let contents = HALVES.to_string()
+            + r#"
+        func main() u32 {
+            let Result<u32, u32> r = quarter(6)
+            if r.is_err() {
+                return 1
+            }
+            return r.unwrap_or(0)
+        }"#;
+
+        assert_eq!(1, generate_result(&contents).unwrap());
+    }
+
+    #[test]
+    fn check_result_from_stdlib() {
+        let contents = r#"
+        import std::fs
+
+        func size() Result<u64, i32> {
+            let string path = "/nonexistent/compette/file"
+            let u64 n = fs::size(path)?
+            return ok(n)
+        }
+
+        func main() u32 {
+            let Result<u64, i32> r = size()
+            if r.is_ok() {
+                return 1
+            }
+            return 0
+        }"#;
+
+        assert_eq!(0, generate_result(contents).unwrap());
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::generate_result;
+
+    #[test]
+    fn check_string_concat() {
+        let contents = r#"
+        func main() u32 {
+            let string a = "foo"
+            let string b = a + "bar" + a
+            if b == "foobarfoo" {
+                return b.len() -> u32
+            }
+            return 0
+        }"#;
+
+        assert_eq!(9, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_string_ordering() {
+        let contents = r#"
+        func main() u32 {
+            let string a = "apple"
+            let string b = "banana"
+            if a < b {
+                if b >= "banana" {
+                    return 1
+                }
+            }
+            return 0
+        }"#;
+
+        assert_eq!(1, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_string_methods() {
+        let contents = r#"
+        func main() u32 {
+            let string s = "hello world"
+            let string w = s.slice(6, 11)
+            if w != "world" {
+                return 1
+            }
+            if s.starts_with("hello") {
+                return s.find("o w") -> u32
+            }
+            return 2
+        }"#;
+
+        assert_eq!(4, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_string_parse_and_format() {
+        let contents = r#"
+        import std::str
+
+        func parse() Result<i64, i32> {
+            let i64 n = str::parse_int(" 42")?
+            return ok(n + 1)
+        }
+
+        func main() u32 {
+            let Result<i64, i32> r = parse()
+            let string s = str::from_int(r.unwrap_or(0))
+            if s == "43" {
+                return 1
+            }
+            return 0
+        }"#;
+
+        assert_eq!(1, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_string_cstring_conversion() {
+        let contents = r#"
+        func main() u32 {
+            let string s = "compette"
+            let cstring c = s -> cstring
+            let string t = c -> string
+            if t == s {
+                return t.len() -> u32
+            }
+            return 0
+        }"#;
+
+        assert_eq!(8, generate_result(contents).unwrap());
+    }
+
+    #[test]
+    fn check_cstring_variables() {
+        let contents = r#"
+        func main() u32 {
+            let string s = "hello"
+            let cstring c = s -> cstring
+            let cstring! d = "hi"
+            let string before = d -> string
+            d = c
+            let string after = d -> string
+            return (before.len() * 10 + after.len()) -> u32
+        }"#;
+
+        assert_eq!(25, generate_result(contents).unwrap());
+    }
+}